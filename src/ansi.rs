@@ -0,0 +1,158 @@
+use std::{iter::Peekable, ops::Range, str::Chars};
+
+use crate::theme;
+
+/// A line of output with its ANSI escape sequences removed and the foreground colors they set
+/// mapped to theme colors
+#[derive(Debug, Default, Clone)]
+pub(crate) struct StyledLine {
+    pub(crate) text: String,
+    /// Theme color and char range of each colored span
+    pub(crate) colors: Vec<(usize, Range<usize>)>,
+}
+
+impl StyledLine {
+    pub(crate) fn parse(line: &str) -> Self {
+        let mut styled_line = Self::default();
+        let mut color = None;
+        let mut span_start = 0;
+        let mut length = 0;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\u{1b}' {
+                styled_line.text.push(c);
+                length += 1;
+                continue;
+            }
+            let Some(parameters) = skip_escape_sequence(&mut chars) else {
+                continue;
+            };
+            let new_color = sgr_color(&parameters, color);
+            if new_color != color {
+                if let Some(color) = color.filter(|_| span_start < length) {
+                    styled_line.colors.push((color, span_start..length));
+                }
+                color = new_color;
+                span_start = length;
+            }
+        }
+        if let Some(color) = color.filter(|_| span_start < length) {
+            styled_line.colors.push((color, span_start..length));
+        }
+        styled_line
+    }
+
+    /// Replaces tabs with spaces up to the next tab stop, moving the colored spans along
+    pub(crate) fn expand_tabs(&self, tab_width: usize) -> Self {
+        if !self.text.contains('\t') {
+            return self.clone();
+        }
+        let mut text = String::new();
+        // Column of each char in the expanded text, and of the end of the line
+        let mut columns = Vec::new();
+        let mut column = 0;
+        for c in self.text.chars() {
+            columns.push(column);
+            if c == '\t' {
+                let spaces = tab_width - column % tab_width;
+                text.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            } else {
+                text.push(c);
+                column += 1;
+            }
+        }
+        columns.push(column);
+        let colors = self
+            .colors
+            .iter()
+            .map(|(color, range)| (*color, columns[range.start]..columns[range.end]))
+            .collect();
+        Self { text, colors }
+    }
+}
+
+/// Removes ANSI escape sequences from a line
+pub(crate) fn strip(line: &str) -> String {
+    if line.contains('\u{1b}') {
+        StyledLine::parse(line).text
+    } else {
+        line.to_owned()
+    }
+}
+
+/// Consumes an escape sequence following an escape character, returning the parameters of SGR
+/// sequences, e.g. `1;31` of `\x1b[1;31m`
+fn skip_escape_sequence(chars: &mut Peekable<Chars>) -> Option<String> {
+    match chars.next()? {
+        '[' => {
+            let mut parameters = String::new();
+            for c in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&c) {
+                    return (c == 'm').then_some(parameters);
+                }
+                parameters.push(c);
+            }
+            None
+        }
+        // Operating system commands end with a bell or a string terminator
+        ']' => {
+            while let Some(c) = chars.next() {
+                if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                    break;
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// The foreground color after applying SGR parameters. Colors without a matching theme color
+/// are shown uncolored.
+fn sgr_color(parameters: &str, mut color: Option<usize>) -> Option<usize> {
+    let theme = theme::current();
+    let mut codes = parameters
+        .split(';')
+        .map(|code| code.parse::<u8>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        color = match code {
+            0 | 39 => None,
+            30..=37 => basic_color(code - 30, &theme),
+            90..=97 => basic_color(code - 90, &theme),
+            38 => match codes.next() {
+                Some(5) => codes
+                    .next()
+                    .filter(|index| *index < 16)
+                    .and_then(|index| basic_color(index % 8, &theme)),
+                Some(2) => {
+                    codes.by_ref().take(3).for_each(drop);
+                    None
+                }
+                _ => None,
+            },
+            // Extended background colors, their arguments aren't codes of their own
+            48 => {
+                match codes.next() {
+                    Some(5) => drop(codes.next()),
+                    Some(2) => codes.by_ref().take(3).for_each(drop),
+                    _ => (),
+                }
+                color
+            }
+            _ => color,
+        };
+    }
+    color
+}
+
+fn basic_color(index: u8, theme: &theme::Theme) -> Option<usize> {
+    match index {
+        1 => Some(theme.fail),
+        2 => Some(theme.pass),
+        3 => Some(theme.skip),
+        4 | 5 => Some(theme.emphasis),
+        6 => Some(theme.running),
+        _ => None,
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Result line of a benchmark, e.g.
+/// `BenchmarkFoo-8  1000000  1234 ns/op  64 B/op  2 allocs/op`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Benchmark {
+    /// Benchmark name including the GOMAXPROCS suffix, e.g. `BenchmarkFoo-8`
+    pub(crate) name: String,
+    pub(crate) iterations: u64,
+    pub(crate) ns_per_op: f64,
+    /// Only reported with `-benchmem` or `b.ReportAllocs()`
+    pub(crate) bytes_per_op: Option<f64>,
+    /// Only reported with `-benchmem` or `b.ReportAllocs()`
+    pub(crate) allocs_per_op: Option<f64>,
+}
+
+impl Benchmark {
+    /// Parses a benchmark result line, returning `None` for any other output
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let name = fields.next().filter(|name| name.starts_with("Benchmark"))?;
+        let iterations = fields.next()?.parse().ok()?;
+        let mut ns_per_op = None;
+        let mut bytes_per_op = None;
+        let mut allocs_per_op = None;
+        while let (Some(value), Some(unit)) = (fields.next(), fields.next()) {
+            let Ok(value) = value.parse::<f64>() else {
+                break;
+            };
+            match unit {
+                "ns/op" => ns_per_op = Some(value),
+                "B/op" => bytes_per_op = Some(value),
+                "allocs/op" => allocs_per_op = Some(value),
+                _ => (),
+            }
+        }
+        Some(Self {
+            name: name.to_owned(),
+            iterations,
+            ns_per_op: ns_per_op?,
+            bytes_per_op,
+            allocs_per_op,
+        })
+    }
+}
@@ -0,0 +1,205 @@
+use std::cmp::Ordering;
+
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{benchmark::Benchmark, keymap::ScreenAction, theme, viewport::Viewport, Package};
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+}
+
+/// Keys of the benchmarks screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum BenchmarksAction {
+    Exit,
+    Down,
+    Up,
+    SortByName,
+    SortByNsPerOp,
+    SortByBytesPerOp,
+    SortByAllocsPerOp,
+}
+
+impl ScreenAction for BenchmarksAction {
+    const SCREEN: &'static str = "Benchmarks";
+
+    fn description(&self) -> &'static str {
+        match self {
+            BenchmarksAction::Exit => "back to the tests",
+            BenchmarksAction::Down => "select the next benchmark",
+            BenchmarksAction::Up => "select the previous benchmark",
+            BenchmarksAction::SortByName => "sort by name, again to reverse",
+            BenchmarksAction::SortByNsPerOp => "sort by ns/op, again to reverse",
+            BenchmarksAction::SortByBytesPerOp => "sort by B/op, again to reverse",
+            BenchmarksAction::SortByAllocsPerOp => "sort by allocs/op, again to reverse",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            BenchmarksAction::Exit => &["Esc"],
+            BenchmarksAction::Down => &["Down", "j"],
+            BenchmarksAction::Up => &["Up", "k"],
+            BenchmarksAction::SortByName => &["1"],
+            BenchmarksAction::SortByNsPerOp => &["2"],
+            BenchmarksAction::SortByBytesPerOp => &["3"],
+            BenchmarksAction::SortByAllocsPerOp => &["4"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum SortKey {
+    #[default]
+    Name,
+    NsPerOp,
+    BytesPerOp,
+    AllocsPerOp,
+}
+
+impl SortKey {
+    fn compare(&self, a: &(&str, &Benchmark), b: &(&str, &Benchmark)) -> Ordering {
+        let (a_package, a) = a;
+        let (b_package, b) = b;
+        match self {
+            SortKey::Name => a_package.cmp(b_package).then_with(|| a.name.cmp(&b.name)),
+            SortKey::NsPerOp => a.ns_per_op.total_cmp(&b.ns_per_op),
+            SortKey::BytesPerOp => compare_metric(a.bytes_per_op, b.bytes_per_op),
+            SortKey::AllocsPerOp => compare_metric(a.allocs_per_op, b.allocs_per_op),
+        }
+    }
+}
+
+/// Lists the benchmark results of all packages, sortable by name and by each metric
+#[derive(Debug, Default)]
+pub(crate) struct BenchmarksScreen {
+    viewport: Viewport,
+    sort_key: SortKey,
+    is_descending: bool,
+}
+
+impl BenchmarksScreen {
+    pub(crate) fn update(&mut self, event: Event, packages: &[Package]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let sort_key = match BenchmarksAction::from_key(&key)? {
+            BenchmarksAction::Exit => return Some(UpdateCommand::ExitScreen),
+            BenchmarksAction::Down => {
+                let benchmark_count = packages
+                    .iter()
+                    .map(|package| package.benchmarks.len())
+                    .sum::<usize>();
+                self.viewport.select_next(1, benchmark_count);
+                return Some(UpdateCommand::Render);
+            }
+            BenchmarksAction::Up => {
+                self.viewport.select_previous(1);
+                return Some(UpdateCommand::Render);
+            }
+            BenchmarksAction::SortByName => SortKey::Name,
+            BenchmarksAction::SortByNsPerOp => SortKey::NsPerOp,
+            BenchmarksAction::SortByBytesPerOp => SortKey::BytesPerOp,
+            BenchmarksAction::SortByAllocsPerOp => SortKey::AllocsPerOp,
+        };
+        // Selecting the current sort key again flips the order
+        if self.sort_key == sort_key {
+            self.is_descending = !self.is_descending;
+        } else {
+            self.sort_key = sort_key;
+            self.is_descending = false;
+        }
+        Some(UpdateCommand::Render)
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize, packages: &[Package]) {
+        let theme = theme::current();
+        let mut benchmarks = packages
+            .iter()
+            .flat_map(|package| {
+                package
+                    .benchmarks
+                    .iter()
+                    .map(|benchmark| (package.name.as_str(), benchmark))
+            })
+            .collect::<Vec<_>>();
+        benchmarks.sort_by(|a, b| {
+            let ordering = self.sort_key.compare(a, b);
+            if self.is_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(benchmarks.len());
+
+        let sortable_title = |sort_key: SortKey, title: &str| {
+            if sort_key == self.sort_key {
+                format!("{} {}", title, if self.is_descending { '▼' } else { '▲' })
+            } else {
+                title.to_owned()
+            }
+        };
+        let header = vec![
+            "package".to_owned(),
+            sortable_title(SortKey::Name, "benchmark"),
+            "iterations".to_owned(),
+            sortable_title(SortKey::NsPerOp, "ns/op"),
+            sortable_title(SortKey::BytesPerOp, "B/op"),
+            sortable_title(SortKey::AllocsPerOp, "allocs/op"),
+        ];
+
+        let table = benchmarks
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(header),
+                |acc, (i, (package, benchmark))| {
+                    let row = vec![
+                        Text::new(*package),
+                        Text::new(&benchmark.name),
+                        Text::new(benchmark.iterations.to_string()),
+                        Text::new(format_metric(Some(benchmark.ns_per_op))),
+                        Text::new(format_metric(benchmark.bytes_per_op)),
+                        Text::new(format_metric(benchmark.allocs_per_op)),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let bottom_text = Text::new(format!(
+            "{} benchmarks — sort by [1] name, [2] ns/op, [3] B/op, [4] allocs/op, [Esc] back",
+            benchmarks.len()
+        ))
+        .color_range(theme.accent, ..benchmarks.len().to_string().len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+/// Benchmarks without the metric sort last
+fn compare_metric(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn format_metric(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| value.to_string())
+}
@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use zellij_tile::prelude::*;
+
+use crate::runner;
+
+/// Context value of the commands copying text to the clipboard
+pub(crate) const CLIPBOARD_CONTEXT: &str = "clipboard";
+
+/// Escape sequence asking the terminal to put the text on the system clipboard, passed through
+/// zellij when its output gets rendered
+pub(crate) fn osc52(text: &str) -> String {
+    format!("\u{1b}]52;c;{}\u{7}", STANDARD.encode(text))
+}
+
+/// Pipes the text into a user configured command, e.g. `wl-copy` or `pbcopy`, for terminals
+/// without OSC 52 support
+pub(crate) fn run_clipboard_command(command: &str, text: &str) {
+    let script = format!("printf '%s' \"$1\" | {}", command);
+    run_command(
+        &["sh", "-c", &script, "sh", text],
+        BTreeMap::from([(runner::CONTEXT_KEY.to_owned(), CLIPBOARD_CONTEXT.to_owned())]),
+    );
+}
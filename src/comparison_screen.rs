@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    keymap::ScreenAction, name::Name, tests_screen::format_elapsed, theme, viewport::Viewport,
+    Package, TestCase, TestResult,
+};
+
+/// Duration changes below this ratio between two runs aren't reported
+const DURATION_CHANGE_RATIO: f64 = 1.5;
+/// Duration changes below this many seconds aren't reported, no matter the ratio
+const MIN_DURATION_CHANGE: f64 = 0.1;
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+}
+
+/// Keys of the comparison screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum ComparisonAction {
+    Exit,
+    Down,
+    Up,
+}
+
+impl ScreenAction for ComparisonAction {
+    const SCREEN: &'static str = "Comparison";
+
+    fn description(&self) -> &'static str {
+        match self {
+            ComparisonAction::Exit => "back to the history",
+            ComparisonAction::Down => "select the next change",
+            ComparisonAction::Up => "select the previous change",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            ComparisonAction::Exit => &["Esc"],
+            ComparisonAction::Down => &["Down", "j"],
+            ComparisonAction::Up => &["Up", "k"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ChangeKind {
+    NewlyFailed,
+    NewlyPassed,
+    Slower,
+    Faster,
+}
+
+impl ChangeKind {
+    fn label(&self) -> &'static str {
+        match self {
+            ChangeKind::NewlyFailed => "newly failed",
+            ChangeKind::NewlyPassed => "newly passed",
+            ChangeKind::Slower => "slower",
+            ChangeKind::Faster => "faster",
+        }
+    }
+
+    fn color(&self) -> usize {
+        let theme = theme::current();
+        match self {
+            ChangeKind::NewlyFailed | ChangeKind::Slower => theme.fail,
+            ChangeKind::NewlyPassed | ChangeKind::Faster => theme.pass,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Change {
+    kind: ChangeKind,
+    package: Name,
+    test: Name,
+    before: String,
+    after: String,
+}
+
+/// Lists the tests that newly failed, newly passed or changed duration significantly between
+/// a run and the one before it
+#[derive(Debug)]
+pub(crate) struct ComparisonScreen {
+    title: String,
+    changes: Vec<Change>,
+    viewport: Viewport,
+}
+
+impl ComparisonScreen {
+    pub(crate) fn new(title: String, previous: &[Package], current: &[Package]) -> Self {
+        let previous_tests = previous
+            .iter()
+            .flat_map(|package| {
+                TestCase::flatten(&package.tests)
+                    .into_iter()
+                    .map(|test| ((package.name.as_str(), test.name.as_str()), test))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let mut changes = current
+            .iter()
+            .flat_map(|package| {
+                TestCase::flatten(&package.tests)
+                    .into_iter()
+                    .map(move |test| (package, test))
+            })
+            .filter_map(|(package, test)| {
+                let previous_test =
+                    previous_tests.get(&(package.name.as_str(), test.name.as_str()))?;
+                compare(previous_test, test).map(|(kind, before, after)| Change {
+                    kind,
+                    package: package.name.clone(),
+                    test: test.name.clone(),
+                    before,
+                    after,
+                })
+            })
+            .collect::<Vec<_>>();
+        changes.sort_by_key(|change| change.kind);
+        Self {
+            title,
+            changes,
+            viewport: Viewport::default(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, event: Event) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match ComparisonAction::from_key(&key)? {
+            ComparisonAction::Exit => Some(UpdateCommand::ExitScreen),
+            ComparisonAction::Down => {
+                self.viewport.select_next(1, self.changes.len());
+                Some(UpdateCommand::Render)
+            }
+            ComparisonAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+        }
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize) {
+        let theme = theme::current();
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(self.changes.len());
+
+        let table = self
+            .changes
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["change", "package", "test", "before", "after"]),
+                |acc, (i, change)| {
+                    let row = vec![
+                        Text::new(change.kind.label()).color_range(change.kind.color(), ..),
+                        Text::new(&change.package),
+                        Text::new(&change.test),
+                        Text::new(&change.before),
+                        Text::new(&change.after),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let bottom_text = Text::new(format!(
+            "{} — {} changes — [Esc] back",
+            self.title,
+            self.changes.len()
+        ));
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+fn compare(previous: &TestCase, current: &TestCase) -> Option<(ChangeKind, String, String)> {
+    let is_failed = |test: &TestCase| test.result.is_some_and(|result| result.is_failure());
+    let result_name = |test: &TestCase| {
+        test.result
+            .map_or_else(|| "-".to_owned(), |result| result.as_ref().to_owned())
+    };
+    if is_failed(current) && !is_failed(previous) {
+        return Some((
+            ChangeKind::NewlyFailed,
+            result_name(previous),
+            result_name(current),
+        ));
+    }
+    if current.result == Some(TestResult::Pass) && is_failed(previous) {
+        return Some((
+            ChangeKind::NewlyPassed,
+            result_name(previous),
+            result_name(current),
+        ));
+    }
+    let (before, after) = previous.elapsed.zip(current.elapsed)?;
+    if (after - before).abs() < MIN_DURATION_CHANGE {
+        return None;
+    }
+    let kind = if after >= before * DURATION_CHANGE_RATIO {
+        ChangeKind::Slower
+    } else if before >= after * DURATION_CHANGE_RATIO {
+        ChangeKind::Faster
+    } else {
+        return None;
+    };
+    Some((kind, format_elapsed(before), format_elapsed(after)))
+}
@@ -0,0 +1,247 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{
+    log_limit::LogLimits,
+    plain_output::InputFormat,
+    search::CaseSensitivity,
+    tests_screen::{ColumnSpec, ResultFilters},
+    theme,
+};
+
+/// `module_prefix` value detecting the prefix with `go list -m`
+pub(crate) const AUTO_MODULE_PREFIX: &str = "auto";
+
+/// Plugin settings read from the configuration passed to `load()`
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) watch: bool,
+    /// Where JUnit XML reports get written, relative paths are resolved against zellij's cwd
+    pub(crate) junit_path: PathBuf,
+    /// Where JSON reports get written, relative paths are resolved against zellij's cwd
+    pub(crate) json_path: PathBuf,
+    /// Whether results are saved to the plugin's data directory and restored on load
+    pub(crate) persist_state: bool,
+    /// How many previous runs are kept in the history
+    pub(crate) history_size: usize,
+    /// Colors the coverage column when set, e.g. `coverage_thresholds "50, 80"`
+    pub(crate) coverage_thresholds: Option<CoverageThresholds>,
+    /// Initial case sensitivity of searches, `sensitive`, `insensitive` or `smart`
+    pub(crate) case_sensitivity: CaseSensitivity,
+    /// Command the copied text gets piped into instead of relying on OSC 52, e.g. `wl-copy`
+    pub(crate) clipboard_command: Option<String>,
+    /// How piped output is parsed, `auto`, `json`, `plain` or `gotestsum`
+    pub(crate) input_format: InputFormat,
+    pub(crate) log_limits: LogLimits,
+    /// Results the tests screen starts filtered by, e.g. `default_filter "fail"`. They take
+    /// precedence over the restored filters.
+    pub(crate) default_filters: Option<ResultFilters>,
+    /// Whether the logs of the first failure open once a run completed
+    pub(crate) auto_open_first_failure: bool,
+    /// Whether the plugin's pane title shows the outcome of the last run, e.g. `✗ 3 failed`
+    pub(crate) pane_title_outcome: bool,
+    /// Whether moving down from the last row of the tests screen selects the first one, and up
+    /// from the first row the last one
+    pub(crate) wrap_navigation: bool,
+    /// Columns of the tests table in their order, e.g. `columns "result, package, elapsed"`.
+    /// `output-lines` only shows while log line counts are toggled on.
+    pub(crate) columns: ColumnSpec,
+    /// Name of the pipe the run's status is published to, e.g. `3F/120P running`
+    pub(crate) status_pipe: Option<String>,
+    /// Plugin the status is sent to, all plugins get it when unset
+    pub(crate) status_plugin: Option<String>,
+    /// Extra flags of the `go test` commands the plugin spawns, e.g. `-race -count=1`
+    pub(crate) go_test_flags: String,
+    /// Import path stripped from the package names in the tests table, e.g.
+    /// `github.com/acme/project`. `auto` detects it with `go list -m`.
+    pub(crate) module_prefix: Option<String>,
+    /// Number of tests listed in the slowest tests screen, e.g. `slowest_count "50"`
+    pub(crate) slowest_count: usize,
+    /// Seconds a test has to take to be shown by the slow filter, e.g. `slow_threshold "500ms"`
+    pub(crate) slow_threshold: f64,
+    /// Where logs get saved, `{package}` and `{test}` are replaced by the names of the log's
+    /// package and test. Relative paths are resolved against zellij's cwd.
+    pub(crate) log_path: String,
+    /// Profile written by `go test -coverprofile` shown in the coverage tab, relative paths are
+    /// resolved against zellij's cwd
+    pub(crate) coverage_profile: PathBuf,
+}
+
+/// Coverage below `low` percent is shown red, below `high` yellow and green otherwise
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CoverageThresholds {
+    pub(crate) low: f32,
+    pub(crate) high: f32,
+}
+
+impl CoverageThresholds {
+    fn parse(value: &str) -> Option<Self> {
+        let (low, high) = value.split_once(',')?;
+        Some(Self {
+            low: low.trim().parse().ok()?,
+            high: high.trim().parse().ok()?,
+        })
+    }
+
+    /// Color index of a coverage percentage
+    pub(crate) fn color(&self, coverage: f32) -> usize {
+        let theme = theme::current();
+        if coverage < self.low {
+            theme.fail
+        } else if coverage < self.high {
+            theme.skip
+        } else {
+            theme.pass
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            watch: false,
+            junit_path: PathBuf::from("junit.xml"),
+            json_path: PathBuf::from("go-tests.json"),
+            persist_state: true,
+            history_size: 10,
+            coverage_thresholds: None,
+            case_sensitivity: CaseSensitivity::default(),
+            clipboard_command: None,
+            input_format: InputFormat::default(),
+            log_limits: LogLimits::default(),
+            default_filters: None,
+            auto_open_first_failure: false,
+            pane_title_outcome: false,
+            wrap_navigation: false,
+            columns: ColumnSpec::default(),
+            status_pipe: None,
+            status_plugin: None,
+            go_test_flags: String::new(),
+            module_prefix: None,
+            slowest_count: 20,
+            slow_threshold: 1.0,
+            log_path: "{test}.log".to_owned(),
+            coverage_profile: PathBuf::from("coverage.out"),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the settings, along with the errors of the invalid ones that fell back to defaults
+    pub(crate) fn new(configuration: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let default = Self::default();
+        let (columns, errors) = configuration
+            .get("columns")
+            .map_or((default.columns, Vec::new()), |names| {
+                ColumnSpec::from_names(names)
+            });
+        let config = Self {
+            watch: configuration
+                .get("watch")
+                .map_or(default.watch, |watch| watch == "true"),
+            junit_path: configuration
+                .get("junit_path")
+                .map_or(default.junit_path, PathBuf::from),
+            json_path: configuration
+                .get("json_path")
+                .map_or(default.json_path, PathBuf::from),
+            persist_state: configuration
+                .get("persist_state")
+                .map_or(default.persist_state, |persist_state| {
+                    persist_state == "true"
+                }),
+            history_size: configuration
+                .get("history_size")
+                .and_then(|history_size| history_size.parse().ok())
+                .unwrap_or(default.history_size),
+            coverage_thresholds: configuration
+                .get("coverage_thresholds")
+                .and_then(|thresholds| CoverageThresholds::parse(thresholds)),
+            case_sensitivity: configuration
+                .get("search_case")
+                .and_then(|name| CaseSensitivity::from_name(name))
+                .unwrap_or(default.case_sensitivity),
+            clipboard_command: configuration.get("clipboard_command").cloned(),
+            input_format: configuration
+                .get("format")
+                .and_then(|name| InputFormat::from_name(name))
+                .unwrap_or(default.input_format),
+            log_limits: LogLimits {
+                per_log: configuration
+                    .get("max_log_lines")
+                    .and_then(|max_log_lines| max_log_lines.parse().ok())
+                    .unwrap_or(default.log_limits.per_log),
+                total: configuration
+                    .get("max_total_log_lines")
+                    .and_then(|max_total_log_lines| max_total_log_lines.parse().ok())
+                    .unwrap_or(default.log_limits.total),
+            },
+            default_filters: configuration
+                .get("default_filter")
+                .and_then(|names| ResultFilters::from_names(names)),
+            auto_open_first_failure: configuration
+                .get("auto_open_first_failure")
+                .map_or(default.auto_open_first_failure, |value| value == "true"),
+            pane_title_outcome: configuration
+                .get("pane_title_outcome")
+                .map_or(default.pane_title_outcome, |value| value == "true"),
+            wrap_navigation: configuration
+                .get("wrap_navigation")
+                .map_or(default.wrap_navigation, |value| value == "true"),
+            columns,
+            status_pipe: configuration.get("status_pipe").cloned(),
+            status_plugin: configuration.get("status_plugin").cloned(),
+            go_test_flags: configuration
+                .get("go_test_flags")
+                .cloned()
+                .unwrap_or(default.go_test_flags),
+            module_prefix: configuration
+                .get("module_prefix")
+                .map(|prefix| prefix.trim().trim_end_matches('/').to_owned())
+                .filter(|prefix| !prefix.is_empty()),
+            slowest_count: configuration
+                .get("slowest_count")
+                .and_then(|slowest_count| slowest_count.parse().ok())
+                .unwrap_or(default.slowest_count),
+            slow_threshold: configuration
+                .get("slow_threshold")
+                .and_then(|threshold| parse_seconds(threshold))
+                .filter(|threshold| *threshold > 0.0)
+                .unwrap_or(default.slow_threshold),
+            log_path: configuration
+                .get("log_path")
+                .cloned()
+                .unwrap_or(default.log_path),
+            coverage_profile: configuration
+                .get("coverage_profile")
+                .map_or(default.coverage_profile, PathBuf::from),
+        };
+        (config, errors)
+    }
+}
+
+/// Parses a duration in seconds, e.g. `2`, `1.5s` or `500ms`
+fn parse_seconds(duration: &str) -> Option<f64> {
+    let duration = duration.trim();
+    match duration.strip_suffix("ms") {
+        Some(millis) => millis
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|millis| millis / 1000.0),
+        None => duration
+            .strip_suffix('s')
+            .unwrap_or(duration)
+            .trim()
+            .parse()
+            .ok(),
+    }
+}
+
+/// Maps a path to the plugin's view of the host filesystem, which is mounted at `/host`
+pub(crate) fn host_path(path: &std::path::Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        PathBuf::from("/host").join(path)
+    }
+}
@@ -0,0 +1,101 @@
+use zellij_tile::prelude::*;
+
+const MAX_COUNT: usize = 99_999;
+
+/// A key read through the count prefix
+#[derive(Debug)]
+pub(crate) enum Input {
+    /// The key extended the pending prefix
+    Pending,
+    /// `gg`, jumps to the first row or to the counted one
+    First(Option<usize>),
+    /// `G`, jumps to the last row or to the counted one
+    Last(Option<usize>),
+    /// Any other key with the count typed before it
+    Key {
+        key: KeyWithModifier,
+        count: Option<usize>,
+        /// Whether a prefix was shown, which the key cancels
+        was_pending: bool,
+    },
+}
+
+/// Vim style count prefixes typed before a motion, e.g. `5j` or `10G`. Digits bound to an action
+/// run it at once, even while a count is pending, so only the unbound ones are counted. `g` always
+/// starts `gg`, while `G` only jumps when it isn't bound to an action of the screen.
+#[derive(Debug, Default)]
+pub(crate) struct CountPrefix {
+    count: Option<usize>,
+    /// Whether a `g` waits for the second `g` of `gg`
+    is_g_pending: bool,
+}
+
+impl CountPrefix {
+    pub(crate) fn feed(
+        &mut self,
+        key: KeyWithModifier,
+        is_bound: impl Fn(&KeyWithModifier) -> bool,
+    ) -> Input {
+        let char = match key.bare_key {
+            BareKey::Char(c) if key.key_modifiers.is_empty() => Some(c),
+            _ => None,
+        };
+        match char {
+            // A leading 0 is left to its action, e.g. scrolling to the start of the lines
+            Some(c @ '0'..='9') if !is_bound(&key) && (self.count.is_some() || c != '0') => {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                self.count = Some(
+                    self.count
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(digit)
+                        .min(MAX_COUNT),
+                );
+                self.is_g_pending = false;
+                Input::Pending
+            }
+            Some('g') => {
+                if self.is_g_pending {
+                    let count = self.count.take();
+                    self.reset();
+                    Input::First(count)
+                } else {
+                    self.is_g_pending = true;
+                    Input::Pending
+                }
+            }
+            Some('G') if !is_bound(&key) => {
+                let count = self.count.take();
+                self.reset();
+                Input::Last(count)
+            }
+            _ => {
+                let was_pending = self.pending().is_some();
+                let count = self.count.take();
+                self.reset();
+                Input::Key {
+                    key,
+                    count,
+                    was_pending,
+                }
+            }
+        }
+    }
+
+    /// The prefix typed so far, e.g. `12` or `g`, shown until the motion is typed
+    pub(crate) fn pending(&self) -> Option<String> {
+        match (self.count, self.is_g_pending) {
+            (None, false) => None,
+            (count, is_g_pending) => Some(format!(
+                "{}{}",
+                count.map(|count| count.to_string()).unwrap_or_default(),
+                if is_g_pending { "g" } else { "" }
+            )),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.is_g_pending = false;
+    }
+}
@@ -0,0 +1,184 @@
+use std::{collections::BTreeMap, io::ErrorKind, path::Path};
+
+use crate::config::host_path;
+
+/// A `line.column` position in a source file
+type Position = (usize, usize);
+
+/// A line of the profile: a block of statements and how many times they ran
+struct Block<'a> {
+    path: &'a str,
+    start: Position,
+    end: Position,
+    statements: usize,
+    count: usize,
+}
+
+/// A range of lines, both ends included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LineRange {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// Coverage of a source file, summed over the blocks of the profile
+#[derive(Debug, Clone)]
+pub(crate) struct FileCoverage {
+    /// Import path of the file, e.g. `example.com/api/handler.go`
+    pub(crate) path: String,
+    pub(crate) statements: usize,
+    pub(crate) covered_statements: usize,
+    /// Lines of the blocks no test ran, in order and merged when adjacent
+    pub(crate) uncovered: Vec<LineRange>,
+}
+
+impl FileCoverage {
+    pub(crate) fn percent(&self) -> f32 {
+        if self.statements == 0 {
+            return 100.0;
+        }
+        self.covered_statements as f32 / self.statements as f32 * 100.0
+    }
+
+    pub(crate) fn split_path(&self) -> (&str, &str) {
+        split_path(&self.path)
+    }
+}
+
+/// The package a file belongs to and its name, e.g. `example.com/api` and `handler.go`
+pub(crate) fn split_path(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+/// The files of a profile written by `go test -coverprofile`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CoverProfile {
+    pub(crate) files: Vec<FileCoverage>,
+}
+
+/// Reads the profile at `path` from the host filesystem, `None` if there's no profile
+pub(crate) fn load(path: &Path) -> Option<Result<CoverProfile, String>> {
+    match std::fs::read_to_string(host_path(path)) {
+        Ok(contents) => {
+            Some(parse(&contents).map_err(|err| format!("{}: {}", path.display(), err)))
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => None,
+        Err(err) => Some(Err(format!("Failed to read {}: {}", path.display(), err))),
+    }
+}
+
+/// Parses a profile, e.g. `mode: set` followed by lines like
+/// `example.com/api/handler.go:12.34,14.2 3 1`. Blocks listed more than once, as in profiles of
+/// several packages run with `-coverpkg`, count as covered if any of them is.
+pub(crate) fn parse(contents: &str) -> Result<CoverProfile, String> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    if !lines.next().is_some_and(|line| line.starts_with("mode:")) {
+        return Err("not a coverage profile, the mode line is missing".to_owned());
+    }
+    // Statements and whether they ran, by the start and end of their block
+    let mut files = BTreeMap::<&str, BTreeMap<_, (usize, bool)>>::new();
+    for (index, line) in lines.enumerate() {
+        let block = parse_block(line)
+            .ok_or_else(|| format!("invalid block on line {}: {}", index + 2, line))?;
+        let (_, is_covered) = files
+            .entry(block.path)
+            .or_default()
+            .entry((block.start, block.end))
+            .or_insert((block.statements, false));
+        *is_covered |= block.count > 0;
+    }
+    Ok(CoverProfile {
+        files: files
+            .into_iter()
+            .map(|(path, blocks)| {
+                let mut uncovered: Vec<LineRange> = Vec::new();
+                for (((start, _), (end, _)), _) in
+                    blocks.iter().filter(|(_, (_, is_covered))| !is_covered)
+                {
+                    match uncovered.last_mut() {
+                        Some(last) if *start <= last.end + 1 => last.end = last.end.max(*end),
+                        _ => uncovered.push(LineRange {
+                            start: *start,
+                            end: *end,
+                        }),
+                    }
+                }
+                FileCoverage {
+                    path: path.to_owned(),
+                    statements: blocks.values().map(|(statements, _)| statements).sum(),
+                    covered_statements: blocks
+                        .values()
+                        .filter(|(_, is_covered)| *is_covered)
+                        .map(|(statements, _)| statements)
+                        .sum(),
+                    uncovered,
+                }
+            })
+            .collect(),
+    })
+}
+
+fn parse_block(line: &str) -> Option<Block<'_>> {
+    let (path, block) = line.rsplit_once(':')?;
+    let mut fields = block.split_whitespace();
+    let (start, end) = fields.next()?.split_once(',')?;
+    Some(Block {
+        path,
+        start: position(start)?,
+        end: position(end)?,
+        statements: fields.next()?.parse().ok()?,
+        count: fields.next()?.parse().ok()?,
+    })
+}
+
+fn position(value: &str) -> Option<Position> {
+    let (line, column) = value.split_once('.')?;
+    Some((line.parse().ok()?, column.parse().ok()?))
+}
+
+/// A row of `go tool cover -func`
+#[derive(Debug, Clone)]
+pub(crate) struct FunctionCoverage {
+    /// Import path of the file declaring the function, e.g. `example.com/api/handler.go`
+    pub(crate) path: String,
+    pub(crate) line: usize,
+    pub(crate) name: String,
+    pub(crate) percent: f32,
+}
+
+/// Parses the output of `go tool cover -func` into its functions and the total coverage, e.g.
+/// `example.com/api/handler.go:12: Get 75.0%` and `total: (statements) 72.3%`
+pub(crate) fn parse_functions(output: &str) -> (Vec<FunctionCoverage>, Option<f32>) {
+    let mut functions = Vec::new();
+    let mut total = None;
+    for line in output.lines() {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        let [location, name, percent] = fields[..] else {
+            continue;
+        };
+        let Some(percent) = percent
+            .strip_suffix('%')
+            .and_then(|percent| percent.parse().ok())
+        else {
+            continue;
+        };
+        if location == "total:" {
+            total = Some(percent);
+            continue;
+        }
+        let Some((path, line)) = location
+            .strip_suffix(':')
+            .and_then(|location| location.rsplit_once(':'))
+            .and_then(|(path, line)| Some((path, line.parse().ok()?)))
+        else {
+            continue;
+        };
+        functions.push(FunctionCoverage {
+            path: path.to_owned(),
+            line,
+            name: name.to_owned(),
+            percent,
+        });
+    }
+    (functions, total)
+}
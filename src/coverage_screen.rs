@@ -0,0 +1,669 @@
+use std::{collections::BTreeMap, path::Path};
+
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    config::CoverageThresholds,
+    cover_profile::{self, CoverProfile, FunctionCoverage},
+    keymap::ScreenAction,
+    runner,
+    source_location::SourceLocation,
+    theme,
+    viewport::Viewport,
+    Package,
+};
+
+/// Context value of the `go tool cover -func` command listing the coverage of each function
+pub(crate) const FUNCTIONS_CONTEXT: &str = "cover-func";
+/// Width of the bars drawn for the coverage percentages
+const BAR_WIDTH: usize = 20;
+/// Functions below this percentage are highlighted when no `coverage_thresholds` are set
+const LOW_COVERAGE: f32 = 50.0;
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+    /// Reads the coverage profile again, e.g. after a new run wrote it
+    ReloadProfile,
+    /// Lists the coverage of each function with `go tool cover -func`
+    ListFunctions,
+    OpenSource {
+        package: String,
+        location: SourceLocation,
+    },
+}
+
+/// Keys of the coverage screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum CoverageAction {
+    Exit,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    ToggleView,
+    ReloadProfile,
+    ListFunctions,
+    CycleFunctionSort,
+    Open,
+}
+
+impl ScreenAction for CoverageAction {
+    const SCREEN: &'static str = "Coverage";
+
+    fn description(&self) -> &'static str {
+        match self {
+            CoverageAction::Exit => "back to the files, or to the tests",
+            CoverageAction::Down => "select the next row",
+            CoverageAction::Up => "select the previous row",
+            CoverageAction::PageDown => "move the selection down a page",
+            CoverageAction::PageUp => "move the selection up a page",
+            CoverageAction::ToggleView => "toggle between the packages and the profile's files",
+            CoverageAction::ReloadProfile => "read the coverage profile again",
+            CoverageAction::ListFunctions => "list the coverage of each function",
+            CoverageAction::CycleFunctionSort => "cycle the sort order of the functions",
+            CoverageAction::Open => "list a file's uncovered lines, or open them in the editor",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            CoverageAction::Exit => &["Esc"],
+            CoverageAction::Down => &["Down", "j"],
+            CoverageAction::Up => &["Up", "k"],
+            CoverageAction::PageDown => &["PageDown", "f"],
+            CoverageAction::PageUp => &["PageUp", "b"],
+            CoverageAction::ToggleView => &["p"],
+            CoverageAction::ReloadProfile => &["r"],
+            CoverageAction::ListFunctions => &["F"],
+            CoverageAction::CycleFunctionSort => &["s"],
+            CoverageAction::Open => &["Enter"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    /// The percentages reported by `go test -cover`
+    Packages,
+    /// The files of the coverage profile
+    Files,
+    /// The line ranges of a file of the profile that no test ran
+    Uncovered { file: usize },
+    /// The functions listed by `go tool cover -func`
+    Functions,
+}
+
+#[derive(Debug)]
+enum Functions {
+    NotListed,
+    Loading,
+    Loaded {
+        functions: Vec<FunctionCoverage>,
+        total: Option<f32>,
+    },
+    Failed(String),
+}
+
+/// Order of the functions, cycled with `s`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum FunctionSort {
+    #[default]
+    LeastCovered,
+    MostCovered,
+    /// By file and line, the order of `go tool cover -func`
+    Location,
+}
+
+impl FunctionSort {
+    fn next(self) -> Self {
+        match self {
+            FunctionSort::LeastCovered => FunctionSort::MostCovered,
+            FunctionSort::MostCovered => FunctionSort::Location,
+            FunctionSort::Location => FunctionSort::LeastCovered,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            FunctionSort::LeastCovered => "least covered first",
+            FunctionSort::MostCovered => "most covered first",
+            FunctionSort::Location => "by file",
+        }
+    }
+}
+
+/// Lists the packages reporting coverage, or the files of the `-coverprofile` profile, least
+/// covered first
+#[derive(Debug)]
+pub(crate) struct CoverageScreen {
+    /// `None` when no profile was found, the error when it couldn't be read
+    profile: Option<Result<CoverProfile, String>>,
+    view: View,
+    viewport: Viewport,
+    /// Selection of the files list, kept while a file's uncovered lines are shown
+    files_viewport: Viewport,
+    functions: Functions,
+    function_sort: FunctionSort,
+}
+
+impl CoverageScreen {
+    /// Starts on the files of the profile if it could be read
+    pub(crate) fn new(profile: Option<Result<CoverProfile, String>>) -> Self {
+        Self {
+            view: match profile {
+                Some(Ok(_)) => View::Files,
+                _ => View::Packages,
+            },
+            profile,
+            viewport: Viewport::default(),
+            files_viewport: Viewport::default(),
+            functions: Functions::NotListed,
+            function_sort: FunctionSort::default(),
+        }
+    }
+
+    /// Replaces the profile with a reloaded one. The uncovered lines go back to the files, which
+    /// may have changed.
+    pub(crate) fn set_profile(&mut self, profile: Option<Result<CoverProfile, String>>) {
+        self.view = match (&profile, self.view) {
+            (Some(Ok(_)), View::Packages) => View::Packages,
+            (Some(Ok(_)), _) => View::Files,
+            _ => View::Packages,
+        };
+        self.profile = profile;
+        self.viewport = Viewport::default();
+        self.files_viewport = Viewport::default();
+        self.functions = Functions::NotListed;
+    }
+
+    /// Shows the functions of the profile, listing them in the background unless they already are
+    pub(crate) fn list_functions(&mut self, profile_path: &Path) {
+        self.view = View::Functions;
+        self.viewport = Viewport::default();
+        if matches!(
+            self.functions,
+            Functions::Loaded { .. } | Functions::Loading
+        ) {
+            return;
+        }
+        run_command(
+            &[
+                "go",
+                "tool",
+                "cover",
+                &format!("-func={}", profile_path.display()),
+            ],
+            BTreeMap::from([(runner::CONTEXT_KEY.to_owned(), FUNCTIONS_CONTEXT.to_owned())]),
+        );
+        self.functions = Functions::Loading;
+    }
+
+    /// Handles the result of the `go tool cover` command started by
+    /// [`CoverageScreen::list_functions`]
+    pub(crate) fn on_functions_listed(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: &[u8],
+        stderr: &[u8],
+    ) {
+        self.functions = if exit_code == Some(0) {
+            let (functions, total) =
+                cover_profile::parse_functions(&String::from_utf8_lossy(stdout));
+            Functions::Loaded { functions, total }
+        } else {
+            Functions::Failed(format!(
+                "Failed to list functions: {}",
+                String::from_utf8_lossy(stderr).trim()
+            ))
+        };
+    }
+
+    /// The listed functions in the selected order
+    fn sorted_functions(&self) -> Vec<&FunctionCoverage> {
+        let Functions::Loaded { functions, .. } = &self.functions else {
+            return Vec::new();
+        };
+        let mut functions = functions.iter().collect::<Vec<_>>();
+        match self.function_sort {
+            FunctionSort::LeastCovered => {
+                functions.sort_by(|a, b| a.percent.total_cmp(&b.percent));
+            }
+            FunctionSort::MostCovered => {
+                functions.sort_by(|a, b| b.percent.total_cmp(&a.percent));
+            }
+            FunctionSort::Location => (),
+        }
+        functions
+    }
+
+    fn files(&self) -> Vec<usize> {
+        let Some(Ok(profile)) = &self.profile else {
+            return Vec::new();
+        };
+        let mut files = (0..profile.files.len()).collect::<Vec<_>>();
+        files.sort_by(|a, b| {
+            let (a, b) = (&profile.files[*a], &profile.files[*b]);
+            a.percent()
+                .total_cmp(&b.percent())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        files
+    }
+
+    pub(crate) fn update(&mut self, event: Event, packages: &[Package]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let row_count = match self.view {
+            View::Packages => packages
+                .iter()
+                .filter(|package| package.coverage.is_some())
+                .count(),
+            View::Files => self.files().len(),
+            View::Uncovered { file } => self.uncovered_count(file),
+            View::Functions => self.sorted_functions().len(),
+        };
+        match CoverageAction::from_key(&key)? {
+            CoverageAction::Exit => match self.view {
+                View::Uncovered { .. } => {
+                    self.view = View::Files;
+                    self.viewport = self.files_viewport.clone();
+                    Some(UpdateCommand::Render)
+                }
+                View::Functions => {
+                    self.view = View::Files;
+                    self.viewport = Viewport::default();
+                    Some(UpdateCommand::Render)
+                }
+                _ => Some(UpdateCommand::ExitScreen),
+            },
+            CoverageAction::Down => {
+                self.viewport.select_next(1, row_count);
+                Some(UpdateCommand::Render)
+            }
+            CoverageAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            CoverageAction::PageDown => {
+                self.viewport.select_next(self.viewport.page(), row_count);
+                Some(UpdateCommand::Render)
+            }
+            CoverageAction::PageUp => {
+                self.viewport.select_previous(self.viewport.page());
+                Some(UpdateCommand::Render)
+            }
+            CoverageAction::ToggleView => {
+                self.view = match self.view {
+                    View::Packages if matches!(self.profile, Some(Ok(_))) => View::Files,
+                    View::Packages => return None,
+                    _ => View::Packages,
+                };
+                self.viewport = Viewport::default();
+                Some(UpdateCommand::Render)
+            }
+            CoverageAction::ReloadProfile => Some(UpdateCommand::ReloadProfile),
+            CoverageAction::ListFunctions => {
+                matches!(self.profile, Some(Ok(_))).then_some(UpdateCommand::ListFunctions)
+            }
+            CoverageAction::CycleFunctionSort => {
+                if self.view != View::Functions {
+                    return None;
+                }
+                self.function_sort = self.function_sort.next();
+                Some(UpdateCommand::Render)
+            }
+            CoverageAction::Open => match self.view {
+                View::Packages => None,
+                View::Files => {
+                    let file = *self.files().get(self.viewport.selected)?;
+                    self.files_viewport = self.viewport.clone();
+                    self.view = View::Uncovered { file };
+                    self.viewport = Viewport::default();
+                    Some(UpdateCommand::Render)
+                }
+                View::Uncovered { file } => {
+                    let Some(Ok(profile)) = &self.profile else {
+                        return None;
+                    };
+                    let file = &profile.files[file];
+                    let range = file.uncovered.get(self.viewport.selected)?;
+                    let (package, file_name) = file.split_path();
+                    Some(UpdateCommand::OpenSource {
+                        package: package.to_owned(),
+                        location: SourceLocation {
+                            file: file_name.to_owned(),
+                            line: range.start,
+                        },
+                    })
+                }
+                View::Functions => {
+                    let function = self
+                        .sorted_functions()
+                        .get(self.viewport.selected)
+                        .copied()?;
+                    let (package, file_name) = cover_profile::split_path(&function.path);
+                    Some(UpdateCommand::OpenSource {
+                        package: package.to_owned(),
+                        location: SourceLocation {
+                            file: file_name.to_owned(),
+                            line: function.line,
+                        },
+                    })
+                }
+            },
+        }
+    }
+
+    fn uncovered_count(&self, file: usize) -> usize {
+        match &self.profile {
+            Some(Ok(profile)) => profile.files[file].uncovered.len(),
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        packages: &[Package],
+        coverage_thresholds: Option<CoverageThresholds>,
+    ) {
+        match self.view {
+            View::Packages => self.render_packages(rows, cols, packages, coverage_thresholds),
+            View::Files => self.render_files(rows, cols, coverage_thresholds),
+            View::Uncovered { file } => self.render_uncovered(rows, cols, file),
+            View::Functions => self.render_functions(rows, cols, coverage_thresholds),
+        }
+    }
+
+    fn render_packages(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        packages: &[Package],
+        coverage_thresholds: Option<CoverageThresholds>,
+    ) {
+        let theme = theme::current();
+        let mut covered = packages
+            .iter()
+            .filter_map(|package| Some((package.name.as_str(), package.coverage?)))
+            .collect::<Vec<_>>();
+        let profile_hint = match &self.profile {
+            Some(Ok(_)) => "[p] files, ",
+            _ => "",
+        };
+        if covered.is_empty() {
+            print_text_with_coordinates(
+                Text::new(format!(
+                    "No coverage reported, run go test with -cover or -coverprofile  {}[Esc] back",
+                    profile_hint
+                )),
+                0,
+                0,
+                Some(cols),
+                Some(1),
+            );
+            self.print_profile_error(rows, cols);
+            return;
+        }
+        covered.sort_by(|(a_name, a), (b_name, b)| a.total_cmp(b).then_with(|| a_name.cmp(b_name)));
+
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(covered.len());
+
+        let table = covered
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["package", "coverage", " "]),
+                |acc, (i, (package, coverage))| {
+                    let row = vec![
+                        Text::new(*package),
+                        Text::new(format!("{:.1}%", coverage)),
+                        bar(*coverage, coverage_thresholds),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let average =
+            covered.iter().map(|(_, coverage)| coverage).sum::<f32>() / covered.len() as f32;
+        let count = covered.len().to_string();
+        let bottom_text = Text::new(format!(
+            "{} packages, {:.1}% on average — {}[Esc] back",
+            count, average, profile_hint
+        ))
+        .color_range(theme.accent, ..count.len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+        self.print_profile_error(rows, cols);
+    }
+
+    /// Shows why the profile couldn't be read above the bottom line
+    fn print_profile_error(&self, rows: usize, cols: usize) {
+        if let Some(Err(err)) = &self.profile {
+            print_text_with_coordinates(
+                Text::new(err).color_range(theme::current().fail, ..),
+                0,
+                rows.saturating_sub(2),
+                Some(cols),
+                Some(1),
+            );
+        }
+    }
+
+    fn render_files(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        coverage_thresholds: Option<CoverageThresholds>,
+    ) {
+        let theme = theme::current();
+        let files = self.files();
+        let Some(Ok(profile)) = &self.profile else {
+            return;
+        };
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(files.len());
+
+        let table = files
+            .iter()
+            .map(|file| &profile.files[*file])
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["file", "coverage", " ", "statements", "uncovered"]),
+                |acc, (i, file)| {
+                    let (package, _) = file.split_path();
+                    let row = vec![
+                        Text::new(&file.path)
+                            .color_range(theme.accent, ..package.len().min(file.path.len())),
+                        Text::new(format!("{:.1}%", file.percent())),
+                        bar(file.percent(), coverage_thresholds),
+                        Text::new(format!("{}/{}", file.covered_statements, file.statements)),
+                        Text::new(format!("{} ranges", file.uncovered.len())),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let statements = profile
+            .files
+            .iter()
+            .map(|file| file.statements)
+            .sum::<usize>();
+        let covered_statements = profile
+            .files
+            .iter()
+            .map(|file| file.covered_statements)
+            .sum::<usize>();
+        let total = if statements == 0 {
+            100.0
+        } else {
+            covered_statements as f32 / statements as f32 * 100.0
+        };
+        let count = files.len().to_string();
+        let bottom_text = Text::new(format!(
+            "{} files, {:.1}% of statements — [Enter] uncovered lines, [F] functions, [p] packages, [r] reload, [Esc] back",
+            count, total
+        ))
+        .color_range(theme.accent, ..count.len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+
+    fn render_uncovered(&mut self, rows: usize, cols: usize, file: usize) {
+        let theme = theme::current();
+        let Some(Ok(profile)) = &self.profile else {
+            return;
+        };
+        let file = &profile.files[file];
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(file.uncovered.len());
+
+        let table = file
+            .uncovered
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["uncovered lines", " "]),
+                |acc, (i, range)| {
+                    let lines = range.end - range.start + 1;
+                    let row = vec![
+                        Text::new(if lines == 1 {
+                            range.start.to_string()
+                        } else {
+                            format!("{}-{}", range.start, range.end)
+                        })
+                        .color_range(theme.fail, ..),
+                        Text::new(format!(
+                            "{} line{}",
+                            lines,
+                            if lines == 1 { "" } else { "s" }
+                        )),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let bottom_text = Text::new(format!(
+            "{} {:.1}% — [Enter] open file, [Esc] files",
+            file.path,
+            file.percent()
+        ))
+        .color_range(theme.accent, ..file.path.len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+
+    fn render_functions(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        coverage_thresholds: Option<CoverageThresholds>,
+    ) {
+        let theme = theme::current();
+        let (total, message) = match &self.functions {
+            Functions::Loaded { total, .. } => (*total, None),
+            Functions::NotListed | Functions::Loading => (None, Some("Listing functions…")),
+            Functions::Failed(err) => (None, Some(err.as_str())),
+        };
+        if let Some(message) = message {
+            print_text_with_coordinates(
+                Text::new(format!("{}  [Esc] files", message)),
+                0,
+                0,
+                Some(cols),
+                Some(1),
+            );
+            return;
+        }
+        let function_count = self.sorted_functions().len();
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(function_count);
+        let functions = self.sorted_functions();
+
+        let table = functions
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["function", "at", "coverage"]),
+                |acc, (i, function)| {
+                    let color = match coverage_thresholds {
+                        Some(thresholds) => Some(thresholds.color(function.percent)),
+                        None => (function.percent < LOW_COVERAGE).then_some(theme.fail),
+                    };
+                    let percent = Text::new(format!("{:.1}%", function.percent));
+                    let row = vec![
+                        Text::new(&function.name),
+                        Text::new(format!("{}:{}", function.path, function.line)),
+                        match color {
+                            Some(color) => percent.color_range(color, ..),
+                            None => percent,
+                        },
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let count = functions.len().to_string();
+        let total = total.map_or(String::new(), |total| format!(", {:.1}% in total", total));
+        let bottom_text = Text::new(format!(
+            "{} functions{} — [s] {}, [Enter] open file, [Esc] files",
+            count,
+            total,
+            self.function_sort.label()
+        ))
+        .color_range(theme.accent, ..count.len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+fn bar(coverage: f32, coverage_thresholds: Option<CoverageThresholds>) -> Text {
+    let filled = ((coverage / 100.0 * BAR_WIDTH as f32).round() as usize).min(BAR_WIDTH);
+    let bar = Text::new(format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH - filled)
+    ));
+    match coverage_thresholds {
+        Some(thresholds) => bar.color_range(thresholds.color(coverage), ..),
+        None => bar,
+    }
+}
@@ -0,0 +1,122 @@
+/// Tab stops of the expanded diff lines, so their columns line up wherever the log is shown
+pub(crate) const TAB_WIDTH: usize = 4;
+
+/// Role of a log line in an assertion diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffLine {
+    /// The line starting a diff, e.g. `mismatch (-want +got):` or testify's `Diff:`
+    Header,
+    /// What the test expected, e.g. a `-` line of `-want +got` or testify's `expected:`
+    Expected,
+    /// What the test got
+    Actual,
+    /// A `@@ -1 +1 @@` hunk header
+    Hunk,
+    /// An unchanged line between the changed ones
+    Context,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Block {
+    /// A go-cmp style `(-want +got)` diff, indented deeper than its header
+    Cmp {
+        indent: usize,
+        /// Whether `-` marks what the test got, as in `(-got +want)`
+        is_swapped: bool,
+    },
+    /// The `Diff:` section of a testify assertion
+    Testify,
+}
+
+/// Spots the diffs of testify and go-cmp in a log. Lines are fed in order, as the role of a line
+/// depends on the ones before it.
+#[derive(Debug, Default)]
+pub(crate) struct DiffDetector {
+    block: Option<Block>,
+}
+
+impl DiffDetector {
+    pub(crate) fn classify(&mut self, line: &str) -> Option<DiffLine> {
+        let line = line.trim_end_matches('\n');
+        if let Some(Block::Cmp { indent, is_swapped }) = self.block {
+            if !line.trim().is_empty() && indentation(line) > indent {
+                let content = line.trim_start_matches(is_indentation);
+                return Some(match content.chars().next() {
+                    Some('-') if is_swapped => DiffLine::Actual,
+                    Some('-') => DiffLine::Expected,
+                    Some('+') if is_swapped => DiffLine::Expected,
+                    Some('+') => DiffLine::Actual,
+                    _ => DiffLine::Context,
+                });
+            }
+            self.block = None;
+        }
+        if let Some((label, content)) = testify_fields(line) {
+            return self.classify_testify(label, content);
+        }
+        self.block = cmp_header(line);
+        self.block.map(|_| DiffLine::Header)
+    }
+
+    /// Classifies a line of a testify assertion, e.g. `expected: 1` in the `Error:` field
+    fn classify_testify(&mut self, label: &str, content: &str) -> Option<DiffLine> {
+        // A new field, e.g. `Test:`, ends the diff
+        if !label.trim().is_empty() {
+            self.block = None;
+        }
+        let content = content.trim_end();
+        if content == "Diff:" {
+            self.block = Some(Block::Testify);
+            return Some(DiffLine::Header);
+        }
+        if let Some(Block::Testify) = self.block {
+            // Including the `--- Expected` and `+++ Actual` headers
+            return Some(match content.chars().next() {
+                _ if content.starts_with("@@") => DiffLine::Hunk,
+                Some('-') => DiffLine::Expected,
+                Some('+') => DiffLine::Actual,
+                _ => DiffLine::Context,
+            });
+        }
+        self.block = None;
+        let (name, _) = content.split_once(':')?;
+        match name.trim_end() {
+            "expected" => Some(DiffLine::Expected),
+            "actual" => Some(DiffLine::Actual),
+            _ => None,
+        }
+    }
+}
+
+/// Splits a line of a testify assertion into its label and content, e.g.
+/// `        \tError:      \tNot equal: `
+fn testify_fields(line: &str) -> Option<(&str, &str)> {
+    line.trim_start_matches(' ')
+        .strip_prefix('\t')?
+        .split_once('\t')
+        .filter(|(label, _)| label.is_empty() || label.ends_with([' ', ':']))
+}
+
+/// Starts a go-cmp block at its header, e.g. `    foo_test.go:12: mismatch (-want +got):`
+fn cmp_header(line: &str) -> Option<Block> {
+    let is_swapped = if line.contains("(-want +got)") {
+        false
+    } else if line.contains("(-got +want)") {
+        true
+    } else {
+        return None;
+    };
+    Some(Block::Cmp {
+        indent: indentation(line),
+        is_swapped,
+    })
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars().take_while(|c| is_indentation(*c)).count()
+}
+
+/// go-cmp pads its output with non-breaking spaces as well
+fn is_indentation(c: char) -> bool {
+    c == ' ' || c == '\t' || c == '\u{a0}'
+}
@@ -0,0 +1,98 @@
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{keymap::ScreenAction, theme, viewport::Viewport, PipeError};
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    ClearErrors,
+    Render,
+}
+
+/// Keys of the errors screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum ErrorsAction {
+    Exit,
+    Down,
+    Up,
+    Clear,
+}
+
+impl ScreenAction for ErrorsAction {
+    const SCREEN: &'static str = "Errors";
+
+    fn description(&self) -> &'static str {
+        match self {
+            ErrorsAction::Exit => "back to the tests",
+            ErrorsAction::Down => "select the next error",
+            ErrorsAction::Up => "select the previous error",
+            ErrorsAction::Clear => "clear the errors",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            ErrorsAction::Exit => &["Esc"],
+            ErrorsAction::Down => &["Down", "j"],
+            ErrorsAction::Up => &["Up", "k"],
+            ErrorsAction::Clear => &["c"],
+        }
+    }
+}
+
+/// Lists the pipe payloads that couldn't be processed, together with the raw line
+#[derive(Debug, Default)]
+pub(crate) struct ErrorsScreen {
+    viewport: Viewport,
+}
+
+impl ErrorsScreen {
+    pub(crate) fn update(&mut self, event: Event, errors: &[PipeError]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match ErrorsAction::from_key(&key)? {
+            ErrorsAction::Exit => Some(UpdateCommand::ExitScreen),
+            ErrorsAction::Down => {
+                self.viewport.select_next(1, errors.len());
+                Some(UpdateCommand::Render)
+            }
+            ErrorsAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            ErrorsAction::Clear => Some(UpdateCommand::ClearErrors),
+        }
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize, errors: &[PipeError]) {
+        let theme = theme::current();
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(errors.len());
+
+        let table = errors
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["error", "line"]),
+                |acc, (i, error)| {
+                    let row = vec![Text::new(&error.message), Text::new(&error.line)];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let bottom_text = Text::new(format!("{} errors — [c] clear, [Esc] back", errors.len()))
+            .color_range(theme.fail, ..errors.len().to_string().len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
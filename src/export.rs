@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{config::host_path, logs_screen::LogSource, Package, TestCase, TestResult};
+
+/// Version of the JSON report schema, bumped on incompatible changes
+pub(crate) const JSON_REPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExportFormat {
+    Junit,
+    Json,
+}
+
+impl ExportFormat {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "junit" => Some(ExportFormat::Junit),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    version: u32,
+    packages: Vec<JsonPackage<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonPackage<'a> {
+    name: &'a str,
+    result: Option<&'a str>,
+    elapsed: Option<f64>,
+    log: &'a [String],
+    tests: Vec<JsonTest<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonTest<'a> {
+    name: &'a str,
+    result: Option<&'a str>,
+    elapsed: Option<f64>,
+    log: &'a [String],
+    subtests: Vec<JsonTest<'a>>,
+}
+
+impl<'a> From<&'a TestCase> for JsonTest<'a> {
+    fn from(test_case: &'a TestCase) -> Self {
+        Self {
+            name: &test_case.name,
+            result: test_case.result.as_ref().map(|result| result.as_ref()),
+            elapsed: test_case.elapsed,
+            log: &test_case.log,
+            subtests: test_case.subtests.iter().map(JsonTest::from).collect(),
+        }
+    }
+}
+
+/// Serializes the whole model into a stable, versioned JSON document
+pub(crate) fn json_report(packages: &[Package]) -> String {
+    let report = JsonReport {
+        version: JSON_REPORT_VERSION,
+        packages: packages
+            .iter()
+            .map(|package| JsonPackage {
+                name: &package.name,
+                result: package.result.as_ref().map(|result| result.as_ref()),
+                elapsed: package.elapsed,
+                log: &package.log,
+                tests: package.tests.iter().map(JsonTest::from).collect(),
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&report).expect("JSON report should always serialize")
+}
+
+/// Serializes the results into the JUnit XML format understood by most CI tools. Subtests are
+/// flattened into test cases named by their full go test name.
+pub(crate) fn junit_xml(packages: &[Package]) -> String {
+    let mut test_suites = String::new();
+    let (mut total_tests, mut total_failures, mut total_skipped, mut total_time) = (0, 0, 0, 0.0);
+    for package in packages {
+        let mut test_cases = String::new();
+        let (mut tests, mut failures, mut skipped) = (0, 0, 0);
+        if package.result == Some(TestResult::BuildFail) {
+            tests += 1;
+            failures += 1;
+            test_cases.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"[build failed]\" time=\"0\">\n      <failure message=\"Build failed\">{}</failure>\n    </testcase>\n",
+                escape(&package.name),
+                escape(&package.log.concat())
+            ));
+        }
+        for test_case in TestCase::flatten(&package.tests) {
+            tests += 1;
+            let outcome = match test_case.result {
+                Some(TestResult::Race) => {
+                    failures += 1;
+                    format!(
+                        "      <failure message=\"Data race\">{}</failure>\n",
+                        escape(&test_case.log.concat())
+                    )
+                }
+                Some(TestResult::Timeout) => {
+                    failures += 1;
+                    format!(
+                        "      <failure message=\"Timed out\">{}</failure>\n",
+                        escape(&test_case.log.concat())
+                    )
+                }
+                Some(TestResult::Fail | TestResult::BuildFail) => {
+                    failures += 1;
+                    format!(
+                        "      <failure message=\"Failed\">{}</failure>\n",
+                        escape(&test_case.log.concat())
+                    )
+                }
+                Some(TestResult::Skip | TestResult::NoTests) | None => {
+                    skipped += 1;
+                    format!(
+                        "      <skipped message=\"Skipped\"/>\n      <system-out>{}</system-out>\n",
+                        escape(&test_case.log.concat())
+                    )
+                }
+                Some(TestResult::Pass) => format!(
+                    "      <system-out>{}</system-out>\n",
+                    escape(&test_case.log.concat())
+                ),
+            };
+            test_cases.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\">\n{}    </testcase>\n",
+                escape(&package.name),
+                escape(&test_case.name),
+                test_case.elapsed.unwrap_or_default(),
+                outcome
+            ));
+        }
+        let time = package.elapsed.unwrap_or_default();
+        test_suites.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n{}  </testsuite>\n",
+            escape(&package.name),
+            tests,
+            failures,
+            skipped,
+            time,
+            test_cases
+        ));
+        total_tests += tests;
+        total_failures += failures;
+        total_skipped += skipped;
+        total_time += time;
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n{}</testsuites>\n",
+        total_tests, total_failures, total_skipped, total_time, test_suites
+    )
+}
+
+/// Writes a report to the host filesystem
+pub(crate) fn write_report(path: &Path, contents: &str) -> Result<(), String> {
+    std::fs::write(host_path(path), contents)
+        .map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+/// Fills the `{package}` and `{test}` placeholders of the `log_path` template. Slashes in the names
+/// are replaced so subtests don't end up in directories. A package log is named after the last
+/// element of the import path, and repeated attempts of a test get their number appended.
+pub(crate) fn log_path(template: &str, source: &LogSource) -> PathBuf {
+    let file_name = |name: &str| name.replace(['/', '\\'], "_");
+    let package = source.package.rsplit('/').next().unwrap_or(&source.package);
+    let mut test = source.test.as_deref().map_or(package.to_owned(), file_name);
+    if source.attempt > 0 {
+        test = format!("{}_{}", test, source.attempt + 1);
+    }
+    PathBuf::from(
+        template
+            .replace("{package}", &file_name(package))
+            .replace("{test}", &test),
+    )
+}
+
+/// Escapes XML special characters and drops control characters, like ANSI escapes, that aren't
+/// allowed in XML documents
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,330 @@
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{keymap::ScreenAction, name::Name, theme, viewport::Viewport, Package, TestCase};
+
+const MAX_WIDTH: usize = 100;
+const MAX_HEIGHT: usize = 24;
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+    /// Selects a package, or one of its tests, in the tests screen
+    Select {
+        package: Name,
+        test: Option<Name>,
+    },
+}
+
+/// Keys of the finder, other keys type the query
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum FinderAction {
+    Exit,
+    Select,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    ClearQuery,
+    DeleteChar,
+}
+
+impl ScreenAction for FinderAction {
+    const SCREEN: &'static str = "Finder";
+
+    fn description(&self) -> &'static str {
+        match self {
+            FinderAction::Exit => "back to the tests",
+            FinderAction::Select => "select the match in the tests",
+            FinderAction::Down => "select the next match",
+            FinderAction::Up => "select the previous match",
+            FinderAction::PageDown => "move the selection down a page",
+            FinderAction::PageUp => "move the selection up a page",
+            FinderAction::ClearQuery => "clear the query",
+            FinderAction::DeleteChar => "delete the last char of the query",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            FinderAction::Exit => &["Esc"],
+            FinderAction::Select => &["Enter"],
+            FinderAction::Down => &["Down", "Ctrl n", "Ctrl j"],
+            FinderAction::Up => &["Up", "Ctrl p", "Ctrl k"],
+            FinderAction::PageDown => &["PageDown"],
+            FinderAction::PageUp => &["PageUp"],
+            FinderAction::ClearQuery => &["Ctrl u"],
+            FinderAction::DeleteChar => &["Backspace"],
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Candidate {
+    package: Name,
+    test: Option<Name>,
+    /// The package and test names matched against, e.g. `example.com/api TestGet/missing`
+    label: String,
+}
+
+#[derive(Debug)]
+struct FuzzyMatch {
+    candidate: usize,
+    score: i64,
+    /// Char indices of the label matched by the query
+    indices: Vec<usize>,
+}
+
+/// Popup narrowing the names of every package and test down to the ones fuzzy matching the
+/// typed query, like fzf
+#[derive(Debug)]
+pub(crate) struct FinderScreen {
+    query: String,
+    candidates: Vec<Candidate>,
+    matches: Vec<FuzzyMatch>,
+    viewport: Viewport,
+}
+
+impl FinderScreen {
+    pub(crate) fn new(packages: &[Package]) -> Self {
+        let candidates = packages
+            .iter()
+            .flat_map(|package| {
+                std::iter::once(Candidate {
+                    package: package.name.clone(),
+                    test: None,
+                    label: package.name.to_string(),
+                })
+                .chain(TestCase::flatten(&package.tests).into_iter().map(
+                    |test| Candidate {
+                        package: package.name.clone(),
+                        test: Some(test.name.clone()),
+                        label: format!("{} {}", package.name, test.name),
+                    },
+                ))
+            })
+            .collect();
+        let mut finder_screen = Self {
+            query: String::new(),
+            candidates,
+            matches: Vec::new(),
+            viewport: Viewport::default(),
+        };
+        finder_screen.filter();
+        finder_screen
+    }
+
+    pub(crate) fn update(&mut self, event: Event) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let Some(action) = FinderAction::from_key(&key) else {
+            let is_typed =
+                !key.has_modifiers(&[KeyModifier::Ctrl]) && !key.has_modifiers(&[KeyModifier::Alt]);
+            return match key.bare_key {
+                BareKey::Char(c) if is_typed => {
+                    self.query.push(c);
+                    self.filter();
+                    Some(UpdateCommand::Render)
+                }
+                _ => None,
+            };
+        };
+        match action {
+            FinderAction::Exit => Some(UpdateCommand::ExitScreen),
+            FinderAction::Select => self.matches.get(self.viewport.selected).map(|fuzzy_match| {
+                let candidate = &self.candidates[fuzzy_match.candidate];
+                UpdateCommand::Select {
+                    package: candidate.package.clone(),
+                    test: candidate.test.clone(),
+                }
+            }),
+            FinderAction::Down => {
+                self.viewport.select_next(1, self.matches.len());
+                Some(UpdateCommand::Render)
+            }
+            FinderAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            FinderAction::PageDown => {
+                self.viewport
+                    .select_next(self.viewport.page(), self.matches.len());
+                Some(UpdateCommand::Render)
+            }
+            FinderAction::PageUp => {
+                self.viewport.select_previous(self.viewport.page());
+                Some(UpdateCommand::Render)
+            }
+            FinderAction::ClearQuery => {
+                self.query.clear();
+                self.filter();
+                Some(UpdateCommand::Render)
+            }
+            FinderAction::DeleteChar => {
+                self.query.pop();
+                self.filter();
+                Some(UpdateCommand::Render)
+            }
+        }
+    }
+
+    /// Narrows the candidates down to the ones matching the query, best matches first
+    fn filter(&mut self) {
+        let mut matches = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| {
+                fuzzy_match(&candidate.label, &self.query).map(|(score, indices)| FuzzyMatch {
+                    candidate: index,
+                    score,
+                    indices,
+                })
+            })
+            .collect::<Vec<_>>();
+        // Ties prefer shorter names, then keep the order the results came in
+        matches.sort_by_key(|fuzzy_match| {
+            (
+                -fuzzy_match.score,
+                self.candidates[fuzzy_match.candidate].label.len(),
+            )
+        });
+        self.matches = matches;
+        self.viewport.selected = 0;
+        self.viewport.scroll_y = 0;
+    }
+
+    /// Draws the popup centered over the screen below it
+    pub(crate) fn render(&mut self, rows: usize, cols: usize) {
+        let theme = theme::current();
+        let width = cols.saturating_sub(4).min(MAX_WIDTH);
+        let height = rows.saturating_sub(2).min(MAX_HEIGHT);
+        // Room for the borders, the query, its separator and a row of matches
+        if width < 10 || height < 5 {
+            return;
+        }
+        let x = (cols - width) / 2;
+        let y = (rows - height) / 2;
+        let inner_width = width - 4;
+        self.viewport.resize(inner_width, height - 4);
+        self.viewport.follow_selection(self.matches.len());
+
+        let print_line = |text: Text, line: usize| {
+            print_text_with_coordinates(text, x, y + line, Some(width), Some(1));
+        };
+        let title = " find ";
+        print_line(
+            Text::new(format!(
+                "┌{}{}┐",
+                title,
+                "─".repeat(width.saturating_sub(title.chars().count() + 2))
+            ))
+            .color_range(theme.accent, 1..1 + title.len()),
+            0,
+        );
+        let count = format!("{}/{}", self.matches.len(), self.candidates.len());
+        let query = truncate_start(
+            &format!("> {}_", self.query),
+            inner_width.saturating_sub(count.len() + 1),
+        );
+        print_line(
+            Text::new(format!(
+                "│ {}{}{} │",
+                query,
+                " ".repeat(inner_width.saturating_sub(query.chars().count() + count.len())),
+                count
+            ))
+            .color_range(theme.accent, 2..4),
+            1,
+        );
+        print_line(Text::new(format!("├{}┤", "─".repeat(width - 2))), 2);
+        let range = self.viewport.visible_range(self.matches.len());
+        for line in 0..self.viewport.height() {
+            let index = range.start + line;
+            let text = match self.matches.get(index).filter(|_| range.contains(&index)) {
+                Some(fuzzy_match) => {
+                    let label = &self.candidates[fuzzy_match.candidate].label;
+                    let label = label.chars().take(inner_width).collect::<String>();
+                    let padding = " ".repeat(inner_width.saturating_sub(label.chars().count()));
+                    let mut text = Text::new(format!("│ {}{} │", label, padding));
+                    // The borders come before the label
+                    let indices = fuzzy_match
+                        .indices
+                        .iter()
+                        .filter(|index| **index < inner_width)
+                        .map(|index| index + 2)
+                        .collect::<Vec<_>>();
+                    if !indices.is_empty() {
+                        text = text.color_indices(theme.search_match, indices);
+                    }
+                    if index == self.viewport.selected {
+                        text = theme.select(text);
+                    }
+                    text
+                }
+                None => Text::new(format!("│{}│", " ".repeat(width - 2))),
+            };
+            print_line(text, 3 + line);
+        }
+        let hint = " [Enter] select, [Esc] close ";
+        print_line(
+            Text::new(format!(
+                "└{}{}┘",
+                "─".repeat(width.saturating_sub(hint.chars().count() + 2)),
+                hint
+            )),
+            height - 1,
+        );
+    }
+}
+
+/// Scores how well `haystack` matches the chars of `query` in order. Matches at the start of a
+/// word and runs of consecutive chars score higher, gaps between them lower. Ignores case unless
+/// the query has an uppercase letter. `None` if not every char of the query is found.
+fn fuzzy_match(haystack: &str, query: &str) -> Option<(i64, Vec<usize>)> {
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let normalize = |c: char| {
+        if ignore_case {
+            c.to_ascii_lowercase()
+        } else {
+            c
+        }
+    };
+    let chars = haystack.chars().collect::<Vec<_>>();
+    let mut indices: Vec<usize> = Vec::new();
+    let mut score = 0;
+    let mut start = 0;
+    for query_char in query.chars().filter(|c| !c.is_whitespace()).map(normalize) {
+        let offset = chars[start..]
+            .iter()
+            .position(|c| normalize(*c) == query_char)?;
+        let index = start + offset;
+        score += 16;
+        if indices.last().is_some_and(|last| last + 1 == index) {
+            score += 12;
+        } else if !indices.is_empty() {
+            score -= offset.min(16) as i64;
+        }
+        let is_word_start = match index.checked_sub(1).map(|previous| chars[previous]) {
+            None => true,
+            Some(previous) => {
+                matches!(previous, '/' | '_' | '-' | '.' | ' ')
+                    || (previous.is_lowercase() && chars[index].is_uppercase())
+            }
+        };
+        if is_word_start {
+            score += 8;
+        }
+        indices.push(index);
+        start = index + 1;
+    }
+    Some((score, indices))
+}
+
+/// Keeps the end of a line that doesn't fit, where the typed query is
+fn truncate_start(line: &str, max_width: usize) -> String {
+    let count = line.chars().count();
+    line.chars().skip(count.saturating_sub(max_width)).collect()
+}
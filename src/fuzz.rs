@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+const STATUS_PREFIX: &str = "fuzz: elapsed: ";
+
+/// Progress of `go test -fuzz`, merged from its periodic status lines, e.g.
+/// `fuzz: elapsed: 3s, execs: 183532 (61169/sec), new interesting: 2 (total: 12)`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct FuzzProgress {
+    /// Time spent fuzzing as printed by go, e.g. `1m3s`
+    pub(crate) elapsed: String,
+    pub(crate) execs: u64,
+    pub(crate) execs_per_sec: u64,
+    /// Inputs that expanded the coverage during this run
+    pub(crate) new_interesting: u64,
+    pub(crate) corpus_size: u64,
+    /// Completed and total corpus entries while the baseline coverage is gathered
+    pub(crate) baseline: Option<(u64, u64)>,
+    /// Failing inputs written to the corpus
+    pub(crate) crashers: usize,
+}
+
+impl FuzzProgress {
+    pub(crate) fn is_status_line(line: &str) -> bool {
+        line.trim_start().starts_with(STATUS_PREFIX)
+    }
+
+    /// Merges a status line into the progress. Fields missing from the line keep their values.
+    pub(crate) fn update(&mut self, line: &str) {
+        let Some(status) = line.trim().strip_prefix(STATUS_PREFIX) else {
+            return;
+        };
+        let mut fields = status.split(", ");
+        if let Some(elapsed) = fields.next() {
+            self.elapsed = elapsed.to_owned();
+        }
+        for field in fields {
+            if let Some(execs) = field.strip_prefix("execs: ") {
+                // e.g. `183532 (61169/sec)`
+                let (execs, rate) = execs.split_once(" (").unwrap_or((execs, ""));
+                self.execs = execs.parse().unwrap_or(self.execs);
+                if let Some(rate) = rate.strip_suffix("/sec)") {
+                    self.execs_per_sec = rate.parse().unwrap_or(self.execs_per_sec);
+                }
+            } else if let Some(interesting) = field.strip_prefix("new interesting: ") {
+                // e.g. `2 (total: 12)`
+                let (new, total) = interesting.split_once(" (").unwrap_or((interesting, ""));
+                self.new_interesting = new.parse().unwrap_or(self.new_interesting);
+                if let Some(total) = total
+                    .strip_prefix("total: ")
+                    .and_then(|total| total.strip_suffix(')'))
+                {
+                    self.corpus_size = total.parse().unwrap_or(self.corpus_size);
+                }
+            } else if let Some(baseline) = field.strip_prefix("gathering baseline coverage: ") {
+                // e.g. `3/8 completed`
+                let (completed, total) = baseline
+                    .trim_end_matches(" completed")
+                    .split_once('/')
+                    .unwrap_or_default();
+                if let (Ok(completed), Ok(total)) = (completed.parse(), total.parse()) {
+                    self.baseline = Some((completed, total));
+                    self.corpus_size = total;
+                }
+            }
+        }
+    }
+
+    /// One line summary of the counters, e.g.
+    /// `fuzzing 3s: 183532 execs (61169/sec), corpus 12 (+2 new), 0 crashers`
+    pub(crate) fn summary(&self) -> String {
+        match self.baseline {
+            Some((completed, total)) if completed < total => format!(
+                "fuzzing {}: gathering baseline coverage {}/{}",
+                self.elapsed, completed, total
+            ),
+            _ => format!(
+                "fuzzing {}: {} execs ({}/sec), corpus {} (+{} new), {} {}",
+                self.elapsed,
+                self.execs,
+                self.execs_per_sec,
+                self.corpus_size,
+                self.new_interesting,
+                self.crashers,
+                if self.crashers == 1 {
+                    "crasher"
+                } else {
+                    "crashers"
+                }
+            ),
+        }
+    }
+}
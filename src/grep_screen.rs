@@ -0,0 +1,307 @@
+use std::borrow::Cow;
+
+use regex::Regex;
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    keymap::ScreenAction, name::Name, search::CaseSensitivity, theme, viewport::Viewport, Package,
+    TestCase,
+};
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    /// Opens the log of a package, or of one of its tests, scrolled to a line
+    OpenLogs {
+        package: Name,
+        test: Option<Name>,
+        attempt: usize,
+        line: usize,
+    },
+    Render,
+}
+
+/// Keys of the grep screen while typing the query, other keys type it
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum GrepQueryAction {
+    Exit,
+    Search,
+    ToggleRegex,
+    DeleteChar,
+}
+
+impl ScreenAction for GrepQueryAction {
+    const SCREEN: &'static str = "Grep query";
+
+    fn description(&self) -> &'static str {
+        match self {
+            GrepQueryAction::Exit => "back to the matches, or to the tests if there are none",
+            GrepQueryAction::Search => "search the logs",
+            GrepQueryAction::ToggleRegex => "toggle matching the query as a regex",
+            GrepQueryAction::DeleteChar => "delete the last char of the query",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            GrepQueryAction::Exit => &["Esc"],
+            GrepQueryAction::Search => &["Enter"],
+            GrepQueryAction::ToggleRegex => &["Tab"],
+            GrepQueryAction::DeleteChar => &["Backspace"],
+        }
+    }
+}
+
+/// Keys of the grep screen while browsing the matches
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum GrepResultsAction {
+    Exit,
+    Down,
+    Up,
+    EditQuery,
+    OpenLogs,
+}
+
+impl ScreenAction for GrepResultsAction {
+    const SCREEN: &'static str = "Grep matches";
+
+    fn description(&self) -> &'static str {
+        match self {
+            GrepResultsAction::Exit => "back to the tests",
+            GrepResultsAction::Down => "select the next match",
+            GrepResultsAction::Up => "select the previous match",
+            GrepResultsAction::EditQuery => "edit the query",
+            GrepResultsAction::OpenLogs => "open the log at the matching line",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            GrepResultsAction::Exit => &["Esc"],
+            GrepResultsAction::Down => &["Down", "j"],
+            GrepResultsAction::Up => &["Up", "k"],
+            GrepResultsAction::EditQuery => &["/"],
+            GrepResultsAction::OpenLogs => &["Enter"],
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+enum Mode {
+    #[default]
+    Query,
+    Results,
+}
+
+#[derive(Debug)]
+struct GrepMatch {
+    package: Name,
+    test: Option<Name>,
+    attempt: usize,
+    line_index: usize,
+    line: String,
+}
+
+/// Searches the logs of every package and test, listing the matching lines grouped by test
+#[derive(Debug, Default)]
+pub(crate) struct GrepScreen {
+    query: String,
+    is_regex: bool,
+    case_sensitivity: CaseSensitivity,
+    mode: Mode,
+    matches: Vec<GrepMatch>,
+    error: Option<String>,
+    viewport: Viewport,
+}
+
+impl GrepScreen {
+    pub(crate) fn new(case_sensitivity: CaseSensitivity) -> Self {
+        Self {
+            case_sensitivity,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn update(&mut self, event: Event, packages: &[Package]) -> Option<UpdateCommand> {
+        match self.mode {
+            Mode::Query => self.update_query(event, packages),
+            Mode::Results => self.update_results(event),
+        }
+    }
+
+    fn update_query(&mut self, event: Event, packages: &[Package]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let Some(action) = GrepQueryAction::from_key(&key) else {
+            let BareKey::Char(c) = key.bare_key else {
+                return None;
+            };
+            self.query.push(c);
+            return Some(UpdateCommand::Render);
+        };
+        match action {
+            GrepQueryAction::Exit => {
+                if self.matches.is_empty() {
+                    Some(UpdateCommand::ExitScreen)
+                } else {
+                    self.mode = Mode::Results;
+                    Some(UpdateCommand::Render)
+                }
+            }
+            GrepQueryAction::Search => {
+                self.search(packages);
+                Some(UpdateCommand::Render)
+            }
+            GrepQueryAction::ToggleRegex => {
+                self.is_regex = !self.is_regex;
+                Some(UpdateCommand::Render)
+            }
+            GrepQueryAction::DeleteChar => {
+                self.query.pop();
+                Some(UpdateCommand::Render)
+            }
+        }
+    }
+
+    fn update_results(&mut self, event: Event) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match GrepResultsAction::from_key(&key)? {
+            GrepResultsAction::Exit => Some(UpdateCommand::ExitScreen),
+            GrepResultsAction::Down => {
+                self.viewport.select_next(1, self.matches.len());
+                Some(UpdateCommand::Render)
+            }
+            GrepResultsAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            GrepResultsAction::EditQuery => {
+                self.mode = Mode::Query;
+                Some(UpdateCommand::Render)
+            }
+            GrepResultsAction::OpenLogs => {
+                self.matches
+                    .get(self.viewport.selected)
+                    .map(|grep_match| UpdateCommand::OpenLogs {
+                        package: grep_match.package.clone(),
+                        test: grep_match.test.clone(),
+                        attempt: grep_match.attempt,
+                        line: grep_match.line_index,
+                    })
+            }
+        }
+    }
+
+    fn search(&mut self, packages: &[Package]) {
+        let regex = if self.is_regex {
+            match Regex::new(&self.query) {
+                Ok(regex) => Some(regex),
+                Err(err) => {
+                    self.error = Some(err.to_string());
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        let is_match = |line: &str| match &regex {
+            Some(regex) => regex.is_match(line),
+            None => self.case_sensitivity.contains(line, &self.query),
+        };
+        let mut matches = Vec::new();
+        for package in packages {
+            let logs = std::iter::once((None, 0, &package.log)).chain(
+                TestCase::flatten(&package.tests)
+                    .into_iter()
+                    .map(|test| (Some(&test.name), test.attempt, &test.log)),
+            );
+            for (test, attempt, log) in logs {
+                for (line_index, line) in log.iter().enumerate() {
+                    if is_match(line) {
+                        matches.push(GrepMatch {
+                            package: package.name.clone(),
+                            test: test.cloned(),
+                            attempt,
+                            line_index,
+                            line: line.trim_end().to_owned(),
+                        });
+                    }
+                }
+            }
+        }
+        self.matches = matches;
+        self.error = None;
+        self.viewport = Viewport::default();
+        self.mode = Mode::Results;
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize) {
+        let theme = theme::current();
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(self.matches.len());
+
+        let table = self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["test", "line", "match"]),
+                |acc, (i, grep_match)| {
+                    // Only the first match of each test is labelled, grouping the rest under it
+                    let is_first_of_test = i == self.viewport.scroll_y
+                        || i.checked_sub(1)
+                            .and_then(|previous| self.matches.get(previous))
+                            .is_none_or(|previous| {
+                                previous.package != grep_match.package
+                                    || previous.test != grep_match.test
+                            });
+                    let label = if is_first_of_test {
+                        match &grep_match.test {
+                            Some(test) => Cow::Owned(format!("{} {}", grep_match.package, test)),
+                            None => Cow::Borrowed(grep_match.package.as_str()),
+                        }
+                    } else {
+                        Cow::Borrowed(" ")
+                    };
+                    let row = vec![
+                        Text::new(label),
+                        Text::new((grep_match.line_index + 1).to_string()),
+                        Text::new(&grep_match.line),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let kind = if self.is_regex { "regex" } else { "text" };
+        let bottom_text = match (&self.mode, &self.error) {
+            (Mode::Query, Some(error)) => {
+                Text::new(format!("grep {}: {} — {}", kind, self.query, error))
+                    .color_range(theme.fail, ..)
+            }
+            (Mode::Query, None) => Text::new(format!(
+                "grep {}: {}  [Enter] search, [Tab] toggle regex",
+                kind, self.query
+            )),
+            (Mode::Results, _) => Text::new(format!(
+                "{} matches for `{}` — [Enter] open, [/] edit, [Esc] back",
+                self.matches.len(),
+                self.query
+            ))
+            .color_range(theme.accent, ..self.matches.len().to_string().len()),
+        };
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
@@ -0,0 +1,210 @@
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    benchmarks_screen::BenchmarksAction,
+    comparison_screen::ComparisonAction,
+    coverage_screen::CoverageAction,
+    errors_screen::ErrorsAction,
+    finder_screen::FinderAction,
+    grep_screen::{GrepQueryAction, GrepResultsAction},
+    history_screen::HistoryAction,
+    keymap::{Binding, Keymap, ScreenAction},
+    lint_screen::LintAction,
+    package_picker::PickerAction,
+    run_screen::{FlagsAction, RunAction},
+    slowest_screen::SlowestAction,
+    theme,
+    triage_screen::TriageAction,
+    viewport::Viewport,
+};
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+}
+
+/// Keys of the help screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum HelpAction {
+    Exit,
+    Down,
+    Up,
+}
+
+impl ScreenAction for HelpAction {
+    const SCREEN: &'static str = "Help";
+
+    fn description(&self) -> &'static str {
+        match self {
+            HelpAction::Exit => "back to the tests",
+            HelpAction::Down => "scroll down a line",
+            HelpAction::Up => "scroll up a line",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            HelpAction::Exit => &["Esc", "?", "q"],
+            HelpAction::Down => &["Down", "j"],
+            HelpAction::Up => &["Up", "k"],
+        }
+    }
+}
+
+#[derive(Debug)]
+enum HelpRow {
+    Group(&'static str),
+    Binding {
+        keys: String,
+        description: &'static str,
+        /// Configuration entry overriding the keys
+        config_key: String,
+    },
+}
+
+/// Lists the key bindings of each screen, including the ones overridden in the configuration
+#[derive(Debug)]
+pub(crate) struct HelpScreen {
+    rows: Vec<HelpRow>,
+    viewport: Viewport,
+}
+
+impl HelpScreen {
+    pub(crate) fn new(keymap: &Keymap) -> Self {
+        let mut rows = vec![HelpRow::Group("Tests")];
+        push_bindings(&mut rows, &keymap.tests, |action| action.description());
+        rows.push(HelpRow::Group("Logs"));
+        push_bindings(&mut rows, &keymap.logs, |action| action.description());
+        // The tab keys aren't configurable
+        rows.push(HelpRow::Group("Any tab"));
+        rows.extend(
+            [
+                ("Alt 1-5", "switch to a tab by its number"),
+                ("Shift Tab", "switch to the next tab"),
+            ]
+            .map(|(keys, description)| HelpRow::Binding {
+                keys: keys.to_owned(),
+                description,
+                config_key: " ".to_owned(),
+            }),
+        );
+        rows.push(HelpRow::Group("Tests and logs"));
+        rows.extend(
+            [
+                (
+                    "N motion",
+                    "repeat a motion N times, with N's digits unbound, e.g. 9j",
+                ),
+                ("gg, Ngg", "jump to the first row, or row N"),
+                (
+                    "G, NG",
+                    "jump to the last row, or row N, unless G is bound to another action",
+                ),
+            ]
+            .map(|(keys, description)| HelpRow::Binding {
+                keys: keys.to_owned(),
+                description,
+                config_key: " ".to_owned(),
+            }),
+        );
+        // The screens whose keys aren't configurable
+        push_screen_actions::<HelpAction>(&mut rows);
+        push_screen_actions::<FinderAction>(&mut rows);
+        push_screen_actions::<PickerAction>(&mut rows);
+        push_screen_actions::<GrepQueryAction>(&mut rows);
+        push_screen_actions::<GrepResultsAction>(&mut rows);
+        push_screen_actions::<ErrorsAction>(&mut rows);
+        push_screen_actions::<HistoryAction>(&mut rows);
+        push_screen_actions::<ComparisonAction>(&mut rows);
+        push_screen_actions::<BenchmarksAction>(&mut rows);
+        push_screen_actions::<TriageAction>(&mut rows);
+        push_screen_actions::<LintAction>(&mut rows);
+        push_screen_actions::<SlowestAction>(&mut rows);
+        push_screen_actions::<CoverageAction>(&mut rows);
+        push_screen_actions::<RunAction>(&mut rows);
+        push_screen_actions::<FlagsAction>(&mut rows);
+        Self {
+            rows,
+            viewport: Viewport::default(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, event: Event) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match HelpAction::from_key(&key)? {
+            HelpAction::Exit => Some(UpdateCommand::ExitScreen),
+            HelpAction::Down => {
+                self.viewport.scroll_down(1, self.rows.len());
+                Some(UpdateCommand::Render)
+            }
+            HelpAction::Up => {
+                self.viewport.scroll_up(1);
+                Some(UpdateCommand::Render)
+            }
+        }
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize) {
+        let theme = theme::current();
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.clamp_scroll_y(self.rows.len());
+
+        let table = self.rows[self.viewport.visible_range(self.rows.len())]
+            .iter()
+            .fold(
+                Table::new().add_row(vec!["key", "action", "configured by"]),
+                |acc, row| match row {
+                    HelpRow::Group(name) => acc.add_styled_row(vec![
+                        Text::new(format!("{} screen", name)).color_range(theme.accent, ..),
+                        Text::new(" "),
+                        Text::new(" "),
+                    ]),
+                    HelpRow::Binding {
+                        keys,
+                        description,
+                        config_key,
+                    } => acc.add_styled_row(vec![
+                        Text::new(keys),
+                        Text::new(description),
+                        Text::new(config_key),
+                    ]),
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let bottom_text = Text::new("[j/k] scroll, [Esc] back");
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+fn push_screen_actions<A: ScreenAction>(rows: &mut Vec<HelpRow>) {
+    rows.push(HelpRow::Group(A::SCREEN));
+    rows.extend(A::iter().map(|action| HelpRow::Binding {
+        keys: action.keys().join(", "),
+        description: action.description(),
+        config_key: " ".to_owned(),
+    }));
+}
+
+fn push_bindings<A: Copy + AsRef<str>>(
+    rows: &mut Vec<HelpRow>,
+    bindings: &[Binding<A>],
+    description: fn(&A) -> &'static str,
+) {
+    rows.extend(bindings.iter().map(|binding| {
+        HelpRow::Binding {
+            keys: binding
+                .keys
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            description: description(&binding.action),
+            config_key: format!("key_{}", binding.action.as_ref()),
+        }
+    }));
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    config::Config,
+    keymap::ScreenAction,
+    summary::Summary,
+    tests_screen::{format_elapsed, TestsScreen},
+    theme,
+    viewport::Viewport,
+    Package,
+};
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    /// Shows the run at the given index of the history, `None` being the current run
+    ShowRun(Option<usize>),
+    /// Compares the run at the given index of the history, `None` being the current run, with the
+    /// run before it
+    CompareRun(Option<usize>),
+    Render,
+}
+
+/// Keys of the history screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum HistoryAction {
+    Exit,
+    Down,
+    Up,
+    ShowRun,
+    CompareRun,
+}
+
+impl ScreenAction for HistoryAction {
+    const SCREEN: &'static str = "History";
+
+    fn description(&self) -> &'static str {
+        match self {
+            HistoryAction::Exit => "back to the tests",
+            HistoryAction::Down => "select the next run",
+            HistoryAction::Up => "select the previous run",
+            HistoryAction::ShowRun => "show the results of the run",
+            HistoryAction::CompareRun => "compare the run with the run before it",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            HistoryAction::Exit => &["Esc"],
+            HistoryAction::Down => &["Down", "j"],
+            HistoryAction::Up => &["Up", "k"],
+            HistoryAction::ShowRun => &["Enter"],
+            HistoryAction::CompareRun => &["c"],
+        }
+    }
+}
+
+/// Results of a finished test run, kept around after a new run started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TestRun {
+    pub(crate) number: usize,
+    pub(crate) packages: Vec<Package>,
+    pub(crate) summary: Summary,
+}
+
+/// A run from the history opened in its own tests screen
+#[derive(Debug)]
+pub(crate) struct ViewedRun {
+    pub(crate) number: usize,
+    pub(crate) summary: Summary,
+    pub(crate) screen: TestsScreen,
+}
+
+impl ViewedRun {
+    pub(crate) fn new(run: &TestRun, config: &Config) -> Self {
+        let mut screen = TestsScreen::new(run.packages.clone());
+        screen.configure(config);
+        Self {
+            number: run.number,
+            summary: run.summary,
+            screen,
+        }
+    }
+}
+
+/// Lists the current run followed by the previous runs, newest first
+#[derive(Debug, Default)]
+pub(crate) struct HistoryScreen {
+    viewport: Viewport,
+}
+
+impl HistoryScreen {
+    pub(crate) fn update(&mut self, event: Event, history: &[TestRun]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match HistoryAction::from_key(&key)? {
+            HistoryAction::Exit => Some(UpdateCommand::ExitScreen),
+            HistoryAction::Down => {
+                self.viewport.select_next(1, history.len() + 1);
+                Some(UpdateCommand::Render)
+            }
+            HistoryAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            HistoryAction::ShowRun => Some(UpdateCommand::ShowRun(self.selected_run(history))),
+            HistoryAction::CompareRun => {
+                Some(UpdateCommand::CompareRun(self.selected_run(history)))
+            }
+        }
+    }
+
+    /// Index of the selected run in the history, `None` if the current run is selected
+    fn selected_run(&self, history: &[TestRun]) -> Option<usize> {
+        self.viewport
+            .selected
+            .checked_sub(1)
+            .and_then(|offset| history.len().checked_sub(offset + 1))
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        current_run: (usize, &Summary),
+        history: &[TestRun],
+    ) {
+        let theme = theme::current();
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(history.len() + 1);
+
+        let (current_number, current_summary) = current_run;
+        let table = std::iter::once((format!("#{} (current)", current_number), current_summary))
+            .chain(
+                history
+                    .iter()
+                    .rev()
+                    .map(|run| (format!("#{}", run.number), &run.summary)),
+            )
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["run", "passed", "failed", "skipped", "elapsed"]),
+                |acc, (i, (label, summary))| {
+                    let row = vec![
+                        Text::new(label),
+                        Text::new(summary.passed.to_string()).color_range(theme.pass, ..),
+                        Text::new(summary.failed.to_string()).color_range(theme.fail, ..),
+                        Text::new(summary.skipped.to_string()).color_range(theme.skip, ..),
+                        Text::new(format_elapsed(summary.elapsed)),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let bottom_text = Text::new(format!(
+            "{} previous runs — [Enter] show, [c] compare with previous, [Esc] back",
+            history.len()
+        ))
+        .color_range(theme.accent, ..history.len().to_string().len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
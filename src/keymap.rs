@@ -0,0 +1,445 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+use zellij_tile::prelude::*;
+
+/// Actions bound to keys in the tests screen. Overridden through `key_<name>` configuration
+/// entries, e.g. `key_down = "n, Down"`.
+///
+/// Space marks rows for the bulk actions, collapsing moved from Space to `c` with them. The old
+/// keys come back with `key_toggle_collapse "Space"` and `key_toggle_mark` set to another key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum TestsAction {
+    Down,
+    Up,
+    ScrollLeft,
+    ScrollRight,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    NextFailure,
+    PreviousFailure,
+    OpenLogs,
+    CycleSort,
+    ReverseSort,
+    FilterPass,
+    FilterFail,
+    FilterSkip,
+    FilterRace,
+    FilterTimeout,
+    FilterNoTests,
+    FilterSlow,
+    CycleKindFilter,
+    Search,
+    GlobalSearch,
+    FindTest,
+    PickPackage,
+    ClearSearch,
+    ToggleCollapse,
+    ToggleLogLines,
+    ToggleGrouping,
+    ToggleZen,
+    ToggleDetails,
+    RerunFailed,
+    RerunSelected,
+    AbortRun,
+    ClearResults,
+    NextStream,
+    NextProject,
+    ToggleWatch,
+    ShowErrors,
+    ShowHistory,
+    ShowBenchmarks,
+    ShowTriage,
+    ShowLint,
+    ShowSlowest,
+    ShowRunScreen,
+    ExportJunit,
+    ExportJson,
+    OpenSource,
+    CopyLog,
+    OpenPager,
+    ToggleMark,
+    CopyRunPattern,
+    CopyCommand,
+    SaveLogs,
+    ShowHelp,
+}
+
+impl TestsAction {
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            TestsAction::Down => "select the next row",
+            TestsAction::Up => "select the previous row",
+            TestsAction::First => "select the first row, or row N after typing N",
+            TestsAction::Last => "select the last row, or row N after typing N",
+            TestsAction::ScrollLeft => "scroll the columns left",
+            TestsAction::ScrollRight => "scroll the columns right",
+            TestsAction::HalfPageDown => "move down half a page",
+            TestsAction::HalfPageUp => "move up half a page",
+            TestsAction::PageDown => "move down a page",
+            TestsAction::PageUp => "move up a page",
+            TestsAction::NextFailure => "jump to the next failure",
+            TestsAction::PreviousFailure => "jump to the previous failure",
+            TestsAction::OpenLogs => "open the logs of the selected row",
+            TestsAction::CycleSort => "cycle the sort order",
+            TestsAction::ReverseSort => "reverse the sort order",
+            TestsAction::FilterPass => "toggle showing passed tests only",
+            TestsAction::FilterFail => "toggle showing failed tests only",
+            TestsAction::FilterSkip => "toggle showing skipped tests only",
+            TestsAction::FilterRace => "toggle showing data races only",
+            TestsAction::FilterTimeout => "toggle showing timeouts only",
+            TestsAction::FilterNoTests => "toggle showing packages without test files only",
+            TestsAction::FilterSlow => "toggle showing tests slower than `slow_threshold` only",
+            TestsAction::CycleKindFilter => "cycle showing all, regular, example or fuzz tests",
+            TestsAction::Search => "filter tests by name",
+            TestsAction::GlobalSearch => "search all logs",
+            TestsAction::FindTest => "find a package or test by fuzzy matching its name",
+            TestsAction::PickPackage => "pick a package to show only",
+            TestsAction::ClearSearch => {
+                "clear the name filter, the picked package or the marks, or go back"
+            }
+            TestsAction::ToggleCollapse => "collapse or expand the selected row",
+            TestsAction::ToggleLogLines => "toggle the column of output line counts",
+            TestsAction::ToggleGrouping => "toggle grouping packages by directory",
+            TestsAction::ToggleZen => "toggle hiding packages that completed without failing",
+            TestsAction::ToggleDetails => "toggle the details of the selected row",
+            TestsAction::RerunFailed => "rerun the failed tests",
+            TestsAction::RerunSelected => "rerun the selected test, or the marked ones",
+            TestsAction::AbortRun => "abort the run started by the plugin",
+            TestsAction::ClearResults => "clear the results",
+            TestsAction::NextStream => "show the next piped in stream",
+            TestsAction::NextProject => "switch to the next project",
+            TestsAction::ToggleWatch => "toggle rerunning on file changes",
+            TestsAction::ShowErrors => "show pipe errors",
+            TestsAction::ShowHistory => "show previous runs",
+            TestsAction::ShowBenchmarks => "show benchmark results",
+            TestsAction::ShowTriage => "triage the failures, one per row with their message",
+            TestsAction::ShowLint => "show the diagnostics piped in from go vet and staticcheck",
+            TestsAction::ShowSlowest => "show the slowest tests and a chart of their durations",
+            TestsAction::ShowRunScreen => "pick packages to run",
+            TestsAction::ExportJunit => "export a JUnit XML report",
+            TestsAction::ExportJson => "export a JSON report",
+            TestsAction::OpenSource => "open the failure's source location",
+            TestsAction::CopyLog => "copy the selected row's log",
+            TestsAction::OpenPager => "open the selected row's log in $PAGER, less if unset",
+            TestsAction::ToggleMark => "mark or unmark the selected row for bulk actions",
+            TestsAction::CopyRunPattern => "copy the -run pattern of the selected or marked tests",
+            TestsAction::CopyCommand => "copy the go test command of the selected or marked tests",
+            TestsAction::SaveLogs => "save the logs of the selected or marked rows to log_path",
+            TestsAction::ShowHelp => "show this help",
+        }
+    }
+
+    /// Whether a count typed before the action repeats it, e.g. `5j`
+    pub(crate) fn is_countable(&self) -> bool {
+        matches!(
+            self,
+            TestsAction::Down
+                | TestsAction::Up
+                | TestsAction::First
+                | TestsAction::Last
+                | TestsAction::ScrollLeft
+                | TestsAction::ScrollRight
+                | TestsAction::HalfPageDown
+                | TestsAction::HalfPageUp
+                | TestsAction::PageDown
+                | TestsAction::PageUp
+                | TestsAction::NextFailure
+                | TestsAction::PreviousFailure
+        )
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            TestsAction::Down => &["Down", "j"],
+            TestsAction::Up => &["Up", "k"],
+            TestsAction::ScrollLeft => &["Left", "h"],
+            TestsAction::ScrollRight => &["Right", "l"],
+            TestsAction::HalfPageDown => &["PageDown", "d"],
+            TestsAction::HalfPageUp => &["PageUp", "u"],
+            TestsAction::PageDown => &["f"],
+            TestsAction::PageUp => &["b"],
+            TestsAction::First => &["Home"],
+            TestsAction::Last => &["End", "G"],
+            TestsAction::NextFailure => &["]"],
+            TestsAction::PreviousFailure => &["["],
+            TestsAction::OpenLogs => &["Enter"],
+            TestsAction::CycleSort => &["s"],
+            TestsAction::ReverseSort => &["S"],
+            TestsAction::FilterPass => &["1"],
+            TestsAction::FilterFail => &["2"],
+            TestsAction::FilterSkip => &["3"],
+            TestsAction::FilterRace => &["4"],
+            TestsAction::FilterTimeout => &["5"],
+            TestsAction::FilterNoTests => &["6"],
+            TestsAction::FilterSlow => &["7"],
+            TestsAction::CycleKindFilter => &["t"],
+            TestsAction::Search => &["/"],
+            TestsAction::GlobalSearch => &["a"],
+            TestsAction::FindTest => &["Ctrl p"],
+            TestsAction::PickPackage => &["p"],
+            TestsAction::ClearSearch => &["Esc"],
+            TestsAction::ToggleCollapse => &["c"],
+            TestsAction::ToggleLogLines => &["L"],
+            TestsAction::ToggleGrouping => &["T"],
+            TestsAction::ToggleZen => &["z"],
+            TestsAction::ToggleDetails => &["i"],
+            TestsAction::RerunFailed => &["r"],
+            TestsAction::RerunSelected => &["R"],
+            TestsAction::AbortRun => &["x"],
+            TestsAction::ClearResults => &["C"],
+            TestsAction::NextStream => &["Tab"],
+            TestsAction::NextProject => &["P"],
+            TestsAction::ToggleWatch => &["W"],
+            TestsAction::ShowErrors => &["E"],
+            TestsAction::ShowHistory => &["H"],
+            TestsAction::ShowBenchmarks => &["B"],
+            TestsAction::ShowTriage => &["F"],
+            TestsAction::ShowLint => &["V"],
+            TestsAction::ShowSlowest => &["D"],
+            TestsAction::ShowRunScreen => &["X"],
+            TestsAction::ExportJunit => &["e"],
+            TestsAction::ExportJson => &["J"],
+            TestsAction::OpenSource => &["o"],
+            TestsAction::CopyLog => &["y"],
+            TestsAction::OpenPager => &["v"],
+            TestsAction::ToggleMark => &["Space"],
+            TestsAction::CopyRunPattern => &["Y"],
+            TestsAction::CopyCommand => &["Ctrl y"],
+            TestsAction::SaveLogs => &["Ctrl s"],
+            TestsAction::ShowHelp => &["?"],
+        }
+    }
+}
+
+/// Actions bound to keys in the logs screen. Actions sharing a name with a [`TestsAction`] are
+/// configured by the same `key_<name>` entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum LogsAction {
+    Exit,
+    Down,
+    Up,
+    ScrollLeft,
+    ScrollRight,
+    LineStart,
+    LineEnd,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    CopyLog,
+    OpenPager,
+    SaveLog,
+    ToggleWrap,
+    ToggleFollow,
+    ToggleTimestamps,
+    Filter,
+    ToggleFilter,
+    TogglePackageLog,
+}
+
+impl LogsAction {
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            LogsAction::Exit => "back to the tests",
+            LogsAction::Down => "scroll down a line",
+            LogsAction::Up => "scroll up a line",
+            LogsAction::ScrollLeft => "scroll left",
+            LogsAction::ScrollRight => "scroll right",
+            LogsAction::LineStart => "scroll to the start of the lines",
+            LogsAction::LineEnd => "scroll to the end of the longest line",
+            LogsAction::HalfPageDown => "scroll down half a page",
+            LogsAction::HalfPageUp => "scroll up half a page",
+            LogsAction::PageDown => "scroll down a page",
+            LogsAction::PageUp => "scroll up a page",
+            LogsAction::Search => "search the log",
+            LogsAction::NextMatch => "jump to the next match",
+            LogsAction::PreviousMatch => "jump to the previous match",
+            LogsAction::CopyLog => "copy the log",
+            LogsAction::OpenPager => "open the log in $PAGER, less if unset",
+            LogsAction::SaveLog => "save the log to the file set with log_path",
+            LogsAction::ToggleWrap => "toggle wrapping long lines",
+            LogsAction::ToggleFollow => "toggle following new output",
+            LogsAction::ToggleTimestamps => "cycle hiding, relative and absolute timestamps",
+            LogsAction::Filter => "only show the lines containing a pattern",
+            LogsAction::ToggleFilter => "toggle between the filtered and the full log",
+            LogsAction::TogglePackageLog => "toggle appending the package's log to a test's log",
+        }
+    }
+
+    /// Whether a count typed before the action repeats it, e.g. `5j`
+    pub(crate) fn is_countable(&self) -> bool {
+        matches!(
+            self,
+            LogsAction::Down
+                | LogsAction::Up
+                | LogsAction::ScrollLeft
+                | LogsAction::ScrollRight
+                | LogsAction::HalfPageDown
+                | LogsAction::HalfPageUp
+                | LogsAction::PageDown
+                | LogsAction::PageUp
+                | LogsAction::NextMatch
+                | LogsAction::PreviousMatch
+        )
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            LogsAction::Exit => &["Esc"],
+            LogsAction::Down => &["Down", "j"],
+            LogsAction::Up => &["Up", "k"],
+            LogsAction::ScrollLeft => &["Left", "h"],
+            LogsAction::ScrollRight => &["Right", "l"],
+            LogsAction::LineStart => &["Home", "0"],
+            LogsAction::LineEnd => &["End", "$"],
+            LogsAction::HalfPageDown => &["PageDown", "d"],
+            LogsAction::HalfPageUp => &["PageUp", "u"],
+            LogsAction::PageDown => &["f"],
+            LogsAction::PageUp => &["b"],
+            LogsAction::Search => &["/"],
+            LogsAction::NextMatch => &["n"],
+            LogsAction::PreviousMatch => &["N"],
+            LogsAction::CopyLog => &["y"],
+            LogsAction::OpenPager => &["v"],
+            LogsAction::SaveLog => &["s"],
+            LogsAction::ToggleWrap => &["w"],
+            LogsAction::ToggleFollow => &["F"],
+            LogsAction::ToggleTimestamps => &["t"],
+            LogsAction::Filter => &["&"],
+            LogsAction::ToggleFilter => &["a"],
+            LogsAction::TogglePackageLog => &["p"],
+        }
+    }
+}
+
+/// Actions of a screen whose keys aren't configurable. The help screen lists them next to the
+/// configurable [`TestsAction`]s and [`LogsAction`]s.
+pub(crate) trait ScreenAction: Copy + IntoEnumIterator {
+    /// Name of the screen the help lists the actions under
+    const SCREEN: &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn keys(&self) -> &'static [&'static str];
+
+    /// The action bound to a key, if any
+    fn from_key(key: &KeyWithModifier) -> Option<Self> {
+        Self::iter().find(|action| {
+            action
+                .keys()
+                .iter()
+                .any(|name| KeyWithModifier::from_str(name).is_ok_and(|bound| bound == *key))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Binding<A> {
+    pub(crate) action: A,
+    pub(crate) keys: Vec<KeyWithModifier>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Keymap {
+    pub(crate) tests: Vec<Binding<TestsAction>>,
+    pub(crate) logs: Vec<Binding<LogsAction>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).0
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from the defaults and the `key_<action>` configuration entries. Invalid
+    /// entries keep the default keys and are reported in the returned errors.
+    pub(crate) fn new(configuration: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let keymap = Self {
+            tests: bindings(
+                configuration,
+                TestsAction::iter(),
+                TestsAction::default_keys,
+                &mut errors,
+            ),
+            logs: bindings(
+                configuration,
+                LogsAction::iter(),
+                LogsAction::default_keys,
+                &mut errors,
+            ),
+        };
+        errors.dedup();
+        (keymap, errors)
+    }
+
+    pub(crate) fn tests_action(&self, key: &KeyWithModifier) -> Option<TestsAction> {
+        find_action(&self.tests, key)
+    }
+
+    pub(crate) fn logs_action(&self, key: &KeyWithModifier) -> Option<LogsAction> {
+        find_action(&self.logs, key)
+    }
+}
+
+fn bindings<A: Copy + AsRef<str>>(
+    configuration: &BTreeMap<String, String>,
+    actions: impl Iterator<Item = A>,
+    default_keys: fn(&A) -> &'static [&'static str],
+    errors: &mut Vec<String>,
+) -> Vec<Binding<A>> {
+    actions
+        .map(|action| {
+            let config_key = format!("key_{}", action.as_ref());
+            let keys = match configuration.get(&config_key) {
+                Some(value) => parse_keys(value).unwrap_or_else(|err| {
+                    errors.push(format!("Invalid `{}` = \"{}\": {}", config_key, value, err));
+                    parse_default_keys(default_keys(&action))
+                }),
+                None => parse_default_keys(default_keys(&action)),
+            };
+            Binding { action, keys }
+        })
+        .collect()
+}
+
+/// Parses a comma separated list of keys, e.g. `"j, Down, Ctrl n"`. `g` is left to `gg`, see
+/// [`CountPrefix`](crate::count_prefix::CountPrefix).
+fn parse_keys(value: &str) -> Result<Vec<KeyWithModifier>, String> {
+    if value.trim() == "," {
+        return Ok(vec![KeyWithModifier::new(BareKey::Char(','))]);
+    }
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| match key {
+            "g" => Err("`g` starts `gg`, the jump to the first row".to_owned()),
+            key => KeyWithModifier::from_str(key).map_err(|err| err.to_string()),
+        })
+        .collect()
+}
+
+fn parse_default_keys(keys: &[&str]) -> Vec<KeyWithModifier> {
+    keys.iter()
+        .map(|key| KeyWithModifier::from_str(key).expect("Default keys should be valid"))
+        .collect()
+}
+
+fn find_action<A: Copy>(bindings: &[Binding<A>], key: &KeyWithModifier) -> Option<A> {
+    bindings
+        .iter()
+        .find(|binding| binding.keys.contains(key))
+        .map(|binding| binding.action)
+}
@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Name of the pipe `go vet -json` and `staticcheck -f json` output gets piped into, e.g.
+/// `go vet -json ./... 2>&1 | zellij pipe --name vet`
+pub(crate) const PIPE_NAME: &str = "vet";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tool {
+    Vet,
+    Staticcheck,
+}
+
+/// A finding of `go vet` or staticcheck
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Diagnostic {
+    pub(crate) tool: Tool,
+    /// Import path of the package for `go vet`, the directory of the file for staticcheck, which
+    /// doesn't report packages
+    pub(crate) package: String,
+    /// Path of the file, empty for errors of an analyzer
+    pub(crate) file: String,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    /// Name of the analyzer, e.g. `printf`, or code of the check, e.g. `SA4006`
+    pub(crate) check: String,
+    pub(crate) message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticcheckLine {
+    code: String,
+    location: StaticcheckLocation,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StaticcheckLocation {
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+/// Diagnostics piped into the `vet` pipe. `go vet -json` prints a multi-line json object per
+/// package, which arrives line by line, while staticcheck prints one json object per line.
+#[derive(Debug, Default)]
+pub(crate) struct Lint {
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    /// Packages `go vet` reported on, including the ones without diagnostics
+    pub(crate) vetted: BTreeSet<String>,
+    /// Lines of the `go vet` report being received, until its closing brace
+    pending: Vec<String>,
+}
+
+impl Lint {
+    /// Handles a line of output, returning whether diagnostics changed
+    pub(crate) fn feed(&mut self, line: &str) -> Result<bool, String> {
+        let line = line.trim_end();
+        if !self.pending.is_empty() {
+            self.pending.push(line.to_owned());
+            if line != "}" {
+                return Ok(false);
+            }
+            let report = self.pending.concat();
+            self.pending.clear();
+            return self.add_vet_report(&report);
+        }
+        if line.is_empty() {
+            return Ok(false);
+        }
+        // `go vet` prints the package before its report, e.g. `# example.com/api`
+        if let Some(package) = line.strip_prefix("# ") {
+            let package = crate::package_name_from_import_path(package.trim()).to_owned();
+            self.drop_vet_diagnostics(&package);
+            self.vetted.insert(package);
+            return Ok(true);
+        }
+        if line == "{" {
+            self.pending.push(line.to_owned());
+            return Ok(false);
+        }
+        if line.contains("\"code\"") {
+            let finding = serde_json::from_str::<StaticcheckLine>(line)
+                .map_err(|err| format!("Failed to deserialize staticcheck json: {}", err))?;
+            return Ok(self.add_staticcheck_finding(finding));
+        }
+        if line.starts_with('{') {
+            return self.add_vet_report(line);
+        }
+        Err("Expected go vet -json or staticcheck -f json output".to_owned())
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.diagnostics.clear();
+        self.vetted.clear();
+        self.pending.clear();
+    }
+
+    /// Replaces the diagnostics of the packages in a `go vet -json` report, e.g.
+    /// `{"example.com/api": {"printf": [{"posn": "/src/api/get.go:12:2", "message": "..."}]}}`.
+    /// Analyzers that failed report `{"error": "..."}` instead of a list.
+    fn add_vet_report(&mut self, report: &str) -> Result<bool, String> {
+        let report = serde_json::from_str::<BTreeMap<String, BTreeMap<String, Value>>>(report)
+            .map_err(|err| format!("Failed to deserialize go vet json: {}", err))?;
+        for (package, analyzers) in report {
+            let package = crate::package_name_from_import_path(&package).to_owned();
+            self.drop_vet_diagnostics(&package);
+            for (check, findings) in analyzers {
+                let findings = match findings {
+                    Value::Array(findings) => findings,
+                    error => vec![error],
+                };
+                for finding in findings {
+                    let message = finding
+                        .get("message")
+                        .or_else(|| finding.get("error"))
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_owned();
+                    let (file, line, column) = finding
+                        .get("posn")
+                        .and_then(Value::as_str)
+                        .and_then(parse_position)
+                        .unwrap_or_default();
+                    self.diagnostics.push(Diagnostic {
+                        tool: Tool::Vet,
+                        package: package.clone(),
+                        file,
+                        line,
+                        column,
+                        check: check.clone(),
+                        message,
+                    });
+                }
+            }
+            self.vetted.insert(package);
+        }
+        Ok(true)
+    }
+
+    /// Adds a finding unless it's already known, e.g. when staticcheck runs again
+    fn add_staticcheck_finding(&mut self, finding: StaticcheckLine) -> bool {
+        let package = finding
+            .location
+            .file
+            .rsplit_once('/')
+            .map_or(String::new(), |(dir, _)| dir.to_owned());
+        let diagnostic = Diagnostic {
+            tool: Tool::Staticcheck,
+            package,
+            file: finding.location.file,
+            line: finding.location.line,
+            column: finding.location.column,
+            check: finding.code,
+            message: finding.message,
+        };
+        if self.diagnostics.contains(&diagnostic) {
+            return false;
+        }
+        self.diagnostics.push(diagnostic);
+        true
+    }
+
+    fn drop_vet_diagnostics(&mut self, package: &str) {
+        self.diagnostics
+            .retain(|diagnostic| diagnostic.tool != Tool::Vet || diagnostic.package != package);
+    }
+}
+
+/// Parses a `file:line:column` position
+fn parse_position(position: &str) -> Option<(String, usize, usize)> {
+    let mut parts = position.rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    Some((parts.next()?.to_owned(), line, column))
+}
@@ -0,0 +1,287 @@
+use std::collections::BTreeSet;
+
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    keymap::ScreenAction,
+    lint::{Diagnostic, Lint},
+    name::Name,
+    source_location::SourceLocation,
+    theme,
+    viewport::Viewport,
+    Package,
+};
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+    OpenLogs {
+        package: Name,
+    },
+    OpenSource {
+        package: Name,
+        location: SourceLocation,
+    },
+    ClearDiagnostics,
+}
+
+/// Keys of the lint screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum LintAction {
+    Exit,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    Open,
+    Clear,
+}
+
+impl ScreenAction for LintAction {
+    const SCREEN: &'static str = "Lint";
+
+    fn description(&self) -> &'static str {
+        match self {
+            LintAction::Exit => "back to the tests",
+            LintAction::Down => "select the next row",
+            LintAction::Up => "select the previous row",
+            LintAction::PageDown => "move the selection down a page",
+            LintAction::PageUp => "move the selection up a page",
+            LintAction::Open => "open the package's logs or the diagnostic's source",
+            LintAction::Clear => "clear the diagnostics",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            LintAction::Exit => &["Esc"],
+            LintAction::Down => &["Down", "j"],
+            LintAction::Up => &["Up", "k"],
+            LintAction::PageDown => &["PageDown", "f"],
+            LintAction::PageUp => &["PageUp", "b"],
+            LintAction::Open => &["Enter"],
+            LintAction::Clear => &["c"],
+        }
+    }
+}
+
+/// A row of the screen: a package with its test result and diagnostic count, or one of the
+/// diagnostics listed below it
+#[derive(Debug)]
+enum Row<'a> {
+    Package {
+        name: &'a str,
+        package: Option<&'a Package>,
+        diagnostic_count: usize,
+        is_vetted: bool,
+    },
+    Diagnostic(&'a Diagnostic),
+}
+
+/// Lists the diagnostics of `go vet` and staticcheck under the package they're in, next to its
+/// test result, packages with diagnostics first
+#[derive(Debug, Default)]
+pub(crate) struct LintScreen {
+    viewport: Viewport,
+}
+
+impl LintScreen {
+    pub(crate) fn update(
+        &mut self,
+        event: Event,
+        lint: &Lint,
+        packages: &[Package],
+    ) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let rows = rows(lint, packages);
+        match LintAction::from_key(&key)? {
+            LintAction::Exit => Some(UpdateCommand::ExitScreen),
+            LintAction::Down => {
+                self.viewport.select_next(1, rows.len());
+                Some(UpdateCommand::Render)
+            }
+            LintAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            LintAction::PageDown => {
+                self.viewport.select_next(self.viewport.page(), rows.len());
+                Some(UpdateCommand::Render)
+            }
+            LintAction::PageUp => {
+                self.viewport.select_previous(self.viewport.page());
+                Some(UpdateCommand::Render)
+            }
+            LintAction::Clear => Some(UpdateCommand::ClearDiagnostics),
+            LintAction::Open => match rows.get(self.viewport.selected)? {
+                Row::Package { package, .. } => package.map(|package| UpdateCommand::OpenLogs {
+                    package: package.name.clone(),
+                }),
+                Row::Diagnostic(diagnostic) if !diagnostic.file.is_empty() => {
+                    Some(UpdateCommand::OpenSource {
+                        package: Name::new(&diagnostic.package),
+                        location: SourceLocation {
+                            file: diagnostic.file.clone(),
+                            line: diagnostic.line,
+                        },
+                    })
+                }
+                Row::Diagnostic(_) => None,
+            },
+        }
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize, lint: &Lint, packages: &[Package]) {
+        let theme = theme::current();
+        let lint_rows = self::rows(lint, packages);
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(lint_rows.len());
+
+        let table = lint_rows
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["package", "tests", "diagnostics"]),
+                |acc, (i, row)| {
+                    let row = match row {
+                        Row::Package {
+                            name,
+                            package,
+                            diagnostic_count,
+                            is_vetted,
+                        } => {
+                            let result = package.and_then(|package| package.result_or_derived());
+                            let tests = match result {
+                                Some(result) => Text::new(result.as_ref())
+                                    .color_range(result.marker_color(), ..),
+                                None if package.is_some() => {
+                                    Text::new("running").color_range(theme.running, ..)
+                                }
+                                None => Text::new("not tested"),
+                            };
+                            let diagnostics = match diagnostic_count {
+                                0 if *is_vetted => Text::new("clean").color_range(theme.pass, ..),
+                                0 => Text::new(" "),
+                                count => Text::new(format!("{} found", count))
+                                    .color_range(theme.fail, ..),
+                            };
+                            vec![
+                                Text::new(*name).color_range(theme.accent, ..),
+                                tests,
+                                diagnostics,
+                            ]
+                        }
+                        Row::Diagnostic(diagnostic) => {
+                            let position = match diagnostic.file.rsplit_once('/') {
+                                _ if diagnostic.file.is_empty() => " ".to_owned(),
+                                Some((_, file)) => {
+                                    format!("  {}:{}:{}", file, diagnostic.line, diagnostic.column)
+                                }
+                                None => format!(
+                                    "  {}:{}:{}",
+                                    diagnostic.file, diagnostic.line, diagnostic.column
+                                ),
+                            };
+                            vec![
+                                Text::new(position),
+                                Text::new(&diagnostic.check).color_range(theme.emphasis, ..),
+                                Text::new(&diagnostic.message),
+                            ]
+                        }
+                    };
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let count = lint.diagnostics.len().to_string();
+        let bottom_text = Text::new(format!(
+            "{} diagnostics — pipe go vet -json or staticcheck -f json into --name {}, [Enter] open, [c] clear, [Esc] back",
+            count,
+            crate::lint::PIPE_NAME
+        ))
+        .color_range(theme.fail, ..count.len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+fn rows<'a>(lint: &'a Lint, packages: &'a [Package]) -> Vec<Row<'a>> {
+    // staticcheck reports directories, matched to the tested packages by their last path elements
+    let tested_package = |name: &str| {
+        packages
+            .iter()
+            .find(|package| package.name == name)
+            .or_else(|| {
+                packages
+                    .iter()
+                    .map(|package| (package, matching_suffix(&package.name, name)))
+                    .filter(|(_, length)| *length > 0)
+                    .max_by_key(|(_, length)| *length)
+                    .map(|(package, _)| package)
+            })
+    };
+    let mut names = lint
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.package.as_str())
+        .chain(lint.vetted.iter().map(String::as_str))
+        .collect::<BTreeSet<_>>();
+    let linted = names
+        .iter()
+        .filter_map(|name| tested_package(name))
+        .map(|package| package.name.as_str())
+        .collect::<BTreeSet<_>>();
+    names.extend(
+        packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .filter(|name| !linted.contains(name)),
+    );
+    let mut groups = names
+        .into_iter()
+        .map(|name| {
+            let diagnostics = lint
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.package == name)
+                .collect::<Vec<_>>();
+            (name, diagnostics)
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|(_, diagnostics)| diagnostics.is_empty());
+    groups
+        .into_iter()
+        .flat_map(|(name, mut diagnostics)| {
+            diagnostics
+                .sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+            std::iter::once(Row::Package {
+                name,
+                package: tested_package(name),
+                diagnostic_count: diagnostics.len(),
+                is_vetted: lint.vetted.contains(name),
+            })
+            .chain(diagnostics.into_iter().map(Row::Diagnostic))
+        })
+        .collect()
+}
+
+/// Number of trailing path elements an import path and a directory have in common
+fn matching_suffix(import_path: &str, dir: &str) -> usize {
+    import_path
+        .rsplit('/')
+        .zip(dir.rsplit('/'))
+        .take_while(|(a, b)| a == b)
+        .count()
+}
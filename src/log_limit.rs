@@ -0,0 +1,73 @@
+/// Caps on the number of log lines kept, so verbose runs don't grow the plugin without bound. A
+/// limit of 0 disables it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LogLimits {
+    /// Lines kept per package or test log, set with `max_log_lines`
+    pub(crate) per_log: usize,
+    /// Lines kept across all logs of the current run, set with `max_total_log_lines`
+    pub(crate) total: usize,
+}
+
+impl Default for LogLimits {
+    fn default() -> Self {
+        Self {
+            per_log: 10_000,
+            total: 200_000,
+        }
+    }
+}
+
+/// Appends a line to a log, dropping its oldest lines once it exceeds the limit. Lines get
+/// dropped a quarter of the limit at a time, so the cost of shifting the log is spread over
+/// many lines.
+pub(crate) fn push(log: &mut Vec<String>, line: String, limit: usize) {
+    log.push(line);
+    if limit > 0 && log.len() > limit {
+        truncate(log, kept_lines(limit));
+    }
+}
+
+/// Number of lines a log over its limit is truncated to
+pub(crate) fn kept_lines(limit: usize) -> usize {
+    (limit - limit / 4).max(1)
+}
+
+/// Drops the oldest lines of a log, keeping the newest `keep` lines below a marker counting every
+/// line dropped so far. Returns the number of lines removed from the log.
+pub(crate) fn truncate(log: &mut Vec<String>, keep: usize) -> usize {
+    let dropped_before = dropped_lines(log);
+    let marker_count = usize::from(dropped_before > 0);
+    let line_count = log.len() - marker_count;
+    if line_count <= keep {
+        return 0;
+    }
+    let dropped = line_count - keep;
+    let length = log.len();
+    log.drain(..marker_count + dropped);
+    log.insert(0, marker(dropped_before + dropped));
+    length - log.len()
+}
+
+/// Number of lines written to a log, including the dropped ones but not their marker
+pub(crate) fn line_count(log: &[String]) -> usize {
+    match dropped_lines(log) {
+        0 => log.len(),
+        dropped => dropped + log.len() - 1,
+    }
+}
+
+/// Number of lines dropped from a log, read from its marker
+pub(crate) fn dropped_lines(log: &[String]) -> usize {
+    log.first()
+        .and_then(|line| line.strip_prefix(MARKER_PREFIX))
+        .and_then(|line| line.strip_suffix(MARKER_SUFFIX))
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0)
+}
+
+const MARKER_PREFIX: &str = "[... ";
+const MARKER_SUFFIX: &str = " earlier lines dropped ...]\n";
+
+fn marker(dropped: usize) -> String {
+    format!("{}{}{}", MARKER_PREFIX, dropped, MARKER_SUFFIX)
+}
@@ -0,0 +1,110 @@
+use std::cell::Cell;
+
+use serde::{Deserialize, Serialize};
+
+use crate::log_limit;
+
+thread_local! {
+    static NEXT_GENERATION: Cell<u64> = const { Cell::new(1) };
+}
+
+/// Timestamps of the lines of a log, from the `Time` field of their `output` actions. They're kept
+/// apart from the lines, numbered counting the lines dropped by the log limits, so dropping lines
+/// doesn't shift them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct LogTimes {
+    times: Vec<(usize, f64)>,
+    /// Changes whenever lines of the log get dropped or the log starts over, while appending
+    /// lines keeps it. Unique among all logs, so a replaced log never has the generation of the
+    /// one before it.
+    #[serde(skip, default = "next_generation")]
+    generation: u64,
+}
+
+impl Default for LogTimes {
+    fn default() -> Self {
+        Self {
+            times: Vec::new(),
+            generation: next_generation(),
+        }
+    }
+}
+
+impl LogTimes {
+    pub(crate) const EMPTY: &LogTimes = &LogTimes {
+        times: Vec::new(),
+        generation: 0,
+    };
+
+    /// Timestamp of a line shown at `index` of the log, `None` for the marker of dropped lines and
+    /// lines without a timestamp
+    pub(crate) fn get(&self, log: &[String], index: usize) -> Option<f64> {
+        let number = match log_limit::dropped_lines(log) {
+            0 => index,
+            // The marker takes the first line
+            _ if index == 0 => return None,
+            dropped => dropped + index - 1,
+        };
+        let position = self
+            .times
+            .binary_search_by_key(&number, |(number, _)| *number)
+            .ok()?;
+        Some(self.times[position].1)
+    }
+
+    /// Timestamp of the oldest line still in the log
+    pub(crate) fn first(&self) -> Option<f64> {
+        self.times.first().map(|(_, time)| *time)
+    }
+
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.times.clear();
+        self.generation = next_generation();
+    }
+
+    /// Marks lines of the log as dropped, e.g. by the total log limit
+    pub(crate) fn on_lines_dropped(&mut self) {
+        self.generation = next_generation();
+    }
+
+    /// Forgets the timestamps of the lines dropped from the log
+    fn prune(&mut self, log: &[String]) {
+        let dropped = log_limit::dropped_lines(log);
+        if self
+            .times
+            .first()
+            .is_some_and(|(number, _)| *number < dropped)
+        {
+            let kept = self.times.partition_point(|(number, _)| *number < dropped);
+            self.times.drain(..kept);
+        }
+    }
+}
+
+/// Appends a line to a log like [`log_limit::push`], recording its timestamp
+pub(crate) fn push(
+    log: &mut Vec<String>,
+    log_times: &mut LogTimes,
+    line: String,
+    time: Option<f64>,
+    limit: usize,
+) {
+    if let Some(time) = time {
+        log_times.times.push((log_limit::line_count(log), time));
+    }
+    let length = log.len();
+    log_limit::push(log, line, limit);
+    if log.len() <= length {
+        log_times.on_lines_dropped();
+    }
+    log_times.prune(log);
+}
+
+fn next_generation() -> u64 {
+    NEXT_GENERATION.with(|next| next.replace(next.get() + 1))
+}
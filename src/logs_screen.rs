@@ -1,214 +1,1116 @@
-use std::ops::Range;
+use std::{iter, mem, ops::Range};
 
 use zellij_tile::prelude::*;
 
+use crate::{
+    ansi::{self, StyledLine},
+    count_prefix::{CountPrefix, Input},
+    diff::{self, DiffDetector, DiffLine},
+    keymap::{Keymap, LogsAction},
+    log_times::LogTimes,
+    name::Name,
+    search::CaseSensitivity,
+    search_worker::{self, SearchMatches},
+    theme, timestamp,
+    viewport::Viewport,
+    DerivedStatus, Package, RunState, TestCase,
+};
+
+/// Number of columns moved by a single horizontal scroll
+const SCROLL_X_STEP: usize = 8;
+/// Start of the line separating a test's log from its package's log appended below it
+const PACKAGE_LOG_SEPARATOR: &str = "──── output of package ";
+/// Number of lines from which on logs get searched in the background by the search worker
+const WORKER_MIN_LINES: usize = 5_000;
+/// Lines sent to the search worker in a message at most
+const INDEX_CHUNK_LINES: usize = 1_000;
+
 #[derive(Debug)]
 pub(crate) enum UpdateCommand {
     ExitScreen,
+    CopyToClipboard(String),
+    /// Writes the log, without its ANSI escapes, to the file set with `log_path`
+    SaveLog(String),
+    /// Opens the log in the user's pager
+    OpenPager,
     Render,
 }
 
+/// How the timestamps of the lines are shown in front of them
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Timestamps {
+    #[default]
+    Hidden,
+    /// Seconds since the first line of the log, e.g. `+1.250s`
+    Relative,
+    /// Time of day in UTC, e.g. `12:34:56.789`
+    Absolute,
+}
+
+impl Timestamps {
+    /// Columns taken by the timestamps, including the space after them
+    fn width(&self) -> usize {
+        match self {
+            Timestamps::Hidden => 0,
+            Timestamps::Relative => 11,
+            Timestamps::Absolute => 13,
+        }
+    }
+
+    fn format(&self, time: f64, first_time: f64) -> String {
+        match self {
+            Timestamps::Hidden => String::new(),
+            Timestamps::Relative => format!("+{:.3}s", (time - first_time).max(0.0)),
+            Timestamps::Absolute => timestamp::format_time_of_day(time),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub(crate) enum Mode {
     #[default]
     Normal,
     Search(String),
+    /// Typing the pattern of the filter, which applies as it's typed
+    Filter(String),
 }
 
+/// Hides the lines not containing a pattern, like `&pattern` in less
 #[derive(Debug, Default)]
+struct LineFilter {
+    query: String,
+    /// Whether each line contains the query
+    is_matching: Vec<bool>,
+    /// Whether lines are hidden, toggled to see the full log without losing the filter
+    is_enabled: bool,
+}
+
+/// The package or test whose log is shown
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct LogSource {
+    pub(crate) package: Name,
+    pub(crate) test: Option<Name>,
+    /// Attempt of the test when it ran repeatedly, always 0 for packages
+    pub(crate) attempt: usize,
+}
+
+impl LogSource {
+    /// The live log in the model, empty once the package or test is gone, e.g. after clearing
+    pub(crate) fn log<'a>(&self, packages: &'a [Package]) -> &'a [String] {
+        self.find_log(packages).map_or(&[], |(log, _)| log)
+    }
+
+    /// Timestamps of the live log's lines
+    pub(crate) fn log_times<'a>(&self, packages: &'a [Package]) -> &'a LogTimes {
+        self.find_log(packages)
+            .map_or(LogTimes::EMPTY, |(_, log_times)| log_times)
+    }
+
+    fn find_log<'a>(&self, packages: &'a [Package]) -> Option<(&'a [String], &'a LogTimes)> {
+        packages
+            .iter()
+            .find(|package| package.name == self.package)
+            .and_then(|package| match &self.test {
+                Some(test) => TestCase::flatten(&package.tests)
+                    .into_iter()
+                    .find(|test_case| &test_case.name == test && test_case.attempt == self.attempt)
+                    .map(|test_case| (test_case.log.as_slice(), &test_case.log_times)),
+                None => Some((package.log.as_slice(), &package.log_times)),
+            })
+    }
+
+    /// Label and color of the live result, or of the run state while unfinished. `None` once the
+    /// package or test is gone.
+    pub(crate) fn status(&self, packages: &[Package]) -> Option<(String, usize)> {
+        let package = packages
+            .iter()
+            .find(|package| package.name == self.package)?;
+        let Some(test) = &self.test else {
+            return Some(match package.result_or_derived() {
+                Some(test_result) => (test_result.as_ref().to_owned(), test_result.marker_color()),
+                None if package.derived_status() == DerivedStatus::Aborted => {
+                    ("aborted".to_owned(), RunState::Aborted.marker_color())
+                }
+                None => ("running".to_owned(), RunState::Running.marker_color()),
+            });
+        };
+        let test_case = TestCase::flatten(&package.tests)
+            .into_iter()
+            .find(|test_case| &test_case.name == test && test_case.attempt == self.attempt)?;
+        let label = match (&test_case.result, test_case.state) {
+            _ if test_case.is_flaky() => "flaky pass",
+            (Some(test_result), _) => test_result.as_ref(),
+            (None, RunState::Paused) => "paused",
+            (None, RunState::Aborted) => "aborted",
+            (None, RunState::Running | RunState::Finished) => "running",
+        };
+        Some((label.to_owned(), test_case.marker_color()))
+    }
+
+    /// The package followed by the segments of the test's name and its attempt, e.g.
+    /// `pkg › TestA › sub #2`
+    pub(crate) fn breadcrumb(&self) -> String {
+        let breadcrumb = self
+            .test
+            .iter()
+            .flat_map(|test| test.split('/'))
+            .fold(self.package.to_string(), |breadcrumb, segment| {
+                format!("{} › {}", breadcrumb, segment)
+            });
+        breadcrumb + &crate::attempt_suffix(self.attempt)
+    }
+}
+
+/// The lines of the log shown, borrowed from the model: a test's log, followed by a separator and
+/// its package's log when that's appended
+#[derive(Debug)]
+pub(crate) struct LogView<'a> {
+    log: &'a [String],
+    package_log: Option<(String, &'a [String])>,
+}
+
+impl<'a> LogView<'a> {
+    pub(crate) fn len(&self) -> usize {
+        self.log.len()
+            + self
+                .package_log
+                .as_ref()
+                .map_or(0, |(_, package_log)| 1 + package_log.len())
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&str> {
+        match index.checked_sub(self.log.len()) {
+            None => Some(&self.log[index]),
+            Some(0) => self
+                .package_log
+                .as_ref()
+                .map(|(separator, _)| separator.as_str()),
+            Some(package_index) => self
+                .package_log
+                .as_ref()
+                .and_then(|(_, package_log)| package_log.get(package_index - 1))
+                .map(String::as_str),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &str> {
+        self.log
+            .iter()
+            .map(String::as_str)
+            .chain(
+                self.package_log
+                    .iter()
+                    .flat_map(|(separator, package_log)| {
+                        iter::once(separator.as_str()).chain(package_log.iter().map(String::as_str))
+                    }),
+            )
+    }
+
+    /// Index of the separator line, the package's log follows it
+    fn package_log_start(&self) -> Option<usize> {
+        self.package_log.as_ref().map(|_| self.log.len())
+    }
+}
+
+impl std::ops::Index<usize> for LogView<'_> {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &str {
+        self.get(index)
+            .expect("Line index should be within the log")
+    }
+}
+
+/// Shows the log of a package or test. The log isn't copied but passed in from the model on
+/// every update and render, so output arriving while the screen is open shows up right away.
+#[derive(Debug)]
 pub(crate) struct LogsScreen {
-    logs: Vec<String>,
-    scroll_x: usize,
-    scroll_y: usize,
-    screen_width: Option<usize>,
-    screen_height: Option<usize>,
+    source: LogSource,
+    /// Char count of each line seen so far, without ANSI escape sequences and trailing newline
+    line_lengths: Vec<usize>,
+    /// Scrolls through screen rows, see `visual_rows`
+    viewport: Viewport,
+    /// Screen rows of the lines shown, extended as lines arrive, see `visual_rows`
+    rows: Vec<(usize, Range<usize>)>,
+    /// Number of lines split into `rows` so far
+    rows_line_count: usize,
+    /// Width the `rows` were split at, `usize::MAX` while not wrapping
+    rows_width: usize,
     mode: Mode,
     search_result: Search,
+    case_sensitivity: CaseSensitivity,
+    /// Whether long lines are soft-wrapped at the screen width instead of scrolled horizontally.
+    /// The viewport counts screen rows, which only differ from lines while wrapping.
+    is_wrapping: bool,
+    /// Whether the screen scrolls to the end when new output arrives, like `tail -f`
+    is_following: bool,
+    timestamps: Timestamps,
+    filter: Option<LineFilter>,
+    /// Whether the package's log, with the output outside of its tests, e.g. of `TestMain`, is
+    /// appended to a test's log
+    show_package_log: bool,
+    /// Index of the line separating the package's log, the lines are synced again once it moves
+    package_log_start: Option<usize>,
+    count_prefix: CountPrefix,
+    /// Role of each line in an assertion diff, colored and with its tabs expanded when set
+    diff_lines: Vec<Option<DiffLine>>,
+    diff_detector: DiffDetector,
+    /// Number of lines whose text was sent to the search worker
+    indexed_lines: usize,
+    /// Generation of the log synced, see [`LogTimes`]
+    log_generation: u64,
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct Search {
+    /// Line index and column range of every match
     matches: Vec<(usize, Range<usize>)>,
     current_index: Option<usize>,
+    /// The last search, which new output gets searched for as well
+    query: String,
+    /// Id of the search running in the worker, the matches of older searches are dropped
+    pending_id: Option<u64>,
 }
 
 impl LogsScreen {
-    pub(crate) fn new(logs: Vec<String>) -> Self {
+    pub(crate) fn new(source: LogSource, case_sensitivity: CaseSensitivity) -> Self {
         Self {
-            logs,
-            ..Self::default()
+            source,
+            line_lengths: Vec::new(),
+            viewport: Viewport::default(),
+            rows: Vec::new(),
+            rows_line_count: 0,
+            rows_width: usize::MAX,
+            mode: Mode::default(),
+            search_result: Search::default(),
+            case_sensitivity,
+            is_wrapping: false,
+            is_following: false,
+            timestamps: Timestamps::default(),
+            filter: None,
+            show_package_log: false,
+            package_log_start: None,
+            count_prefix: CountPrefix::default(),
+            diff_lines: Vec::new(),
+            diff_detector: DiffDetector::default(),
+            indexed_lines: 0,
+            log_generation: 0,
         }
     }
 
-    pub(crate) fn update(&mut self, event: Event) -> Option<UpdateCommand> {
+    pub(crate) fn source(&self) -> &LogSource {
+        &self.source
+    }
+
+    pub(crate) fn set_following(&mut self, is_following: bool) {
+        self.is_following = is_following;
+    }
+
+    /// The log shown, the live log of the source followed by its package's log if enabled
+    pub(crate) fn log<'a>(&self, packages: &'a [Package]) -> LogView<'a> {
+        let log = self.source.log(packages);
+        if !self.show_package_log || self.source.test.is_none() {
+            return LogView {
+                log,
+                package_log: None,
+            };
+        }
+        let package_source = LogSource {
+            package: self.source.package.clone(),
+            test: None,
+            attempt: 0,
+        };
+        let separator = format!("{}{} ────\n", PACKAGE_LOG_SEPARATOR, self.source.package);
+        LogView {
+            log,
+            package_log: Some((separator, package_source.log(packages))),
+        }
+    }
+
+    /// Generation of the log shown, which changes once it or the package's log appended to it got
+    /// truncated or replaced. Generations only grow, so their sum changes whenever either does.
+    pub(crate) fn log_generation(&self, packages: &[Package]) -> u64 {
+        let generation = self.source.log_times(packages).generation();
+        if !self.show_package_log || self.source.test.is_none() {
+            return generation;
+        }
+        let package_source = LogSource {
+            package: self.source.package.clone(),
+            test: None,
+            attempt: 0,
+        };
+        generation + package_source.log_times(packages).generation()
+    }
+
+    /// Catches up with the lines appended to the log since the last call, searching them and
+    /// following them if enabled. A log of another generation got truncated or replaced, e.g. by
+    /// a rerun, so it's read from the start again.
+    fn sync(&mut self, log: &LogView, generation: u64) {
+        // The test's log grows above the package's while the test runs
+        let package_log_start = log.package_log_start();
+        if generation != self.log_generation
+            || log.len() < self.line_lengths.len()
+            || package_log_start != self.package_log_start
+        {
+            self.log_generation = generation;
+            self.package_log_start = package_log_start;
+            self.line_lengths.clear();
+            self.diff_lines.clear();
+            self.diff_detector = DiffDetector::default();
+            if let Some(filter) = &mut self.filter {
+                filter.is_matching.clear();
+            }
+            self.search_result.matches.clear();
+            self.search_result.current_index = None;
+            self.search_result.pending_id = None;
+            self.indexed_lines = 0;
+            self.invalidate_rows();
+        }
+        let first_new_line = self.line_lengths.len();
+        if first_new_line == log.len() {
+            return;
+        }
+        for (index, line) in log.iter().enumerate().skip(first_new_line) {
+            let diff_line = self.diff_detector.classify(&ansi::strip(line));
+            self.diff_lines.push(diff_line);
+            let text = self.styled_line(index, line).text;
+            self.line_lengths
+                .push(text.trim_end_matches('\n').chars().count());
+            if let Some(filter) = &mut self.filter {
+                filter
+                    .is_matching
+                    .push(self.case_sensitivity.contains(&text, &filter.query));
+            }
+            if !self.search_result.query.is_empty() {
+                self.search_result.matches.extend(
+                    self.case_sensitivity
+                        .find_matches(&text, &self.search_result.query)
+                        .into_iter()
+                        .map(|range| (index, range)),
+                );
+            }
+        }
+        // The rest goes along with the next lines, or at once when searched
+        self.index_in_worker(log, 1);
+        self.update_rows();
+        if self.is_following {
+            self.viewport.scroll_y = self.max_scroll_y();
+        } else {
+            self.viewport.scroll_y = self.viewport.scroll_y.min(self.max_scroll_y());
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        event: Event,
+        keymap: &Keymap,
+        log: &LogView,
+        generation: u64,
+    ) -> Option<UpdateCommand> {
+        self.sync(log, generation);
         match &mut self.mode {
-            Mode::Normal => match event {
-                Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Esc,
-                    ..
-                }) => Some(UpdateCommand::ExitScreen),
-                Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Down | BareKey::Char('j'),
-                    ..
-                }) => {
-                    self.scroll_y = self
-                        .scroll_y
-                        .saturating_add(1)
-                        .min(self.logs.len().saturating_sub(1));
-                    Some(UpdateCommand::Render)
-                }
-                Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Up | BareKey::Char('k'),
-                    ..
-                }) => {
-                    self.scroll_y = self.scroll_y.saturating_sub(1);
-                    Some(UpdateCommand::Render)
-                }
+            Mode::Normal => {
+                let key = match event {
+                    Event::Key(key) => key,
+                    Event::Mouse(Mouse::ScrollDown(lines)) => {
+                        self.viewport.scroll_down(lines, self.visual_rows().len());
+                        return Some(UpdateCommand::Render);
+                    }
+                    Event::Mouse(Mouse::ScrollUp(lines)) => {
+                        self.is_following = false;
+                        self.viewport.scroll_up(lines);
+                        return Some(UpdateCommand::Render);
+                    }
+                    _ => return None,
+                };
+                let (action, count) = match self
+                    .count_prefix
+                    .feed(key, |key| keymap.logs_action(key).is_some())
+                {
+                    Input::Pending => return Some(UpdateCommand::Render),
+                    // Counted jumps go to the line of the count
+                    Input::First(count) | Input::Last(count) if count.is_some() => {
+                        self.is_following = false;
+                        self.viewport.scroll_y =
+                            self.row_of(count.unwrap_or(1).saturating_sub(1), 0);
+                        return Some(UpdateCommand::Render);
+                    }
+                    Input::First(_) => {
+                        self.is_following = false;
+                        self.viewport.scroll_y = 0;
+                        return Some(UpdateCommand::Render);
+                    }
+                    Input::Last(_) => {
+                        self.viewport.scroll_y = self.max_scroll_y();
+                        return Some(UpdateCommand::Render);
+                    }
+                    Input::Key {
+                        key,
+                        count,
+                        was_pending,
+                    } => match keymap.logs_action(&key) {
+                        Some(action) if action.is_countable() => (action, count.unwrap_or(1)),
+                        // Other actions drop the count
+                        action => {
+                            return action
+                                .and_then(|action| self.run_action(action, 1, log))
+                                .or(was_pending.then_some(UpdateCommand::Render));
+                        }
+                    },
+                };
+                self.run_action(action, count, log)
+            }
+            Mode::Search(search_string) => match event {
                 Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Left | BareKey::Char('h'),
+                    bare_key: BareKey::Esc | BareKey::Enter,
                     ..
                 }) => {
-                    self.scroll_x = self.scroll_x.saturating_sub(1);
+                    self.mode = Mode::Normal;
                     Some(UpdateCommand::Render)
                 }
                 Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Right | BareKey::Char('l'),
+                    bare_key: BareKey::Tab,
                     ..
                 }) => {
-                    self.scroll_x = (self.scroll_x + 1).min(1);
+                    self.case_sensitivity = self.case_sensitivity.next();
+                    let search_string = search_string.clone();
+                    self.search(&search_string, log);
                     Some(UpdateCommand::Render)
                 }
                 Event::Key(KeyWithModifier {
-                    bare_key: BareKey::PageDown | BareKey::Char('d'),
+                    bare_key: BareKey::Char(c),
                     ..
                 }) => {
-                    if let Some(height) = self.screen_height {
-                        self.scroll_y = self
-                            .scroll_y
-                            .saturating_add(height / 2)
-                            .min(self.logs.len().saturating_sub(1));
-                    }
+                    search_string.push(c);
+                    let search_string = search_string.clone();
+                    self.search(&search_string, log);
                     Some(UpdateCommand::Render)
                 }
+                _ => None,
+            },
+            Mode::Filter(query) => match event {
                 Event::Key(KeyWithModifier {
-                    bare_key: BareKey::PageUp | BareKey::Char('u'),
+                    bare_key: BareKey::Esc | BareKey::Enter,
                     ..
                 }) => {
-                    if let Some(height) = self.screen_height {
-                        self.scroll_y = self.scroll_y.saturating_sub(height / 2);
-                    }
+                    self.mode = Mode::Normal;
                     Some(UpdateCommand::Render)
                 }
                 Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Char('f'),
+                    bare_key: BareKey::Tab,
                     ..
                 }) => {
-                    if let Some(height) = self.screen_height {
-                        self.scroll_y = self
-                            .scroll_y
-                            .saturating_add(height)
-                            .min(self.logs.len().saturating_sub(1));
-                    }
+                    self.case_sensitivity = self.case_sensitivity.next();
+                    let query = query.clone();
+                    self.set_filter(&query, log);
                     Some(UpdateCommand::Render)
                 }
                 Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Char('b'),
+                    bare_key: BareKey::Backspace,
                     ..
                 }) => {
-                    if let Some(height) = self.screen_height {
-                        self.scroll_y = self.scroll_y.saturating_sub(height);
-                    }
+                    query.pop();
+                    let query = query.clone();
+                    self.set_filter(&query, log);
                     Some(UpdateCommand::Render)
                 }
                 Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Char('/'),
+                    bare_key: BareKey::Char(c),
                     ..
                 }) => {
-                    self.mode = Mode::Search(String::new());
+                    query.push(c);
+                    let query = query.clone();
+                    self.set_filter(&query, log);
                     Some(UpdateCommand::Render)
                 }
-                Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Char('n'),
-                    ..
-                }) => {
-                    if let Some(current_index) = &mut self.search_result.current_index {
-                        *current_index = current_index
-                            .saturating_add(1)
-                            .min(self.search_result.matches.len().saturating_sub(1));
-                        self.scroll_y = self.search_result.matches[*current_index].0;
-                        Some(UpdateCommand::Render)
-                    } else {
-                        None
-                    }
-                }
-                Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Char('N'),
-                    ..
-                }) => {
-                    if let Some(current_index) = &mut self.search_result.current_index {
-                        *current_index = current_index.saturating_sub(1);
-                        self.scroll_y = self.search_result.matches[*current_index].0;
-                        Some(UpdateCommand::Render)
-                    } else {
-                        None
-                    }
-                }
                 _ => None,
             },
-            Mode::Search(search_string) => match event {
-                Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Esc | BareKey::Enter,
-                    ..
-                }) => {
-                    self.mode = Mode::Normal;
-                    Some(UpdateCommand::Render)
+        }
+    }
+
+    /// Runs an action, motions get repeated `count` times
+    fn run_action(
+        &mut self,
+        action: LogsAction,
+        count: usize,
+        log: &LogView,
+    ) -> Option<UpdateCommand> {
+        match action {
+            LogsAction::Exit => Some(UpdateCommand::ExitScreen),
+            LogsAction::Down => {
+                self.viewport.scroll_down(count, self.visual_rows().len());
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::Up => {
+                self.is_following = false;
+                self.viewport.scroll_up(count);
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::ToggleFollow => {
+                self.is_following = !self.is_following;
+                if self.is_following {
+                    self.viewport.scroll_y = self.max_scroll_y();
                 }
-                Event::Key(KeyWithModifier {
-                    bare_key: BareKey::Char(c),
-                    ..
-                }) => {
-                    search_string.push(c);
-                    self.search_result.matches = self
-                        .logs
-                        .iter()
-                        .enumerate()
-                        .flat_map(|(idx, line)| {
-                            line.match_indices(search_string.as_str())
-                                .map(|(start_idx, needle)| (idx, start_idx..(needle.len())))
-                                .collect::<Vec<(usize, Range<usize>)>>()
-                        })
-                        .collect();
-                    if let [head, ..] = &self.search_result.matches[..] {
-                        self.scroll_y = head.0;
-                        self.search_result.current_index = Some(0);
-                    } else {
-                        self.search_result.current_index = None;
-                    }
-                    Some(UpdateCommand::Render)
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::ToggleTimestamps => {
+                self.timestamps = match self.timestamps {
+                    Timestamps::Hidden => Timestamps::Relative,
+                    Timestamps::Relative => Timestamps::Absolute,
+                    Timestamps::Absolute => Timestamps::Hidden,
+                };
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::ToggleWrap => {
+                let line = self
+                    .visual_rows()
+                    .get(self.viewport.scroll_y)
+                    .map(|(line, _)| *line);
+                self.is_wrapping = !self.is_wrapping;
+                self.viewport.scroll_x = 0;
+                self.invalidate_rows();
+                if let Some(line) = line {
+                    self.viewport.scroll_y = self.row_of(line, 0);
                 }
-                _ => None,
-            },
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::ScrollLeft => {
+                self.viewport.scroll_left(SCROLL_X_STEP * count);
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::ScrollRight => {
+                self.viewport
+                    .scroll_right(SCROLL_X_STEP * count, self.max_scroll_x());
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::LineStart => {
+                self.viewport.scroll_x = 0;
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::LineEnd => {
+                self.viewport.scroll_x = self.max_scroll_x();
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::HalfPageDown => {
+                let rows = self.viewport.half_page() * count;
+                self.viewport.scroll_down(rows, self.visual_rows().len());
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::HalfPageUp => {
+                self.is_following = false;
+                self.viewport.scroll_up(self.viewport.half_page() * count);
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::PageDown => {
+                let rows = self.viewport.page() * count;
+                self.viewport.scroll_down(rows, self.visual_rows().len());
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::PageUp => {
+                self.is_following = false;
+                self.viewport.scroll_up(self.viewport.page() * count);
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::Search => {
+                self.mode = Mode::Search(String::new());
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::Filter => {
+                self.mode = Mode::Filter(String::new());
+                self.set_filter("", log);
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::ToggleFilter => {
+                let line = self.top_line();
+                let filter = self.filter.as_mut()?;
+                filter.is_enabled = !filter.is_enabled;
+                self.invalidate_rows();
+                self.scroll_to_top_line(line);
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::NextMatch => {
+                let current_index = self.search_result.current_index?;
+                self.jump_to_match(
+                    current_index
+                        .saturating_add(count)
+                        .min(self.search_result.matches.len().saturating_sub(1)),
+                );
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::TogglePackageLog => {
+                self.source.test.as_ref()?;
+                self.show_package_log = !self.show_package_log;
+                Some(UpdateCommand::Render)
+            }
+            LogsAction::CopyLog => Some(UpdateCommand::CopyToClipboard(
+                log.iter().map(ansi::strip).collect(),
+            )),
+            LogsAction::OpenPager => Some(UpdateCommand::OpenPager),
+            LogsAction::SaveLog => Some(UpdateCommand::SaveLog(
+                log.iter().map(ansi::strip).collect(),
+            )),
+            LogsAction::PreviousMatch => {
+                let current_index = self.search_result.current_index?;
+                self.jump_to_match(current_index.saturating_sub(count));
+                Some(UpdateCommand::Render)
+            }
         }
     }
 
-    pub(crate) fn render(&mut self, rows: usize, cols: usize) {
-        self.screen_width = Some(cols);
-        self.screen_height = Some(rows - 1);
-        for (y, item) in self
-            .logs
+    pub(crate) fn scroll_to_line(&mut self, line: usize, log: &LogView, generation: u64) {
+        self.sync(log, generation);
+        self.viewport.scroll_y = self.row_of(line, 0);
+    }
+
+    /// A line as shown, diff lines have their tabs expanded
+    fn styled_line(&self, index: usize, line: &str) -> StyledLine {
+        let styled_line = StyledLine::parse(line);
+        match self.diff_lines.get(index) {
+            Some(Some(_)) => styled_line.expand_tabs(diff::TAB_WIDTH),
+            _ => styled_line,
+        }
+    }
+
+    /// Hides the lines not containing the query, an empty query shows all lines again
+    fn set_filter(&mut self, query: &str, log: &LogView) {
+        let line = self.top_line();
+        self.filter = (!query.is_empty()).then(|| LineFilter {
+            query: query.to_owned(),
+            is_matching: log
+                .iter()
+                .enumerate()
+                .map(|(index, line)| {
+                    self.case_sensitivity
+                        .contains(&self.styled_line(index, line).text, query)
+                })
+                .collect(),
+            is_enabled: true,
+        });
+        self.invalidate_rows();
+        self.scroll_to_top_line(line);
+    }
+
+    /// Whether a line passes the filter
+    fn is_shown(&self, index: usize) -> bool {
+        self.filter
+            .as_ref()
+            .filter(|filter| filter.is_enabled)
+            .is_none_or(|filter| filter.is_matching.get(index).copied().unwrap_or(true))
+    }
+
+    /// Number of lines hidden by the filter
+    fn hidden_count(&self) -> usize {
+        self.filter
+            .as_ref()
+            .filter(|filter| filter.is_enabled)
+            .map_or(0, |filter| {
+                filter
+                    .is_matching
+                    .iter()
+                    .filter(|is_matching| !**is_matching)
+                    .count()
+            })
+    }
+
+    /// The line shown in the first row
+    fn top_line(&self) -> usize {
+        self.visual_rows()
+            .get(self.viewport.scroll_y)
+            .map_or(0, |(line, _)| *line)
+    }
+
+    /// Scrolls a line to the first row after the shown lines changed, or the next line shown if
+    /// it's hidden
+    fn scroll_to_top_line(&mut self, line: usize) {
+        self.viewport.scroll_y = self.row_of(line, 0);
+    }
+
+    fn search(&mut self, search_string: &str, log: &LogView) {
+        self.search_result.query = search_string.to_owned();
+        if log.len() >= WORKER_MIN_LINES && !search_string.is_empty() {
+            self.search_in_worker(log);
+            return;
+        }
+        self.search_result.pending_id = None;
+        self.search_result.matches = log
             .iter()
-            .skip(self.scroll_y)
-            .take(self.screen_height.unwrap())
             .enumerate()
+            .flat_map(|(idx, line)| {
+                self.case_sensitivity
+                    .find_matches(&self.styled_line(idx, line).text, search_string)
+                    .into_iter()
+                    .map(move |range| (idx, range))
+            })
+            .collect();
+        if self.search_result.matches.is_empty() {
+            self.search_result.current_index = None;
+        } else {
+            self.jump_to_match(0);
+        }
+    }
+
+    /// Sends up to `chunks` chunks of the lines the worker doesn't have yet, once the log is long
+    /// enough to be searched there. The lines go as they are and the worker strips their styling.
+    fn index_in_worker(&mut self, log: &LogView, chunks: usize) {
+        if log.len() < WORKER_MIN_LINES {
+            return;
+        }
+        for _ in 0..chunks {
+            if self.indexed_lines >= log.len() {
+                break;
+            }
+            let lines = self.indexed_lines..(self.indexed_lines + INDEX_CHUNK_LINES).min(log.len());
+            let diff_lines = lines
+                .clone()
+                .map(|index| self.diff_lines.get(index).is_some_and(Option::is_some))
+                .collect();
+            let raw_lines = lines.clone().map(|index| log[index].to_owned()).collect();
+            search_worker::index(self.indexed_lines, raw_lines, diff_lines);
+            self.indexed_lines = lines.end;
+        }
+    }
+
+    /// Searches the lines sent to the worker there. Lines arriving until the matches are back get
+    /// searched as they arrive.
+    fn search_in_worker(&mut self, log: &LogView) {
+        self.index_in_worker(log, usize::MAX);
+        self.search_result.matches.clear();
+        self.search_result.current_index = None;
+        self.search_result.pending_id = Some(search_worker::search(
+            &self.search_result.query,
+            self.case_sensitivity,
+            self.indexed_lines,
+        ));
+    }
+
+    /// Takes the matches found by the worker, returning whether they're of the pending search
+    pub(crate) fn on_search_matches(&mut self, search_matches: SearchMatches) -> bool {
+        if self.search_result.pending_id != Some(search_matches.id) {
+            return false;
+        }
+        self.search_result.pending_id = None;
+        // Only lines after the searched ones got matched in the meantime
+        let newer_matches = mem::take(&mut self.search_result.matches);
+        self.search_result.matches = search_matches.matches;
+        self.search_result.matches.extend(
+            newer_matches
+                .into_iter()
+                .filter(|(line, _)| *line >= search_matches.line_count),
+        );
+        if !self.search_result.matches.is_empty() {
+            self.jump_to_match(0);
+        }
+        true
+    }
+
+    /// Selects a match, scrolling it into view
+    fn jump_to_match(&mut self, index: usize) {
+        let (line, range) = &self.search_result.matches[index];
+        let (line, range) = (*line, range.clone());
+        self.search_result.current_index = Some(index);
+        self.viewport.scroll_y = self.row_of(line, range.start);
+        let width = self.viewport.width();
+        if !self.is_wrapping
+            && (range.start < self.viewport.scroll_x || range.end > self.viewport.scroll_x + width)
         {
-            print_text_with_coordinates(Text::new(item), 0, y, Some(cols), Some(1));
+            self.viewport.scroll_x = range.start.saturating_sub(width / 4);
+        }
+    }
+
+    /// Screen rows as line index and column range. Each line takes a single row unless wrapping.
+    fn visual_rows(&self) -> &[(usize, Range<usize>)] {
+        &self.rows
+    }
+
+    /// Splits the lines into rows again, e.g. once wrapping or the filter changed
+    fn invalidate_rows(&mut self) {
+        self.rows.clear();
+        self.rows_line_count = 0;
+        self.update_rows();
+    }
+
+    /// Splits the lines that arrived since the last call into rows, or all lines once the width
+    /// they wrap at changed
+    fn update_rows(&mut self) {
+        let width = match self.viewport.width() {
+            width if self.is_wrapping && width > 0 => width,
+            _ => usize::MAX,
+        };
+        if width != self.rows_width {
+            self.rows.clear();
+            self.rows_line_count = 0;
+            self.rows_width = width;
+        }
+        for index in self.rows_line_count..self.line_lengths.len() {
+            if !self.is_shown(index) {
+                continue;
+            }
+            let length = self.line_lengths[index];
+            let row_count = length.div_ceil(width).max(1);
+            self.rows.extend((0..row_count).map(|row| {
+                let start = row.saturating_mul(width);
+                (index, start..start.saturating_add(width).min(length))
+            }));
         }
+        self.rows_line_count = self.line_lengths.len();
+    }
+
+    /// The row showing a column of a line, or the next line shown if it's filtered out
+    fn row_of(&self, line: usize, column: usize) -> usize {
+        let rows = self.visual_rows();
+        rows.iter()
+            .position(|(row_line, range)| *row_line == line && range.contains(&column))
+            .or_else(|| rows.iter().position(|(row_line, _)| *row_line >= line))
+            .unwrap_or(rows.len().saturating_sub(1))
+    }
 
+    /// Scroll offset showing the last page of rows
+    fn max_scroll_y(&self) -> usize {
+        self.viewport.max_scroll_y(self.visual_rows().len())
+    }
+
+    /// Rows shown on the screen
+    fn visible_rows(&self) -> &[(usize, Range<usize>)] {
+        let rows = self.visual_rows();
+        &rows[self.viewport.visible_range(rows.len())]
+    }
+
+    /// Scroll offset at which the end of the longest visible line reaches the right edge
+    fn max_scroll_x(&self) -> usize {
+        if self.is_wrapping {
+            return 0;
+        }
+        self.visible_rows()
+            .iter()
+            .map(|(_, range)| range.end)
+            .max()
+            .unwrap_or(0)
+            .saturating_sub(self.viewport.width())
+    }
+
+    /// Renders the log below a title naming its source, badged with the source's `status`
+    pub(crate) fn render(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        log: &LogView,
+        log_times: &LogTimes,
+        status: Option<(String, usize)>,
+        generation: u64,
+    ) {
+        self.sync(log, generation);
+        // The title takes the first row and the status the last one, the timestamps the columns
+        // on the left
+        let x = self.timestamps.width().min(cols / 2);
+        self.viewport
+            .resize(cols.saturating_sub(x), rows.saturating_sub(2));
+        self.update_rows();
+        self.viewport.clamp_scroll_y(self.visual_rows().len());
+        self.viewport.scroll_x = self.viewport.scroll_x.min(self.max_scroll_x());
+        let theme = theme::current();
+        let breadcrumb = self.source.breadcrumb();
+        let title = match status {
+            Some((label, color)) => {
+                let badge_start = breadcrumb.chars().count() + 2;
+                Text::new(format!("{}  [{}]", breadcrumb, label))
+                    .color_range(color, badge_start..badge_start + label.chars().count() + 2)
+            }
+            None => Text::new(format!("{}  [gone]", breadcrumb)),
+        };
+        print_text_with_coordinates(title, 0, 0, Some(cols), Some(1));
+        let first_time = log_times.first().unwrap_or_default();
+        for (y, (line_index, row_range)) in self.visible_rows().iter().cloned().enumerate() {
+            // Rows continuing a wrapped line leave the timestamp to the line's first row
+            let time = log_times
+                .get(log.log, line_index)
+                .filter(|_| row_range.start == 0 && x > 0);
+            if let Some(time) = time {
+                print_text_with_coordinates(
+                    Text::new(self.timestamps.format(time, first_time)),
+                    0,
+                    y + 1,
+                    Some(x),
+                    Some(1),
+                );
+            }
+            let styled_line = self.styled_line(line_index, &log[line_index]);
+            let offset = row_range.start + self.viewport.scroll_x;
+            let row_text = styled_line
+                .text
+                .trim_end_matches('\n')
+                .chars()
+                .skip(offset)
+                .take(row_range.end.saturating_sub(offset))
+                .collect::<String>();
+            // Color of each char of the row, search matches take precedence over the log's colors
+            let row_length = row_text.chars().count();
+            let mut char_colors = vec![None; row_length];
+            let mut color_chars = |color: usize, range: &Range<usize>| {
+                let start = range.start.saturating_sub(offset);
+                let end = range.end.min(offset + row_length).saturating_sub(offset);
+                for char_color in char_colors.iter_mut().take(end).skip(start) {
+                    *char_color = Some(color);
+                }
+            };
+            // The log's own colors take precedence over the diff's
+            let diff_color = self.diff_lines[line_index].and_then(|diff_line| match diff_line {
+                DiffLine::Header => Some(theme.emphasis),
+                DiffLine::Expected => Some(theme.fail),
+                DiffLine::Actual => Some(theme.pass),
+                DiffLine::Hunk => Some(theme.accent),
+                DiffLine::Context => None,
+            });
+            if let Some(diff_color) = diff_color {
+                let text = styled_line.text.trim_end();
+                let start = text.chars().take_while(|c| c.is_whitespace()).count();
+                color_chars(diff_color, &(start..text.chars().count()));
+            }
+            for (color, range) in &styled_line.colors {
+                color_chars(*color, range);
+            }
+            self.search_result
+                .matches
+                .iter()
+                .enumerate()
+                .filter(|(_, (match_line, _))| *match_line == line_index)
+                .for_each(|(match_index, (_, range))| {
+                    let color = if self.search_result.current_index == Some(match_index) {
+                        theme.current_match
+                    } else {
+                        theme.search_match
+                    };
+                    color_chars(color, range);
+                });
+            let text = (0..4).fold(Text::new(row_text), |text, color| {
+                let indices = char_colors
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, char_color)| **char_color == Some(color))
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+                if indices.is_empty() {
+                    text
+                } else {
+                    text.color_indices(color, indices)
+                }
+            });
+            print_text_with_coordinates(text, x, y + 1, Some(cols.saturating_sub(x)), Some(1));
+        }
+
+        let match_indicator = match (self.search_result.current_index, &self.mode) {
+            _ if self.search_result.pending_id.is_some() => "  searching…".to_owned(),
+            (Some(current_index), _) => format!(
+                "  match {}/{}",
+                current_index + 1,
+                self.search_result.matches.len()
+            ),
+            (None, Mode::Search(search_string)) if !search_string.is_empty() => {
+                "  no matches".to_owned()
+            }
+            (None, _) => String::new(),
+        };
         let bottom_text = match &self.mode {
-            Mode::Normal => Text::new(":"),
-            Mode::Search(search_string) => Text::new(format!("/{}", search_string)),
+            Mode::Normal => {
+                let mut text = format!(
+                    ":{}{}",
+                    self.count_prefix.pending().unwrap_or_default(),
+                    match_indicator
+                );
+                if self.is_following {
+                    text.push_str("  [F] following");
+                }
+                if self.is_wrapping {
+                    text.push_str("  [w] wrap");
+                }
+                if self.show_package_log {
+                    text.push_str("  [p] package log below");
+                }
+                match &self.filter {
+                    Some(filter) if filter.is_enabled => text.push_str(&format!(
+                        "  [&] {}: {} lines hidden",
+                        filter.query,
+                        self.hidden_count()
+                    )),
+                    Some(_) => text.push_str("  [a] filter off"),
+                    None => (),
+                }
+                match self.timestamps {
+                    Timestamps::Hidden => (),
+                    Timestamps::Relative => text.push_str("  [t] relative times"),
+                    Timestamps::Absolute => text.push_str("  [t] UTC times"),
+                }
+                Text::new(text)
+            }
+            Mode::Search(search_string) => Text::new(format!(
+                "/{}{}  [Tab] {}  in {}",
+                search_string,
+                match_indicator,
+                self.case_sensitivity.label(),
+                breadcrumb
+            )),
+            Mode::Filter(query) => Text::new(format!(
+                "&{}  {} lines hidden  [Enter] done, [Tab] {}",
+                query,
+                self.hidden_count(),
+                self.case_sensitivity.label()
+            )),
+        };
+
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_package_log_after_the_test_log_and_the_separator() {
+        let (log, package_log) = (["a\n".to_owned()], ["b\n".to_owned(), "c\n".to_owned()]);
+        let log = LogView {
+            log: &log,
+            package_log: Some(("──── output of package pkg ────\n".to_owned(), &package_log)),
+        };
+        assert_eq!(log.len(), 4);
+        assert_eq!(log.package_log_start(), Some(1));
+        assert_eq!(&log[2], "b\n");
+        assert_eq!(log.get(4), None);
+        assert_eq!(
+            log.iter().collect::<Vec<_>>(),
+            ["a\n", "──── output of package pkg ────\n", "b\n", "c\n"]
+        );
+    }
+
+    #[test]
+    fn drops_the_matches_of_a_log_searched_before_another_one() {
+        let lines = vec!["match\n".to_owned(); WORKER_MIN_LINES];
+        let log = LogView {
+            log: &lines,
+            package_log: None,
+        };
+        let source = |test: &str| LogSource {
+            package: Name::new("example.com/api"),
+            test: Some(Name::new(test)),
+            attempt: 0,
         };
+        let mut first_screen = LogsScreen::new(source("TestA"), CaseSensitivity::Smart);
+        first_screen.sync(&log, 0);
+        first_screen.search("match", &log);
+        let first_id = first_screen
+            .search_result
+            .pending_id
+            .expect("Search should run in the worker");
 
-        print_text_with_coordinates(bottom_text, 0, rows - 1, Some(cols), Some(1));
+        let mut second_screen = LogsScreen::new(source("TestB"), CaseSensitivity::Smart);
+        second_screen.sync(&log, 0);
+        second_screen.search("match", &log);
+        let first_matches = SearchMatches {
+            id: first_id,
+            line_count: WORKER_MIN_LINES,
+            matches: vec![(WORKER_MIN_LINES + 10, 0..5)],
+        };
+        assert!(!second_screen.on_search_matches(first_matches));
+        assert!(second_screen.search_result.matches.is_empty());
+        assert!(second_screen.search_result.pending_id.is_some());
     }
 }
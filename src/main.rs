@@ -1,39 +1,136 @@
-use logs_screen::LogsScreen;
+use benchmark::Benchmark;
+use benchmarks_screen::BenchmarksScreen;
+use comparison_screen::ComparisonScreen;
+use config::{Config, AUTO_MODULE_PREFIX};
+use coverage_screen::CoverageScreen;
+use errors_screen::ErrorsScreen;
+use export::ExportFormat;
+use finder_screen::FinderScreen;
+use fuzz::FuzzProgress;
+use grep_screen::GrepScreen;
+use help_screen::HelpScreen;
+use history_screen::{HistoryScreen, TestRun, ViewedRun};
+use keymap::Keymap;
+use lint::Lint;
+use lint_screen::LintScreen;
+use log_times::LogTimes;
+use logs_screen::{LogSource, LogsScreen};
+use name::Name;
+use package_picker::PackagePicker;
+use parser::{ModelChange, TestLine, TestStream};
+use persistence::{SaveScheduler, SavedState, State};
+use pipe_command::PipeCommand;
+use plain_output::{InputFormat, PlainParser};
+use render_throttle::RenderThrottle;
+use run_screen::RunScreen;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use slowest_screen::SlowestScreen;
+use source_location::{SourceLocation, SourceOpener};
+use spinner::Spinner;
+use status_publisher::StatusPublisher;
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem,
+    path::PathBuf,
+};
+use stream::Stream;
 use strum::AsRefStr;
-use tests_screen::TestsScreen;
+use summary::Summary;
+use tabs::Tab;
+use tests_screen::{StatusBar, TestsScreen};
+use theme::{Theme, ThemeConfig};
+use triage_screen::TriageScreen;
+use watcher::Watcher;
 use zellij_tile::prelude::*;
 
+mod ansi;
+mod benchmark;
+mod benchmarks_screen;
+mod clipboard;
+mod comparison_screen;
+mod config;
+mod count_prefix;
+mod cover_profile;
+mod coverage_screen;
+mod diff;
+mod errors_screen;
+mod export;
+mod finder_screen;
+mod fuzz;
+mod grep_screen;
+mod help_screen;
+mod history_screen;
+mod keymap;
+mod lint;
+mod lint_screen;
+mod log_limit;
+mod log_times;
 mod logs_screen;
+mod name;
+mod output_scope;
+mod package_picker;
+mod pager;
+mod parser;
+mod persistence;
+mod pipe_command;
+mod plain_output;
+mod render_throttle;
+mod run_screen;
+mod runner;
+mod search;
+mod search_worker;
+mod slowest_screen;
+mod source_location;
+mod spinner;
+mod status_publisher;
+mod stream;
+mod summary;
+mod tabs;
 mod tests_screen;
+mod theme;
+mod timer;
+mod timestamp;
+mod triage_screen;
+mod viewport;
+mod watcher;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, AsRefStr)]
-#[serde(rename_all = "lowercase")]
-enum Action {
-    Start,
-    Run,
-    Output,
-    Pass,
-    Fail,
-    Skip,
-}
-
-#[derive(Debug, Clone, Copy, AsRefStr, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Copy, AsRefStr, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 #[strum(serialize_all = "lowercase")]
 enum TestResult {
     #[default]
     Skip,
+    #[strum(serialize = "build-fail")]
+    BuildFail,
+    /// A failure caused by a data race detected by `-race`
+    Race,
+    /// A failure caused by the test binary panicking once the `-timeout` elapsed
+    Timeout,
     Fail,
     Pass,
+    /// A package skipped for having no test files
+    #[strum(serialize = "no-tests")]
+    NoTests,
 }
 
 impl TestResult {
+    /// Whether the result counts as a failure, including build failures, data races and timeouts
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            TestResult::Fail | TestResult::BuildFail | TestResult::Race | TestResult::Timeout
+        )
+    }
+
     pub fn marker_color(&self) -> usize {
+        let theme = theme::current();
         match self {
-            TestResult::Pass => 2,
-            TestResult::Fail => 0,
-            TestResult::Skip => 1,
+            TestResult::Pass => theme.pass,
+            TestResult::Fail => theme.fail,
+            TestResult::Skip => theme.skip,
+            TestResult::BuildFail | TestResult::Race | TestResult::Timeout => theme.emphasis,
+            TestResult::NoTests => theme.running,
         }
     }
 
@@ -42,206 +139,2544 @@ impl TestResult {
             TestResult::Pass => 'P',
             TestResult::Fail => 'F',
             TestResult::Skip => '?',
+            TestResult::BuildFail => 'B',
+            TestResult::Race => 'R',
+            TestResult::Timeout => 'T',
+            TestResult::NoTests => '-',
         }
     }
 }
 
-impl TryFrom<Action> for TestResult {
-    type Error = String;
+/// Whether a test is still in flight, as tests running with `t.Parallel()` get paused and continued
+#[derive(Debug, Clone, Copy, AsRefStr, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
+enum RunState {
+    #[default]
+    Running,
+    Paused,
+    Finished,
+    /// The run was aborted before the test finished
+    Aborted,
+}
 
-    fn try_from(value: Action) -> Result<Self, Self::Error> {
-        match value {
-            Action::Pass => Ok(TestResult::Pass),
-            Action::Fail => Ok(TestResult::Fail),
-            Action::Skip => Ok(TestResult::Skip),
-            action => Err(format!(
-                "Action `{}` is not a valid TestResult",
-                action.as_ref()
-            )),
+impl RunState {
+    pub fn marker_color(&self) -> usize {
+        let theme = theme::current();
+        match self {
+            RunState::Running | RunState::Paused => theme.running,
+            RunState::Finished => theme.pass,
+            RunState::Aborted => theme.skip,
+        }
+    }
+
+    pub fn marker_char(&self) -> char {
+        match self {
+            RunState::Running => '▸',
+            RunState::Paused => '‖',
+            RunState::Finished => ' ',
+            RunState::Aborted => '⊘',
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all(deserialize = "PascalCase"))]
-struct TestLine {
-    action: Option<Action>,
-    package: Option<String>,
-    test: Option<String>,
-    output: Option<String>,
-    elapsed: Option<f64>,
+/// Status of a package computed from its tests, shown while `go test` hasn't reported the
+/// package's own result, e.g. when the stream got cut off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DerivedStatus {
+    Running,
+    /// The run was aborted before the package finished
+    Aborted,
+    Pass,
+    Fail,
+}
+
+/// What a package starting again while it has results from before means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Restart {
+    /// Another run of the package, e.g. with several runs piped in, see [`start_package_attempt`]
+    Attempt,
+    /// A rerun started from the plugin, whose tests got cleared before
+    Rerun,
+    /// Piped in with the `gotestsum` format, where `--rerun-fails` starts a failed package again to
+    /// retry its failed tests
+    RetryFailed,
 }
 
-#[derive(Debug, Clone, Default)]
+/// A pipe payload that couldn't be processed
+#[derive(Debug, Clone)]
+struct PipeError {
+    line: String,
+    message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct Package {
-    name: String,
+    name: Name,
     result: Option<TestResult>,
     elapsed: Option<f64>,
     tests: Vec<TestCase>,
     log: Vec<String>,
+    #[serde(default)]
+    log_times: LogTimes,
+    benchmarks: Vec<Benchmark>,
+    /// Percentage of statements covered, reported when running with `-cover`
+    coverage: Option<f32>,
+    /// Set by build actions until the package's `start` action arrives
+    is_building: bool,
+    /// Timestamp of the `start` action in seconds since the Unix epoch
+    started_at: Option<f64>,
+    /// Set when the run was aborted before the package finished
+    #[serde(default)]
+    is_aborted: bool,
+    /// Message of the panic the test binary exits with once the `-timeout` elapsed, e.g. `test
+    /// timed out after 10m0s`
+    #[serde(default)]
+    timeout: Option<String>,
+    /// The test that was running when the timeout elapsed
+    #[serde(default)]
+    timed_out_test: Option<String>,
+    /// Counters of `go test -fuzz`, whose status lines don't go to the logs
+    #[serde(default)]
+    fuzz_progress: Option<FuzzProgress>,
+    /// How many times the package started before in the current results, e.g. when several runs
+    /// are piped in without starting a new run, see [`attempt_suffix`]
+    #[serde(default)]
+    attempt: usize,
+    /// Set from a start retrying the package's failed tests until it finishes again
+    #[serde(default)]
+    is_retrying: bool,
+    /// Counts of the package's tests and subtests, recounted along with the run's summary
+    #[serde(skip)]
+    summary: Summary,
+    /// Number of tests started so far per name, numbering the attempts of the next ones
+    #[serde(skip)]
+    attempts: HashMap<Name, usize>,
 }
 
 impl Package {
-    pub fn new<T: Into<String>>(name: T) -> Self {
+    pub fn new<T: Into<Name>>(name: T) -> Self {
         Self {
             name: name.into(),
             ..Self::default()
         }
     }
+
+    /// Pass once all tests finished without failures, fail once all finished and any failed and
+    /// running while a test is in flight or no test finished yet
+    pub fn derived_status(&self) -> DerivedStatus {
+        let tests = TestCase::flatten(&self.tests);
+        if self.is_aborted {
+            DerivedStatus::Aborted
+        } else if tests.is_empty() || tests.iter().any(|test| test.result.is_none()) {
+            DerivedStatus::Running
+        } else if tests
+            .iter()
+            .any(|test| test.result.is_some_and(|result| result.is_failure()))
+        {
+            DerivedStatus::Fail
+        } else {
+            DerivedStatus::Pass
+        }
+    }
+
+    /// The package's result, falling back to the derived status
+    pub fn result_or_derived(&self) -> Option<TestResult> {
+        self.result.or(match self.derived_status() {
+            DerivedStatus::Running | DerivedStatus::Aborted => None,
+            DerivedStatus::Pass => Some(TestResult::Pass),
+            DerivedStatus::Fail => Some(TestResult::Fail),
+        })
+    }
+
+    /// Elapsed times of the finished top level tests summed up, `None` until one finished
+    pub fn test_time(&self) -> Option<f64> {
+        self.tests
+            .iter()
+            .filter_map(|test| test.elapsed)
+            .reduce(|total, elapsed| total + elapsed)
+    }
+
+    /// Adds a benchmark result, replacing an earlier result of the same benchmark
+    pub fn add_benchmark(&mut self, benchmark: Benchmark) {
+        match self
+            .benchmarks
+            .iter_mut()
+            .find(|existing| existing.name == benchmark.name)
+        {
+            Some(existing) => *existing = benchmark,
+            None => self.benchmarks.push(benchmark),
+        }
+    }
+
+    /// Finds a test or subtest by its full go test name, e.g. `TestFoo/case_a`
+    pub fn find_test_mut(&mut self, name: &str) -> Option<&mut TestCase> {
+        TestCase::find_in_mut(&mut self.tests, name)
+    }
+
+    /// The package's log followed by the logs of all its tests and subtests, each with its
+    /// timestamps
+    pub fn logs_mut(&mut self) -> Vec<(&mut Vec<String>, &mut LogTimes)> {
+        let mut logs = vec![(&mut self.log, &mut self.log_times)];
+        TestCase::collect_logs_mut(&mut self.tests, &mut logs);
+        logs
+    }
+
+    /// Detects the panic of an elapsed `-timeout` in an output line of the package or its tests,
+    /// and the test named by the lines following it
+    fn scan_timeout(&mut self, output: &str) {
+        if let Some(message) = timeout_message(output) {
+            self.timeout = Some(message.to_owned());
+        } else if self.timeout.is_some() && self.timed_out_test.is_none() {
+            self.timed_out_test = timed_out_test(output);
+        }
+    }
+
+    /// Attempt of a test starting now, the number of tests of the same name started before
+    fn next_attempt(&mut self, name: &Name) -> usize {
+        let count = self.attempts.entry(name.clone()).or_default();
+        *count += 1;
+        *count - 1
+    }
+
+    /// Counts the tests of each name again, once tests got removed or the package got restored
+    fn count_attempts(&mut self) {
+        self.attempts.clear();
+        for test in TestCase::flatten(&self.tests) {
+            *self.attempts.entry(test.name.clone()).or_default() += 1;
+        }
+    }
+
+    /// Adds a test under its parent test, or at the top level if the parent is unknown
+    pub fn add_test(&mut self, test_case: TestCase) {
+        let parent = test_case
+            .name
+            .rsplit_once('/')
+            .and_then(|(parent_name, _)| self.find_test_mut(parent_name));
+        match parent {
+            Some(parent) => parent.subtests.push(test_case),
+            None => self.tests.push(test_case),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Kind of a test function, told apart by the prefix of its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TestKind {
+    Test,
+    Benchmark,
+    Example,
+    Fuzz,
+}
+
+impl TestKind {
+    /// The kind of the top level function of a test or subtest name
+    pub fn of(test_name: &str) -> Self {
+        let function = test_name.split('/').next().unwrap_or(test_name);
+        if function.starts_with("Fuzz") {
+            TestKind::Fuzz
+        } else if function.starts_with("Example") {
+            TestKind::Example
+        } else if function.starts_with("Benchmark") {
+            TestKind::Benchmark
+        } else {
+            TestKind::Test
+        }
+    }
+
+    /// Badge shown next to the name of tests that aren't regular tests
+    pub fn badge(&self) -> Option<&'static str> {
+        match self {
+            TestKind::Test => None,
+            TestKind::Benchmark => Some("bench"),
+            TestKind::Example => Some("example"),
+            TestKind::Fuzz => Some("fuzz"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct TestCase {
-    name: String,
+    name: Name,
     result: Option<TestResult>,
     elapsed: Option<f64>,
     log: Vec<String>,
+    #[serde(default)]
+    log_times: LogTimes,
+    subtests: Vec<TestCase>,
+    state: RunState,
+    /// Set when the race detector reported a data race in the test's output
+    has_data_race: bool,
+    /// Message the test was skipped with, e.g. the argument of `t.Skip`
+    skip_reason: Option<String>,
+    /// First meaningful line explaining why the test failed
+    failure_message: Option<String>,
+    /// Timestamp of the `run` action in seconds since the Unix epoch
+    started_at: Option<f64>,
+    /// Path of the input a fuzz target failed with, written to its corpus by `go test -fuzz`
+    #[serde(default)]
+    fuzz_input: Option<String>,
+    /// How many times a test of the same name ran before in the package's run, e.g. with
+    /// `-count=2`, telling the results and logs of the attempts apart
+    #[serde(default)]
+    attempt: usize,
+    /// Results of the earlier attempts of a retried test, oldest first
+    #[serde(default)]
+    earlier_results: Vec<TestResult>,
+}
+
+impl TestCase {
+    pub fn new<T: Into<Name>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            result: None,
+            elapsed: None,
+            log: Vec::new(),
+            log_times: LogTimes::default(),
+            subtests: Vec::new(),
+            state: RunState::Running,
+            has_data_race: false,
+            skip_reason: None,
+            failure_message: None,
+            started_at: None,
+            fuzz_input: None,
+            attempt: 0,
+            earlier_results: Vec::new(),
+        }
+    }
+
+    /// Whether the test passed after failing in earlier attempts
+    pub fn is_flaky(&self) -> bool {
+        self.result == Some(TestResult::Pass)
+            && self
+                .earlier_results
+                .iter()
+                .any(|result| result.is_failure())
+    }
+
+    pub fn marker_color(&self) -> usize {
+        self.result
+            .map(|test_result| test_result.marker_color())
+            .unwrap_or_else(|| self.state.marker_color())
+    }
+
+    pub fn marker_char(&self) -> char {
+        self.result
+            .map(|test_result| test_result.marker_char())
+            .unwrap_or_else(|| self.state.marker_char())
+    }
+
+    pub fn kind(&self) -> TestKind {
+        TestKind::of(&self.name)
+    }
+
+    /// The last segment of the test name, e.g. `case_a` for `TestFoo/case_a`
+    pub fn short_name(&self) -> &str {
+        self.name
+            .rsplit_once('/')
+            .map(|(_, short_name)| short_name)
+            .unwrap_or(&self.name)
+    }
+
+    pub fn subtest_count(&self) -> usize {
+        self.subtests
+            .iter()
+            .map(|subtest| 1 + subtest.subtest_count())
+            .sum()
+    }
+
+    /// Number of failed tests among this test and its subtests
+    pub fn failed_count(&self) -> usize {
+        usize::from(self.result.is_some_and(|result| result.is_failure()))
+            + self
+                .subtests
+                .iter()
+                .map(TestCase::failed_count)
+                .sum::<usize>()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.result.is_none() && self.state == RunState::Running
+    }
+
+    /// Marks the tests and subtests that haven't finished as aborted
+    fn abort_unfinished(tests: &mut [TestCase]) {
+        for test in tests {
+            if test.result.is_none() {
+                test.state = RunState::Aborted;
+            }
+            Self::abort_unfinished(&mut test.subtests);
+        }
+    }
+
+    /// Whether the test took longer than the slow filter's threshold
+    pub fn is_slow(&self, slow_threshold: f64) -> bool {
+        self.elapsed.is_some_and(|elapsed| elapsed > slow_threshold)
+    }
+
+    /// Resets the test for running it again, keeping its subtests. Returns its previous result.
+    pub fn restart(&mut self, started_at: Option<f64>) -> Option<TestResult> {
+        self.elapsed = None;
+        self.log.clear();
+        self.log_times.clear();
+        self.state = RunState::Running;
+        self.has_data_race = false;
+        self.skip_reason = None;
+        self.failure_message = None;
+        self.fuzz_input = None;
+        self.started_at = started_at;
+        self.result.take()
+    }
+
+    /// Lists the tests together with all their nested subtests, parents first
+    pub fn flatten(tests: &[TestCase]) -> Vec<&TestCase> {
+        tests
+            .iter()
+            .flat_map(|test_case| {
+                let mut test_cases = vec![test_case];
+                test_cases.extend(Self::flatten(&test_case.subtests));
+                test_cases
+            })
+            .collect()
+    }
+
+    fn collect_logs_mut<'a>(
+        tests: &'a mut [TestCase],
+        logs: &mut Vec<(&'a mut Vec<String>, &'a mut LogTimes)>,
+    ) {
+        for test in tests {
+            logs.push((&mut test.log, &mut test.log_times));
+            Self::collect_logs_mut(&mut test.subtests, logs);
+        }
+    }
+
+    /// Finds a test by name, the latest attempt if it ran repeatedly
+    fn find_in_mut<'a>(tests: &'a mut [TestCase], name: &str) -> Option<&'a mut TestCase> {
+        let test = tests.iter_mut().rev().find(|test| {
+            name.strip_prefix(test.name.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })?;
+        if test.name == name {
+            Some(test)
+        } else {
+            Self::find_in_mut(&mut test.subtests, name)
+        }
+    }
+}
+
+/// The screen in front, which gets the input. Only one is open at a time, on top of the screen
+/// of the active tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    /// The screen of the active tab, see `switch_tab`
+    Tab,
+    Help,
+    Finder,
+    PackagePicker,
+    Logs,
+    Errors,
+    Grep,
+    Triage,
+    Lint,
+    Slowest,
+    Comparison,
+    Run,
 }
 
 #[derive(Debug, Default)]
 struct GoTestsPlugin {
     tests_screen: TestsScreen,
     logs_screen: Option<LogsScreen>,
+    summary: Summary,
+    watcher: Watcher,
+    errors_screen: Option<ErrorsScreen>,
+    errors: Vec<PipeError>,
+    history_screen: Option<HistoryScreen>,
+    run_screen: Option<RunScreen>,
+    comparison_screen: Option<ComparisonScreen>,
+    benchmarks_screen: Option<BenchmarksScreen>,
+    coverage_screen: Option<CoverageScreen>,
+    /// The screen selected in the tab bar, see `switch_tab`
+    active_tab: Tab,
+    /// Popup shown over the tests screen
+    finder_screen: Option<FinderScreen>,
+    package_picker: Option<PackagePicker>,
+    grep_screen: Option<GrepScreen>,
+    triage_screen: Option<TriageScreen>,
+    lint_screen: Option<LintScreen>,
+    slowest_screen: Option<SlowestScreen>,
+    help_screen: Option<HelpScreen>,
+    /// Previous test runs, oldest first
+    history: Vec<TestRun>,
+    /// Number of the run shown in the tests screen, counting from 1
+    run_number: usize,
+    /// A run from the history shown instead of the current run
+    viewed_run: Option<ViewedRun>,
+    config: Config,
+    keymap: Keymap,
+    /// Feedback about the last action, shown in the status bar until the next key press
+    notice: Option<String>,
+    source_opener: SourceOpener,
+    spinner: Spinner,
+    save_scheduler: SaveScheduler,
+    /// Text copied to the clipboard with OSC 52 on the next render
+    pending_clipboard: Option<String>,
+    plain_parser: PlainParser,
+    test_stream: TestStream,
+    /// Every stream results were piped in for, in order of appearance. The active stream's
+    /// results live in the plugin's own fields instead.
+    streams: Vec<Stream>,
+    active_stream: usize,
+    /// Directory the plugin was started in, the project of results piped in without a `project`
+    initial_cwd: PathBuf,
+    theme_config: ThemeConfig,
+    /// Lines in the current run's logs. It's an upper bound, as lines dropped from a log aren't
+    /// subtracted, and gets recounted once it exceeds the total limit.
+    log_line_count: usize,
+    render_throttle: RenderThrottle,
+    status_publisher: StatusPublisher,
+    /// Diagnostics piped in from `go vet` and staticcheck
+    lint: Lint,
+    /// Pane id and context of the command panes running `go test` spawned by the plugin
+    command_panes: Vec<(u32, String)>,
+    /// Answer to the permission request of `load`, `None` while the prompt is pending
+    permission_status: Option<PermissionStatus>,
 }
 
+/// Panes with fewer rows show a one line summary instead of a screen
+const MIN_ROWS: usize = 5;
+
 impl ZellijPlugin for GoTestsPlugin {
-    fn load(&mut self, _configuration: BTreeMap<String, String>) {
-        subscribe(&[EventType::Key])
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        let (config, mut config_errors) = Config::new(&configuration);
+        self.config = config;
+        let mut permissions = vec![
+            PermissionType::OpenTerminalsOrPlugins,
+            PermissionType::RunCommands,
+            PermissionType::OpenFiles,
+            PermissionType::ReadApplicationState,
+        ];
+        if self.config.pane_title_outcome {
+            permissions.push(PermissionType::ChangeApplicationState);
+        }
+        self.status_publisher = StatusPublisher::new(
+            self.config.status_pipe.clone(),
+            self.config.status_plugin.clone(),
+        );
+        if self.status_publisher.is_enabled() {
+            permissions.push(PermissionType::MessageAndLaunchOtherPlugins);
+        }
+        request_permission(&permissions);
+        subscribe(&[
+            EventType::Key,
+            EventType::Mouse,
+            EventType::CommandPaneOpened,
+            EventType::CommandPaneExited,
+            EventType::RunCommandResult,
+            EventType::FileSystemCreate,
+            EventType::FileSystemUpdate,
+            EventType::FileSystemDelete,
+            EventType::Timer,
+            EventType::ModeUpdate,
+            EventType::PermissionRequestResult,
+            EventType::CustomMessage,
+        ]);
+        self.run_number = 1;
+        runner::set_flags(&self.config.go_test_flags);
+        self.streams = vec![Stream::new(runner::PIPE_NAME, None)];
+        self.initial_cwd = get_plugin_ids().initial_cwd;
+        let (keymap, keymap_errors) = Keymap::new(&configuration);
+        self.keymap = keymap;
+        config_errors.extend(keymap_errors);
+        let (theme_config, theme_errors) = ThemeConfig::new(&configuration);
+        self.theme_config = theme_config;
+        theme::set(theme_config.apply(Theme::default()));
+        config_errors.extend(theme_errors);
+        if !config_errors.is_empty() {
+            self.notice = Some(config_errors.join("; "));
+        }
+        if self.config.persist_state {
+            if let Some(state) = State::load() {
+                self.restore_state(state);
+            }
+        }
+        // After restoring the view state, so the default filters apply to it
+        self.tests_screen.configure(&self.config);
+        if self.config.watch {
+            self.watcher.set_enabled(true);
+        }
     }
 
     fn update(&mut self, event: Event) -> bool {
-        match &mut self.logs_screen {
-            Some(logs_screen) => match logs_screen.update(event) {
+        match &event {
+            Event::CommandPaneOpened(pane_id, context) => {
+                if let Some(context) = context.get(runner::CONTEXT_KEY) {
+                    self.command_panes.push((*pane_id, context.clone()));
+                }
+                return false;
+            }
+            Event::CommandPaneExited(pane_id, _, context) => {
+                self.command_panes.retain(|(id, _)| id != pane_id);
+                return match context.get(runner::CONTEXT_KEY).map(String::as_str) {
+                    Some(context) => {
+                        close_terminal_pane(*pane_id);
+                        context == runner::RUN_ALL_CONTEXT && self.watcher.on_run_finished()
+                    }
+                    None => false,
+                };
+            }
+            Event::RunCommandResult(exit_code, stdout, stderr, context) => {
+                return match context.get(runner::CONTEXT_KEY).map(String::as_str) {
+                    Some(clipboard::CLIPBOARD_CONTEXT) => {
+                        if *exit_code != Some(0) {
+                            self.notice = Some(format!(
+                                "Clipboard command failed: {}",
+                                String::from_utf8_lossy(stderr).trim()
+                            ));
+                            return true;
+                        }
+                        false
+                    }
+                    Some(run_screen::PACKAGE_LIST_CONTEXT) => match &mut self.run_screen {
+                        Some(run_screen) => {
+                            run_screen.on_packages_listed(*exit_code, stdout, stderr);
+                            true
+                        }
+                        None => false,
+                    },
+                    Some(coverage_screen::FUNCTIONS_CONTEXT) => match &mut self.coverage_screen {
+                        Some(coverage_screen) => {
+                            coverage_screen.on_functions_listed(*exit_code, stdout, stderr);
+                            true
+                        }
+                        None => false,
+                    },
+                    Some(runner::MODULE_CONTEXT) => {
+                        match runner::parse_module(stdout).filter(|_| *exit_code == Some(0)) {
+                            Some(module_prefix) => {
+                                self.config.module_prefix = Some(module_prefix);
+                                self.configure_tests_screens();
+                            }
+                            None => {
+                                self.notice = Some(format!(
+                                    "Failed to detect the module: {}",
+                                    String::from_utf8_lossy(stderr).trim()
+                                ));
+                            }
+                        }
+                        true
+                    }
+                    Some(source_location::PACKAGE_DIR_CONTEXT) => {
+                        match self
+                            .source_opener
+                            .on_package_dir_resolved(*exit_code, stdout, stderr, context)
+                        {
+                            Ok(()) => false,
+                            Err(err) => {
+                                self.notice = Some(err);
+                                true
+                            }
+                        }
+                    }
+                    _ => false,
+                };
+            }
+            Event::FileSystemCreate(paths)
+            | Event::FileSystemUpdate(paths)
+            | Event::FileSystemDelete(paths) => {
+                self.watcher.on_files_changed(paths);
+                return false;
+            }
+            // Commands can only run once permitted
+            Event::PermissionRequestResult(permission_status) => {
+                self.permission_status = Some(*permission_status);
+                if *permission_status == PermissionStatus::Denied {
+                    // Watching would start runs that can't open their panes
+                    self.watcher.set_enabled(false);
+                    self.notice = Some(
+                        "Permissions denied: running, watching and opening files are unavailable"
+                            .to_owned(),
+                    );
+                    return true;
+                }
+                if self.config.module_prefix.as_deref() == Some(AUTO_MODULE_PREFIX) {
+                    runner::detect_module();
+                }
+                return false;
+            }
+            Event::CustomMessage(message, payload) if message == search_worker::MATCHES_MESSAGE => {
+                let Some(logs_screen) = &mut self.logs_screen else {
+                    return false;
+                };
+                return serde_json::from_str(payload)
+                    .is_ok_and(|search_matches| logs_screen.on_search_matches(search_matches));
+            }
+            Event::ModeUpdate(mode_info) => {
+                let theme = self
+                    .theme_config
+                    .apply(Theme::from_palette(&mode_info.style.colors));
+                let is_changed = theme != theme::current();
+                theme::set(theme);
+                return is_changed;
+            }
+            Event::Timer(_) => {
+                let is_running = self.is_running();
+                let started_run = self.watcher.on_timer();
+                let spinner_moved = self.spinner.on_timer(is_running);
+                let render_due = self.render_throttle.on_timer();
+                if self.save_scheduler.on_timer() {
+                    self.save_state();
+                }
+                return started_run || spinner_moved || render_due;
+            }
+            Event::Key(key) => {
+                self.notice = None;
+                let is_tab_shown = self.top_screen() == Screen::Tab;
+                if let Some(tab) = Tab::from_key(key, self.active_tab).filter(|_| is_tab_shown) {
+                    self.switch_tab(tab);
+                    return true;
+                }
+            }
+            _ => (),
+        }
+        if let Some(help_screen) = &mut self.help_screen {
+            return match help_screen.update(event) {
+                Some(help_screen::UpdateCommand::Render) => true,
+                Some(help_screen::UpdateCommand::ExitScreen) => {
+                    self.help_screen = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(finder_screen) = &mut self.finder_screen {
+            return match finder_screen.update(event) {
+                Some(finder_screen::UpdateCommand::Render) => true,
+                Some(finder_screen::UpdateCommand::Select { package, test }) => {
+                    self.finder_screen = None;
+                    let tests_screen = match &mut self.viewed_run {
+                        Some(viewed_run) => &mut viewed_run.screen,
+                        None => &mut self.tests_screen,
+                    };
+                    if !tests_screen.reveal(&package, test.as_deref()) {
+                        self.notice =
+                            Some(format!("{} is hidden in this tab", test.unwrap_or(package)));
+                    }
+                    true
+                }
+                Some(finder_screen::UpdateCommand::ExitScreen) => {
+                    self.finder_screen = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(package_picker) = &mut self.package_picker {
+            let tests_screen = match &mut self.viewed_run {
+                Some(viewed_run) => &mut viewed_run.screen,
+                None => &mut self.tests_screen,
+            };
+            return match package_picker.update(event, &tests_screen.packages) {
+                Some(package_picker::UpdateCommand::Render) => true,
+                Some(package_picker::UpdateCommand::Select { package }) => {
+                    self.package_picker = None;
+                    tests_screen.show_only_package(package);
+                    true
+                }
+                Some(package_picker::UpdateCommand::ExitScreen) => {
+                    self.package_picker = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(logs_screen) = &mut self.logs_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            let (log, generation) = (
+                logs_screen.log(packages),
+                logs_screen.log_generation(packages),
+            );
+            return match logs_screen.update(event, &self.keymap, &log, generation) {
                 Some(logs_screen::UpdateCommand::Render) => true,
+                Some(logs_screen::UpdateCommand::CopyToClipboard(text)) => {
+                    self.copy_to_clipboard(text);
+                    true
+                }
+                Some(logs_screen::UpdateCommand::OpenPager) => {
+                    let source = logs_screen.source().clone();
+                    self.open_pager(&source);
+                    true
+                }
+                Some(logs_screen::UpdateCommand::SaveLog(contents)) => {
+                    let path = export::log_path(&self.config.log_path, logs_screen.source());
+                    self.notice = Some(match export::write_report(&path, &contents) {
+                        Ok(()) => format!("Saved the log to {}", path.display()),
+                        Err(err) => err,
+                    });
+                    true
+                }
                 Some(logs_screen::UpdateCommand::ExitScreen) => {
                     self.logs_screen = None;
                     true
                 }
                 None => false,
-            },
-            None => match self.tests_screen.update(event) {
-                Some(tests_screen::UpdateCommand::Render) => true,
-                Some(tests_screen::UpdateCommand::ShowLogsScreen(logs_screen)) => {
-                    self.logs_screen = Some(logs_screen);
+            };
+        }
+        if let Some(errors_screen) = &mut self.errors_screen {
+            return match errors_screen.update(event, &self.errors) {
+                Some(errors_screen::UpdateCommand::Render) => true,
+                Some(errors_screen::UpdateCommand::ClearErrors) => {
+                    self.errors.clear();
+                    self.errors_screen = None;
+                    true
+                }
+                Some(errors_screen::UpdateCommand::ExitScreen) => {
+                    self.errors_screen = None;
                     true
                 }
                 None => false,
-            },
+            };
         }
-    }
-
-    fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
-        if let Some(payload) = pipe_message.payload {
-            let line: TestLine =
-                serde_json::from_str(&payload).expect("Failed to deserialize Go test line json");
-            match line.action {
-                Some(Action::Start) => {
-                    let new_package = Package::new(
-                        line.package
-                            .expect("Expected name for package in `Start` action"),
+        if let Some(grep_screen) = &mut self.grep_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            return match grep_screen.update(event, packages) {
+                Some(grep_screen::UpdateCommand::Render) => true,
+                Some(grep_screen::UpdateCommand::OpenLogs {
+                    package,
+                    test,
+                    attempt,
+                    line,
+                }) => {
+                    let source = LogSource {
+                        package,
+                        test,
+                        attempt,
+                    };
+                    let mut logs_screen = LogsScreen::new(source, self.config.case_sensitivity);
+                    let (log, generation) = (
+                        logs_screen.log(packages),
+                        logs_screen.log_generation(packages),
                     );
-
-                    if let Some(package) = self
-                        .tests_screen
-                        .packages
-                        .iter_mut()
-                        .find(|package| package.name == new_package.name)
-                    {
-                        *package = new_package;
-                    } else {
-                        self.tests_screen.packages.push(new_package);
-                    }
+                    logs_screen.scroll_to_line(line, &log, generation);
+                    self.logs_screen = Some(logs_screen);
+                    true
                 }
-                Some(action @ (Action::Skip | Action::Pass | Action::Fail)) => {
-                    if let Some(package) = self.tests_screen.packages.iter_mut().find(|package| {
-                        package.name
-                            == line.package.as_deref().expect(&format!(
-                                "Expected name for package in `{}` action",
-                                action.as_ref()
-                            ))
-                    }) {
-                        if let Some(test) = package
-                            .tests
-                            .iter_mut()
-                            .find(|test| line.test.as_deref() == Some(&test.name))
-                        {
-                            test.result = Some(action.try_into().unwrap());
-                            test.elapsed = line.elapsed;
-                        } else {
-                            package.result = Some(action.try_into().unwrap());
-                            package.elapsed = line.elapsed;
-                        }
+                Some(grep_screen::UpdateCommand::ExitScreen) => {
+                    self.grep_screen = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(triage_screen) = &mut self.triage_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            return match triage_screen.update(event, packages) {
+                Some(triage_screen::UpdateCommand::Render) => true,
+                Some(triage_screen::UpdateCommand::OpenLogs {
+                    package,
+                    test,
+                    attempt,
+                }) => {
+                    let source = LogSource {
+                        package,
+                        test,
+                        attempt,
+                    };
+                    self.logs_screen = Some(LogsScreen::new(source, self.config.case_sensitivity));
+                    true
+                }
+                Some(triage_screen::UpdateCommand::OpenSource { package, location }) => {
+                    if let Some(message) = self.missing_permission("Opening files") {
+                        self.notice = Some(message);
+                        return true;
                     }
+                    self.source_opener.open(&package, location);
+                    false
                 }
-                Some(Action::Run) => {
-                    if let Some(package) = self.tests_screen.packages.iter_mut().find(|package| {
-                        package.name
-                            == line
-                                .package
-                                .as_deref()
-                                .expect("Expected name for package in `Run` action")
-                    }) {
-                        package.tests.push(TestCase {
-                            name: line.test.expect("Expected test name"),
-                            result: None,
-                            log: Vec::new(),
-                            elapsed: None,
-                        });
+                Some(triage_screen::UpdateCommand::Notice(notice)) => {
+                    self.notice = Some(notice);
+                    true
+                }
+                Some(triage_screen::UpdateCommand::ExitScreen) => {
+                    self.triage_screen = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(lint_screen) = &mut self.lint_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            return match lint_screen.update(event, &self.lint, packages) {
+                Some(lint_screen::UpdateCommand::Render) => true,
+                Some(lint_screen::UpdateCommand::OpenLogs { package }) => {
+                    let source = LogSource {
+                        package,
+                        test: None,
+                        attempt: 0,
+                    };
+                    self.logs_screen = Some(LogsScreen::new(source, self.config.case_sensitivity));
+                    true
+                }
+                Some(lint_screen::UpdateCommand::OpenSource { package, location }) => {
+                    if let Some(message) = self.missing_permission("Opening files") {
+                        self.notice = Some(message);
+                        return true;
                     }
+                    self.source_opener.open(&package, location);
+                    false
                 }
-                Some(Action::Output) => {
-                    if let Some(test_case) = &line.test {
-                        if let Some(test) = self
-                            .tests_screen
-                            .packages
-                            .iter_mut()
-                            .find(|package| {
-                                package.name
-                                    == line
-                                        .package
-                                        .as_deref()
-                                        .expect("Expected name for package in `Output` action")
-                            })
-                            .and_then(|package| {
-                                package
-                                    .tests
-                                    .iter_mut()
-                                    .find(|test| test.name == *test_case)
-                            })
-                        {
-                            test.log
-                                .push(line.output.expect("Expected output in `Output` action"));
-                        }
-                    } else {
-                        if let Some(package) =
-                            self.tests_screen.packages.iter_mut().find(|package| {
-                                package.name
-                                    == line
-                                        .package
-                                        .as_deref()
-                                        .expect("Expected name for package in `Output` action")
-                            })
-                        {
-                            package
-                                .log
-                                .push(line.output.expect("Expected output in `Output` action"));
-                        }
+                Some(lint_screen::UpdateCommand::ClearDiagnostics) => {
+                    self.lint.clear();
+                    true
+                }
+                Some(lint_screen::UpdateCommand::ExitScreen) => {
+                    self.lint_screen = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(slowest_screen) = &mut self.slowest_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            return match slowest_screen.update(event, packages) {
+                Some(slowest_screen::UpdateCommand::Render) => true,
+                Some(slowest_screen::UpdateCommand::OpenLogs {
+                    package,
+                    test,
+                    attempt,
+                }) => {
+                    let source = LogSource {
+                        package,
+                        test: Some(test),
+                        attempt,
+                    };
+                    self.logs_screen = Some(LogsScreen::new(source, self.config.case_sensitivity));
+                    true
+                }
+                Some(slowest_screen::UpdateCommand::ExitScreen) => {
+                    self.slowest_screen = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(benchmarks_screen) = &mut self.benchmarks_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            return match benchmarks_screen.update(event, packages) {
+                Some(benchmarks_screen::UpdateCommand::Render) => true,
+                Some(benchmarks_screen::UpdateCommand::ExitScreen) => {
+                    self.switch_tab(Tab::Tests);
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(coverage_screen) = &mut self.coverage_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            return match coverage_screen.update(event, packages) {
+                Some(coverage_screen::UpdateCommand::Render) => true,
+                Some(coverage_screen::UpdateCommand::ListFunctions) => {
+                    self.list_functions();
+                    true
+                }
+                Some(coverage_screen::UpdateCommand::ReloadProfile) => {
+                    coverage_screen.set_profile(cover_profile::load(&self.config.coverage_profile));
+                    true
+                }
+                Some(coverage_screen::UpdateCommand::OpenSource { package, location }) => {
+                    if let Some(message) = self.missing_permission("Opening files") {
+                        self.notice = Some(message);
+                        return true;
                     }
+                    self.source_opener.open(&package, location);
+                    false
                 }
-                _ => (),
-            }
-            true
-        } else {
-            false
+                Some(coverage_screen::UpdateCommand::ExitScreen) => {
+                    self.switch_tab(Tab::Tests);
+                    true
+                }
+                None => false,
+            };
         }
-    }
-
-    fn render(&mut self, rows: usize, cols: usize) {
-        match &mut self.logs_screen {
-            Some(logs_screen) => logs_screen.render(rows, cols),
-            None => self.tests_screen.render(rows, cols),
+        if let Some(comparison_screen) = &mut self.comparison_screen {
+            return match comparison_screen.update(event) {
+                Some(comparison_screen::UpdateCommand::Render) => true,
+                Some(comparison_screen::UpdateCommand::ExitScreen) => {
+                    self.comparison_screen = None;
+                    true
+                }
+                None => false,
+            };
         }
-    }
-}
-
-register_plugin!(GoTestsPlugin);
+        if let Some(history_screen) = &mut self.history_screen {
+            return match history_screen.update(event, &self.history) {
+                Some(history_screen::UpdateCommand::Render) => true,
+                Some(history_screen::UpdateCommand::ShowRun(index)) => {
+                    self.viewed_run = index.map(|index| {
+                        let mut viewed_run = ViewedRun::new(&self.history[index], &self.config);
+                        viewed_run
+                            .summary
+                            .recount(&mut viewed_run.screen.packages, self.config.slow_threshold);
+                        viewed_run
+                    });
+                    self.switch_tab(Tab::Tests);
+                    true
+                }
+                Some(history_screen::UpdateCommand::CompareRun(index)) => {
+                    self.compare_run(index);
+                    true
+                }
+                Some(history_screen::UpdateCommand::ExitScreen) => {
+                    self.switch_tab(Tab::Tests);
+                    true
+                }
+                None => false,
+            };
+        }
+        if let Some(run_screen) = &mut self.run_screen {
+            return match run_screen.update(event) {
+                Some(run_screen::UpdateCommand::Render) => true,
+                Some(run_screen::UpdateCommand::Run(packages)) => {
+                    runner::run_tests(&packages);
+                    self.notice = Some(format!("Running the tests of {} packages", packages.len()));
+                    self.run_screen = None;
+                    true
+                }
+                Some(run_screen::UpdateCommand::SetFlags(flags)) => {
+                    runner::set_flags(&flags);
+                    true
+                }
+                Some(run_screen::UpdateCommand::ExitScreen) => {
+                    self.run_screen = None;
+                    true
+                }
+                None => false,
+            };
+        }
+        let command = match &mut self.viewed_run {
+            Some(viewed_run) => viewed_run.screen.update(event, &self.keymap),
+            None => {
+                let view_state = self.tests_screen.view_state();
+                let command = self.tests_screen.update(event, &self.keymap);
+                if self.tests_screen.view_state() != view_state {
+                    self.mark_dirty();
+                }
+                command
+            }
+        };
+        if let Some(notice) = command
+            .as_ref()
+            .and_then(required_permission)
+            .and_then(|feature| self.missing_permission(feature))
+        {
+            self.notice = Some(notice);
+            return true;
+        }
+        match command {
+            Some(tests_screen::UpdateCommand::Render) => true,
+            Some(tests_screen::UpdateCommand::ShowLogsScreen(logs_screen)) => {
+                self.logs_screen = Some(*logs_screen);
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowErrorsScreen) => {
+                self.errors_screen = Some(ErrorsScreen::default());
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowHelpScreen) => {
+                self.help_screen = Some(HelpScreen::new(&self.keymap));
+                true
+            }
+            Some(tests_screen::UpdateCommand::ClearResults) => {
+                self.clear();
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowFinder) => {
+                let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+                self.finder_screen = Some(FinderScreen::new(packages));
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowPackagePicker) => {
+                let tests_screen = match &self.viewed_run {
+                    Some(viewed_run) => &viewed_run.screen,
+                    None => &self.tests_screen,
+                };
+                self.package_picker = Some(PackagePicker::new(
+                    &tests_screen.packages,
+                    tests_screen.package_filter(),
+                ));
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowGrepScreen) => {
+                self.grep_screen = Some(GrepScreen::new(self.config.case_sensitivity));
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowLintScreen) => {
+                self.lint_screen = Some(LintScreen::default());
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowTriageScreen) => {
+                self.triage_screen = Some(TriageScreen::default());
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowSlowestScreen) => {
+                self.slowest_screen = Some(SlowestScreen::new(self.config.slowest_count));
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowBenchmarksScreen) => {
+                self.switch_tab(Tab::Benchmarks);
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowHistoryScreen) => {
+                self.switch_tab(Tab::History);
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowRunScreen)
+                if self.streams[self.active_stream].project.is_some() =>
+            {
+                self.notice =
+                    Some("Runs only start in the plugin's own project, see [P]".to_owned());
+                true
+            }
+            Some(tests_screen::UpdateCommand::ShowRunScreen) => {
+                self.run_screen = Some(RunScreen::new());
+                true
+            }
+            Some(tests_screen::UpdateCommand::ExitScreen) => self.viewed_run.take().is_some(),
+            Some(tests_screen::UpdateCommand::Export(format)) => {
+                self.export(format);
+                true
+            }
+            Some(tests_screen::UpdateCommand::OpenSource { package, location }) => {
+                self.source_opener.open(&package, location);
+                false
+            }
+            Some(tests_screen::UpdateCommand::CopyToClipboard(text)) => {
+                self.copy_to_clipboard(text);
+                true
+            }
+            Some(tests_screen::UpdateCommand::OpenPager(source)) => {
+                self.open_pager(&source);
+                true
+            }
+            Some(tests_screen::UpdateCommand::SaveLogs(sources)) => {
+                self.save_logs(&sources);
+                true
+            }
+            Some(tests_screen::UpdateCommand::Notice(notice)) => {
+                self.notice = Some(notice);
+                true
+            }
+            Some(tests_screen::UpdateCommand::ToggleWatch) => {
+                let is_enabled = !self.watcher.is_enabled();
+                match self
+                    .missing_permission("Watching for changes")
+                    .filter(|_| is_enabled)
+                {
+                    Some(notice) => self.notice = Some(notice),
+                    None => self.watcher.set_enabled(is_enabled),
+                }
+                true
+            }
+            Some(
+                tests_screen::UpdateCommand::RerunFailed(_)
+                | tests_screen::UpdateCommand::RerunTest(_),
+            ) if self.viewed_run.is_some() => {
+                self.notice = Some("Only the current run's tests can be rerun".to_owned());
+                true
+            }
+            Some(tests_screen::UpdateCommand::RerunFailed(reruns)) => {
+                self.clear_results(&reruns);
+                let stream = &self.streams[self.active_stream];
+                runner::rerun_tests(&reruns, &stream.name, stream.project.as_deref());
+                true
+            }
+            Some(tests_screen::UpdateCommand::RerunTest(rerun)) => {
+                if let Some(package) = self
+                    .tests_screen
+                    .packages
+                    .iter_mut()
+                    .find(|package| package.name == rerun.package)
+                {
+                    clear_package_result(&mut self.summary, package);
+                }
+                self.tests_screen.mark_packages_changed();
+                let stream = &self.streams[self.active_stream];
+                runner::rerun_tests(&[rerun], &stream.name, stream.project.as_deref());
+                true
+            }
+            Some(tests_screen::UpdateCommand::AbortRun) => {
+                self.abort_run();
+                true
+            }
+            Some(tests_screen::UpdateCommand::NextStream) => {
+                // Cycles through the streams of the active project only
+                let project = &self.streams[self.active_stream].project;
+                let next_stream = (1..self.streams.len())
+                    .map(|offset| (self.active_stream + offset) % self.streams.len())
+                    .find(|index| &self.streams[*index].project == project);
+                match next_stream {
+                    Some(next_stream) => self.switch_stream(next_stream),
+                    None => self.notice = Some("No other streams were piped in".to_owned()),
+                }
+                true
+            }
+            Some(tests_screen::UpdateCommand::NextProject) => {
+                let projects = self.projects();
+                if projects.len() < 2 {
+                    self.notice = Some("No other projects were piped in".to_owned());
+                } else {
+                    let project = &self.streams[self.active_stream].project;
+                    let active_project = projects.iter().position(|p| p == project).unwrap_or(0);
+                    let next_project = &projects[(active_project + 1) % projects.len()];
+                    if let Some(index) = self
+                        .streams
+                        .iter()
+                        .position(|stream| &stream.project == next_project)
+                    {
+                        self.switch_stream(index);
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
+        if pipe_message.name == lint::PIPE_NAME {
+            return self.handle_lint_message(pipe_message);
+        }
+        let project = pipe_message
+            .args
+            .get(runner::PROJECT_ARG)
+            .map(PathBuf::from)
+            .filter(|project| *project != self.initial_cwd);
+        let existing_index = self.find_stream(&pipe_message, &project);
+        let index = match existing_index {
+            Some(index) => index,
+            None => {
+                let stream_name = self.new_stream_name(&pipe_message, &project);
+                let mut stream = Stream::new(stream_name, project);
+                stream.tests_screen.configure(&self.config);
+                self.streams.push(stream);
+                self.streams.len() - 1
+            }
+        };
+        if let (PipeSource::Cli(pipe_id), None) = (
+            &pipe_message.source,
+            pipe_message.args.get(runner::STREAM_ARG),
+        ) {
+            self.streams[index].source = Some(pipe_id.clone());
+        }
+        if index == self.active_stream {
+            let error_count = self.errors.len();
+            let was_running = self.is_running();
+            let is_changed = self.handle_pipe_message(pipe_message);
+            match (was_running, self.is_running()) {
+                (false, true) => self.on_run_started(),
+                (true, false) => self.on_run_completed(),
+                _ => (),
+            }
+            self.status_publisher
+                .publish(&self.summary, self.is_running());
+            // Results of the current run are hidden while a run from the history is viewed
+            let is_visible = self.viewed_run.is_none()
+                || self.history_screen.is_some()
+                || self.errors.len() != error_count;
+            return is_changed && is_visible && self.render_throttle.request();
+        }
+        self.swap_stream(index);
+        self.handle_pipe_message(pipe_message);
+        self.swap_stream(index);
+        // Only the status bar's list of streams shows anything of an inactive stream
+        existing_index.is_none()
+    }
+
+    fn render(&mut self, rows: usize, cols: usize) {
+        self.render_throttle.on_render();
+        if let Some(text) = self.pending_clipboard.take() {
+            print!("{}", clipboard::osc52(&text));
+        }
+        if rows < MIN_ROWS {
+            let (streams, active_stream) = self.project_streams();
+            let (projects, active_project) = self.project_labels();
+            self.tests_screen.render_compact(
+                rows,
+                cols,
+                &StatusBar {
+                    summary: self.summary,
+                    is_watching: self.watcher.is_enabled(),
+                    error_count: self.errors.len(),
+                    notice: self.notice.as_deref(),
+                    spinner: self.spinner.frame(),
+                    streams: &streams,
+                    active_stream,
+                    projects: &projects,
+                    active_project,
+                },
+            );
+        } else if let Some(help_screen) = &mut self.help_screen {
+            help_screen.render(rows, cols);
+        } else if let Some(logs_screen) = &mut self.logs_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            let source = logs_screen.source();
+            let (log, status) = (logs_screen.log(packages), source.status(packages));
+            let log_times = source.log_times(packages);
+            let generation = logs_screen.log_generation(packages);
+            logs_screen.render(rows, cols, &log, log_times, status, generation);
+        } else if let Some(errors_screen) = &mut self.errors_screen {
+            errors_screen.render(rows, cols, &self.errors);
+        } else if let Some(grep_screen) = &mut self.grep_screen {
+            grep_screen.render(rows, cols);
+        } else if let Some(triage_screen) = &mut self.triage_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            triage_screen.render(rows, cols, packages);
+        } else if let Some(lint_screen) = &mut self.lint_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            lint_screen.render(rows, cols, &self.lint, packages);
+        } else if let Some(slowest_screen) = &mut self.slowest_screen {
+            let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+            slowest_screen.render(rows, cols, packages);
+        } else if let Some(comparison_screen) = &mut self.comparison_screen {
+            comparison_screen.render(rows, cols);
+        } else if let Some(run_screen) = &mut self.run_screen {
+            run_screen.render(rows, cols);
+        } else {
+            // The screens of the tab bar leave its line to it
+            self.render_tab(rows.saturating_sub(1), cols);
+            tabs::render_tab_bar(self.active_tab, rows.saturating_sub(1));
+            if let Some(finder_screen) = &mut self.finder_screen {
+                finder_screen.render(rows, cols);
+            }
+            if let Some(package_picker) = &mut self.package_picker {
+                let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+                package_picker.render(rows, cols, packages);
+            }
+        }
+    }
+}
+
+impl GoTestsPlugin {
+    /// Explains why a feature needing the permissions requested in `load` is unavailable, `None`
+    /// once they're granted
+    fn missing_permission(&self, feature: &str) -> Option<String> {
+        match self.permission_status {
+            Some(PermissionStatus::Granted) => None,
+            Some(PermissionStatus::Denied) => Some(format!(
+                "{} needs the plugin's permissions, which were denied. Reload the plugin to be asked again",
+                feature
+            )),
+            None => Some(format!(
+                "{} needs the plugin's permissions, answer the prompt in its pane first",
+                feature
+            )),
+        }
+    }
+
+    fn render_tab(&mut self, rows: usize, cols: usize) {
+        let (streams, active_stream) = self.project_streams();
+        let (projects, active_project) = self.project_labels();
+        let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+        if let Some(benchmarks_screen) = &mut self.benchmarks_screen {
+            benchmarks_screen.render(rows, cols, packages);
+        } else if let Some(coverage_screen) = &mut self.coverage_screen {
+            coverage_screen.render(rows, cols, packages, self.config.coverage_thresholds);
+        } else if let Some(history_screen) = &mut self.history_screen {
+            history_screen.render(rows, cols, (self.run_number, &self.summary), &self.history);
+        } else if let Some(viewed_run) = &mut self.viewed_run {
+            let notice = format!(
+                "viewing run #{} of {} — [Esc] back to the current run",
+                viewed_run.number, self.run_number
+            );
+            viewed_run.screen.render(
+                rows,
+                cols,
+                self.config.coverage_thresholds,
+                &StatusBar {
+                    summary: viewed_run.summary,
+                    is_watching: self.watcher.is_enabled(),
+                    error_count: self.errors.len(),
+                    notice: Some(self.notice.as_deref().unwrap_or(&notice)),
+                    spinner: self.spinner.frame(),
+                    streams: &[],
+                    active_stream: 0,
+                    projects: &[],
+                    active_project: 0,
+                },
+            );
+        } else {
+            self.tests_screen.render(
+                rows,
+                cols,
+                self.config.coverage_thresholds,
+                &StatusBar {
+                    summary: self.summary,
+                    is_watching: self.watcher.is_enabled(),
+                    error_count: self.errors.len(),
+                    notice: self.notice.as_deref(),
+                    spinner: self.spinner.frame(),
+                    streams: &streams,
+                    active_stream,
+                    projects: &projects,
+                    active_project,
+                },
+            );
+        }
+    }
+
+    /// The screen getting the input, in the order `update` offers it to them
+    fn top_screen(&self) -> Screen {
+        let screens = [
+            (self.help_screen.is_some(), Screen::Help),
+            (self.finder_screen.is_some(), Screen::Finder),
+            (self.package_picker.is_some(), Screen::PackagePicker),
+            (self.logs_screen.is_some(), Screen::Logs),
+            (self.errors_screen.is_some(), Screen::Errors),
+            (self.grep_screen.is_some(), Screen::Grep),
+            (self.triage_screen.is_some(), Screen::Triage),
+            (self.lint_screen.is_some(), Screen::Lint),
+            (self.slowest_screen.is_some(), Screen::Slowest),
+            (self.comparison_screen.is_some(), Screen::Comparison),
+            (self.run_screen.is_some(), Screen::Run),
+        ];
+        screens
+            .into_iter()
+            .find_map(|(is_open, screen)| is_open.then_some(screen))
+            .unwrap_or(Screen::Tab)
+    }
+
+    /// Shows the screen of a tab, closing the screen of the previously active tab
+    fn switch_tab(&mut self, tab: Tab) {
+        if tab == self.active_tab {
+            return;
+        }
+        self.benchmarks_screen = (tab == Tab::Benchmarks).then(BenchmarksScreen::default);
+        self.coverage_screen = (tab == Tab::Coverage)
+            .then(|| CoverageScreen::new(cover_profile::load(&self.config.coverage_profile)));
+        self.history_screen = (tab == Tab::History).then(HistoryScreen::default);
+        let failures_only = tab == Tab::Failures;
+        self.tests_screen.set_failures_only(failures_only);
+        if let Some(viewed_run) = &mut self.viewed_run {
+            viewed_run.screen.set_failures_only(failures_only);
+        }
+        self.active_tab = tab;
+    }
+
+    /// Whether a package of the current run hasn't reported its result yet while its tests are
+    /// still running
+    fn is_running(&self) -> bool {
+        is_any_running(&self.tests_screen.packages)
+    }
+
+    /// The stream a message belongs to. Without a `stream` argument, the messages of a CLI pipe
+    /// go to the stream it fed before, or to a finished stream of the pipe's name, so `zellij
+    /// pipe`s of the same name running in separate panes get streams of their own.
+    fn find_stream(&self, pipe_message: &PipeMessage, project: &Option<PathBuf>) -> Option<usize> {
+        let in_project = |index: &usize| &self.streams[*index].project == project;
+        if let Some(stream_name) = pipe_message.args.get(runner::STREAM_ARG) {
+            return (0..self.streams.len())
+                .filter(in_project)
+                .find(|index| &self.streams[*index].name == stream_name);
+        }
+        let PipeSource::Cli(pipe_id) = &pipe_message.source else {
+            return (0..self.streams.len())
+                .filter(in_project)
+                .find(|index| self.streams[*index].name == pipe_message.name);
+        };
+        (0..self.streams.len())
+            .filter(in_project)
+            .find(|index| self.streams[*index].source.as_ref() == Some(pipe_id))
+            .or_else(|| {
+                (0..self.streams.len()).filter(in_project).find(|index| {
+                    let is_running = if *index == self.active_stream {
+                        self.is_running()
+                    } else {
+                        is_any_running(&self.streams[*index].tests_screen.packages)
+                    };
+                    self.streams[*index].name == pipe_message.name && !is_running
+                })
+            })
+    }
+
+    /// Name of a stream created for a message, numbered when a stream of the same name is still
+    /// running in another pane, e.g. `zj-go-tests 2`
+    fn new_stream_name(&self, pipe_message: &PipeMessage, project: &Option<PathBuf>) -> String {
+        let name = pipe_message
+            .args
+            .get(runner::STREAM_ARG)
+            .unwrap_or(&pipe_message.name);
+        let is_taken = |candidate: &str| {
+            self.streams
+                .iter()
+                .any(|stream| stream.name == candidate && &stream.project == project)
+        };
+        (1..)
+            .map(|number| match number {
+                1 => name.clone(),
+                number => format!("{} {}", name, number),
+            })
+            .find(|candidate| !is_taken(candidate))
+            .unwrap_or_else(|| name.clone())
+    }
+
+    fn on_run_started(&mut self) {
+        if self.config.pane_title_outcome {
+            self.set_pane_title("running");
+        }
+    }
+
+    /// Called once the last running package of the current run finished
+    fn on_run_completed(&mut self) {
+        let has_failure = self.summary.failed > 0
+            || self.tests_screen.packages.iter().any(|package| {
+                package
+                    .result_or_derived()
+                    .is_some_and(|result| result.is_failure())
+            });
+        let outcome = self.summary.outcome(has_failure);
+        if self.config.pane_title_outcome {
+            self.set_pane_title(&outcome);
+        }
+        self.notice = Some(format!("Run #{} completed: {}", self.run_number, outcome));
+        let is_tests_screen_shown = self.top_screen() == Screen::Tab
+            && matches!(self.active_tab, Tab::Tests | Tab::Failures)
+            && self.viewed_run.is_none();
+        if self.config.auto_open_first_failure && is_tests_screen_shown {
+            self.logs_screen = self.tests_screen.open_first_failure();
+        }
+    }
+
+    fn set_pane_title(&self, status: &str) {
+        rename_plugin_pane(get_plugin_ids().plugin_id, format!("go tests: {}", status));
+    }
+
+    fn copy_to_clipboard(&mut self, text: String) {
+        let line_count = text.lines().count();
+        match &self.config.clipboard_command {
+            Some(command) => clipboard::run_clipboard_command(command, &text),
+            None => self.pending_clipboard = Some(text),
+        }
+        self.notice = Some(format!("Copied {} lines to the clipboard", line_count));
+    }
+
+    /// Shows the functions of the coverage profile in the coverage tab
+    fn list_functions(&mut self) {
+        if let Some(notice) = self.missing_permission("Listing functions") {
+            self.notice = Some(notice);
+            return;
+        }
+        if let Some(coverage_screen) = &mut self.coverage_screen {
+            coverage_screen.list_functions(&self.config.coverage_profile);
+        }
+    }
+
+    /// Opens a log of the run being shown in the user's pager
+    fn open_pager(&mut self, source: &LogSource) {
+        if let Some(notice) = self.missing_permission("Opening a pager") {
+            self.notice = Some(notice);
+            return;
+        }
+        let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+        let contents = source
+            .log(packages)
+            .iter()
+            .map(|line| ansi::strip(line))
+            .collect::<String>();
+        if let Err(err) = pager::open(source, &contents, &self.initial_cwd) {
+            self.notice = Some(err);
+        }
+    }
+
+    /// Writes each log, without its ANSI escapes, to the file `log_path` names for it
+    fn save_logs(&mut self, sources: &[LogSource]) {
+        let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+        let mut paths = Vec::new();
+        for source in sources {
+            let path = export::log_path(&self.config.log_path, source);
+            let contents = source
+                .log(packages)
+                .iter()
+                .map(|line| ansi::strip(line))
+                .collect::<String>();
+            if let Err(err) = export::write_report(&path, &contents) {
+                self.notice = Some(err);
+                return;
+            }
+            paths.push(path);
+        }
+        self.notice = Some(match &paths[..] {
+            [path] => format!("Saved the log to {}", path.display()),
+            paths => format!("Saved {} logs", paths.len()),
+        });
+    }
+
+    /// Exports the results of the run being shown
+    fn export(&mut self, format: ExportFormat) {
+        let packages = shown_packages(&self.viewed_run, &self.tests_screen);
+        let (path, report) = match format {
+            ExportFormat::Junit => (&self.config.junit_path, export::junit_xml(packages)),
+            ExportFormat::Json => (&self.config.json_path, export::json_report(packages)),
+        };
+        self.notice = Some(match export::write_report(path, &report) {
+            Ok(()) => format!("Exported to {}", path.display()),
+            Err(err) => err,
+        });
+    }
+
+    /// Moves the current results to the history, dropping the oldest runs beyond the configured
+    /// history size
+    fn start_run(&mut self) {
+        self.test_stream = TestStream::default();
+        if self.tests_screen.packages.is_empty() {
+            return;
+        }
+        self.history.push(TestRun {
+            number: self.run_number,
+            packages: self.tests_screen.take_packages(),
+            summary: std::mem::take(&mut self.summary),
+        });
+        self.run_number += 1;
+        self.mark_dirty();
+        let excess = self.history.len().saturating_sub(self.config.history_size);
+        self.history.drain(..excess);
+        name::drop_unused();
+    }
+
+    fn run_pipe_command(&mut self, command: PipeCommand) {
+        match command {
+            PipeCommand::Export { format: None } => self.export(ExportFormat::Junit),
+            PipeCommand::Export { format: Some(name) } => match ExportFormat::from_name(&name) {
+                Some(format) => self.export(format),
+                None => self.notice = Some(format!("Unknown export format `{}`", name)),
+            },
+            PipeCommand::RunStart => self.start_run(),
+            PipeCommand::Clear => self.clear(),
+            PipeCommand::Run { args: None } => runner::run_all_tests(),
+            PipeCommand::Run { args: Some(args) } => {
+                runner::run_tests(&args.split_whitespace().collect::<Vec<_>>());
+            }
+        }
+    }
+
+    /// Exchanges the results of a stream with the ones in the plugin's own fields. Used to show
+    /// another stream, and to handle messages of an inactive stream.
+    fn swap_stream(&mut self, index: usize) {
+        let stream = &mut self.streams[index];
+        mem::swap(&mut self.tests_screen, &mut stream.tests_screen);
+        mem::swap(&mut self.summary, &mut stream.summary);
+        mem::swap(&mut self.plain_parser, &mut stream.plain_parser);
+        mem::swap(&mut self.test_stream, &mut stream.test_stream);
+        mem::swap(&mut self.history, &mut stream.history);
+        mem::swap(&mut self.run_number, &mut stream.run_number);
+    }
+
+    /// Shows another stream in place of the active one
+    fn switch_stream(&mut self, index: usize) {
+        self.swap_stream(self.active_stream);
+        self.swap_stream(index);
+        self.active_stream = index;
+        self.viewed_run = None;
+        self.status_publisher
+            .publish(&self.summary, self.is_running());
+    }
+
+    /// Projects results were piped in for, in order of appearance
+    fn projects(&self) -> Vec<Option<PathBuf>> {
+        self.streams
+            .iter()
+            .fold(Vec::new(), |mut projects, stream| {
+                if !projects.contains(&stream.project) {
+                    projects.push(stream.project.clone());
+                }
+                projects
+            })
+    }
+
+    /// Names of the active project's streams and the position of the active stream among them,
+    /// as listed in the status bar
+    fn project_streams(&self) -> (Vec<String>, usize) {
+        let project = &self.streams[self.active_stream].project;
+        let indices = (0..self.streams.len())
+            .filter(|index| &self.streams[*index].project == project)
+            .collect::<Vec<_>>();
+        (
+            indices
+                .iter()
+                .map(|index| self.streams[*index].name.clone())
+                .collect(),
+            indices
+                .iter()
+                .position(|index| *index == self.active_stream)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Names of the projects and the position of the active one, as listed in the status bar
+    fn project_labels(&self) -> (Vec<String>, usize) {
+        let projects = self.projects();
+        let project = &self.streams[self.active_stream].project;
+        (
+            projects
+                .iter()
+                .map(|project| {
+                    stream::project_label(project.as_deref().unwrap_or(&self.initial_cwd))
+                })
+                .collect(),
+            projects.iter().position(|p| p == project).unwrap_or(0),
+        )
+    }
+
+    /// Closes the command panes running `go test`, which kills the tests, and marks the packages
+    /// and tests in flight as aborted
+    fn abort_run(&mut self) {
+        let command_panes = mem::take(&mut self.command_panes);
+        let mut is_run_aborted = false;
+        for (pane_id, context) in command_panes {
+            match context.as_str() {
+                runner::RUN_ALL_CONTEXT => {
+                    self.watcher.on_run_aborted();
+                    is_run_aborted = true;
+                }
+                runner::RERUN_CONTEXT => is_run_aborted = true,
+                // Other commands, e.g. the clipboard command, run to completion
+                _ => {
+                    self.command_panes.push((pane_id, context));
+                    continue;
+                }
+            }
+            close_terminal_pane(pane_id);
+        }
+        if !is_run_aborted {
+            self.notice = Some("No run started by the plugin is in flight".to_owned());
+            return;
+        }
+        for package in &mut self.tests_screen.packages {
+            if package.result.is_none() {
+                package.is_aborted = true;
+                TestCase::abort_unfinished(&mut package.tests);
+            }
+        }
+        self.tests_screen.mark_packages_changed();
+        self.status_publisher.publish(&self.summary, false);
+        self.mark_dirty();
+        self.notice = Some("Aborted the run".to_owned());
+    }
+
+    /// Drops the current run's results, keeping the history
+    fn clear(&mut self) {
+        self.test_stream = TestStream::default();
+        self.tests_screen.clear();
+        self.lint.clear();
+        self.summary = Summary::default();
+        self.status_publisher.publish(&self.summary, false);
+        self.viewed_run = None;
+        name::drop_unused();
+        self.mark_dirty();
+    }
+
+    fn mark_dirty(&mut self) {
+        if self.config.persist_state {
+            self.save_scheduler.mark_dirty();
+        }
+    }
+
+    /// Saves the results of the stream of the plugin's own pipe, other streams aren't persisted
+    fn save_state(&mut self) {
+        let (tests_screen, summary, history, run_number) = match self.active_stream {
+            0 => (
+                &self.tests_screen,
+                self.summary,
+                &self.history,
+                self.run_number,
+            ),
+            _ => (
+                &self.streams[0].tests_screen,
+                self.streams[0].summary,
+                &self.streams[0].history,
+                self.streams[0].run_number,
+            ),
+        };
+        let state = SavedState::new(
+            &tests_screen.packages,
+            summary,
+            history,
+            run_number,
+            tests_screen.view_state(),
+        );
+        if let Err(err) = state.save() {
+            self.notice = Some(err);
+        }
+    }
+
+    /// Applies the config to the tests screens of every stream and the viewed run, e.g. once the
+    /// module prefix got detected
+    fn configure_tests_screens(&mut self) {
+        self.tests_screen.configure(&self.config);
+        for stream in &mut self.streams {
+            stream.tests_screen.configure(&self.config);
+        }
+        if let Some(viewed_run) = &mut self.viewed_run {
+            viewed_run.screen.configure(&self.config);
+        }
+    }
+
+    fn restore_state(&mut self, state: State) {
+        self.tests_screen.packages = state.packages;
+        for package in &mut self.tests_screen.packages {
+            package.count_attempts();
+        }
+        self.tests_screen.mark_packages_changed();
+        self.tests_screen.restore_view_state(state.view_state);
+        self.summary = state.summary;
+        self.summary
+            .recount(&mut self.tests_screen.packages, self.config.slow_threshold);
+        self.history = state.history;
+        self.run_number = state.run_number;
+    }
+
+    /// Opens the comparison of a run with the run before it. `None` selects the current run.
+    fn compare_run(&mut self, index: Option<usize>) {
+        let (number, current) = match index {
+            Some(index) => (self.history[index].number, &self.history[index].packages),
+            None => (self.run_number, &self.tests_screen.packages),
+        };
+        let previous_index = index.unwrap_or(self.history.len()).checked_sub(1);
+        match previous_index.map(|previous_index| &self.history[previous_index]) {
+            Some(previous) => {
+                self.comparison_screen = Some(ComparisonScreen::new(
+                    format!("run #{} compared to run #{}", number, previous.number),
+                    &previous.packages,
+                    current,
+                ));
+            }
+            None => {
+                self.notice = Some(format!(
+                    "Run #{} has no previous run to compare with",
+                    number
+                ));
+                self.switch_tab(Tab::Tests);
+            }
+        }
+    }
+
+    /// Handles a pipe message for the stream whose results are in the plugin's own fields
+    fn handle_pipe_message(&mut self, pipe_message: PipeMessage) -> bool {
+        let command = PipeCommand::from_args(&pipe_message.args).or_else(|| {
+            pipe_message
+                .payload
+                .as_deref()
+                .and_then(PipeCommand::from_payload)
+        });
+        if let Some(command) = command {
+            match command {
+                Ok(command) => self.run_pipe_command(command),
+                Err(err) => self.notice = Some(err),
+            }
+            return true;
+        }
+        let Some(payload) = pipe_message.payload else {
+            return false;
+        };
+        if payload.trim().is_empty() {
+            return false;
+        }
+        let input_format = pipe_message
+            .args
+            .get("format")
+            .and_then(|name| InputFormat::from_name(name))
+            .unwrap_or(self.config.input_format);
+        let restart = if pipe_message
+            .args
+            .get(runner::RERUN_ARG)
+            .is_some_and(|value| value == "true")
+        {
+            Restart::Rerun
+        } else if input_format == InputFormat::Gotestsum {
+            Restart::RetryFailed
+        } else {
+            Restart::Attempt
+        };
+        if input_format.is_plain(&payload) {
+            for line in payload.lines() {
+                for test_line in self.plain_parser.parse(line) {
+                    if let Err(message) = self.handle_line(test_line, restart) {
+                        self.errors.push(PipeError {
+                            line: line.to_owned(),
+                            message,
+                        });
+                    }
+                }
+            }
+        } else if let Err(message) = self.handle_payload(&payload, restart) {
+            self.errors.push(PipeError {
+                line: payload,
+                message,
+            });
+        }
+        self.tests_screen.mark_packages_changed();
+        if self.is_running() {
+            self.spinner.start();
+        }
+        self.mark_dirty();
+        true
+    }
+
+    /// Adds the diagnostics piped into the `vet` pipe, kept apart from the streams of test results
+    fn handle_lint_message(&mut self, pipe_message: PipeMessage) -> bool {
+        let Some(payload) = pipe_message.payload else {
+            return false;
+        };
+        let mut is_changed = false;
+        for line in payload.lines() {
+            match self.lint.feed(line) {
+                Ok(is_line_changed) => is_changed |= is_line_changed,
+                Err(message) => {
+                    self.errors.push(PipeError {
+                        line: line.to_owned(),
+                        message,
+                    });
+                    is_changed = true;
+                }
+            }
+        }
+        is_changed && self.render_throttle.request()
+    }
+
+    /// Applies the changes decoded from a line, errors are reported for the line
+    fn handle_line(&mut self, line: TestLine, restart: Restart) -> Result<(), String> {
+        for change in self.test_stream.feed(line)? {
+            self.apply_change(change, restart);
+        }
+        Ok(())
+    }
+
+    fn handle_payload(&mut self, payload: &str, restart: Restart) -> Result<(), String> {
+        for change in self.test_stream.feed_line(payload)? {
+            self.apply_change(change, restart);
+        }
+        Ok(())
+    }
+
+    fn apply_change(&mut self, change: ModelChange, restart: Restart) {
+        match &change {
+            ModelChange::PackageStarted {
+                time: Some(time), ..
+            }
+            | ModelChange::TestStarted {
+                time: Some(time), ..
+            } => self.summary.add_start_time(*time),
+            ModelChange::TestFinished {
+                time: Some(time), ..
+            }
+            | ModelChange::PackageFinished {
+                time: Some(time), ..
+            } => self.summary.add_finish_time(*time),
+            _ => (),
+        }
+        match change {
+            ModelChange::PackageStarted { package, time } => {
+                let mut new_package = Package::new(package);
+                new_package.started_at = time;
+
+                if let Some(package) = self
+                    .tests_screen
+                    .packages
+                    .iter_mut()
+                    .find(|package| package.name == new_package.name)
+                {
+                    package.is_aborted = false;
+                    package.timeout = None;
+                    package.timed_out_test = None;
+                    package.fuzz_progress = None;
+                    if package.is_building {
+                        package.is_building = false;
+                        package.started_at = new_package.started_at;
+                    } else if restart == Restart::RetryFailed
+                        && package
+                            .result_or_derived()
+                            .is_some_and(|result| result.is_failure())
+                    {
+                        // `gotestsum --rerun-fails` starts a failed package again to retry its
+                        // failed tests, which restart in place keeping their earlier results
+                        start_package_attempt(&mut self.summary, package);
+                        package.started_at = new_package.started_at;
+                        package.is_retrying = true;
+                    } else if restart != Restart::Rerun {
+                        // Another run of the package, e.g. piped in without starting a new run,
+                        // whose tests get numbered next to the ones of the earlier attempts
+                        start_package_attempt(&mut self.summary, package);
+                        package.started_at = new_package.started_at;
+                    }
+                } else {
+                    self.tests_screen.packages.push(new_package);
+                }
+            }
+            ModelChange::TestFinished {
+                package,
+                test,
+                result,
+                elapsed,
+                time,
+            } => {
+                let Some(package) = find_package_mut(&mut self.tests_screen.packages, &package)
+                else {
+                    return;
+                };
+                let timed_out_test = package.timed_out_test.clone();
+                let Some(test) = TestCase::find_in_mut(&mut package.tests, &test) else {
+                    return;
+                };
+                let result = if result != TestResult::Fail {
+                    result
+                } else if test.has_data_race {
+                    TestResult::Race
+                } else if timed_out_test.as_deref() == Some(test.name.as_str()) {
+                    TestResult::Timeout
+                } else {
+                    result
+                };
+                self.summary.remove_test(test, self.config.slow_threshold);
+                package
+                    .summary
+                    .remove_test(test, self.config.slow_threshold);
+                test.result = Some(result);
+                test.elapsed = elapsed.or_else(|| elapsed_since(test.started_at, time));
+                self.summary.add_test(test, self.config.slow_threshold);
+                package.summary.add_test(test, self.config.slow_threshold);
+                test.state = RunState::Finished;
+                if result.is_failure() {
+                    test.failure_message = failure_message(&test.log);
+                }
+                if result == TestResult::Skip {
+                    test.skip_reason = test
+                        .log
+                        .iter()
+                        .rev()
+                        .find_map(|line| SourceLocation::strip_from_line(line))
+                        .map(ansi::strip);
+                }
+            }
+            ModelChange::PackageFinished {
+                package,
+                result,
+                elapsed,
+                time,
+                failed_build,
+            } => {
+                let Some(package) = find_package_mut(&mut self.tests_screen.packages, &package)
+                else {
+                    return;
+                };
+                package.is_retrying = false;
+                self.summary.remove_package_result(package);
+                package.result = Some(if failed_build {
+                    TestResult::BuildFail
+                } else if result == TestResult::Fail && package.timeout.is_some() {
+                    TestResult::Timeout
+                } else if result == TestResult::Skip && has_no_test_files(&package.log) {
+                    TestResult::NoTests
+                } else {
+                    result
+                });
+                self.summary.add_package_result(package);
+                // The timed out test only gets a result of its own from recent go versions
+                let timeout = package.timeout.clone();
+                if let Some(test) = package
+                    .timed_out_test
+                    .clone()
+                    .and_then(|test_name| TestCase::find_in_mut(&mut package.tests, &test_name))
+                    .filter(|test| test.result.is_none())
+                {
+                    self.summary.remove_test(test, self.config.slow_threshold);
+                    package
+                        .summary
+                        .remove_test(test, self.config.slow_threshold);
+                    test.result = Some(TestResult::Timeout);
+                    test.state = RunState::Finished;
+                    test.failure_message = timeout;
+                    self.summary.add_test(test, self.config.slow_threshold);
+                    package.summary.add_test(test, self.config.slow_threshold);
+                }
+                if let Some(previous_elapsed) = package.elapsed.take() {
+                    self.summary.remove_elapsed(previous_elapsed);
+                }
+                let elapsed = elapsed.or_else(|| elapsed_since(package.started_at, time));
+                if let Some(elapsed) = elapsed {
+                    self.summary.add_elapsed(elapsed);
+                }
+                package.elapsed = elapsed;
+            }
+            ModelChange::TestStarted {
+                package,
+                test,
+                time,
+            } => {
+                let Some(package) = find_package_mut(&mut self.tests_screen.packages, &package)
+                else {
+                    return;
+                };
+                // Rerun and retried tests restart in place, keeping the results of their
+                // subtests until those run again
+                let is_retrying = package.is_retrying;
+                let existing_test = TestCase::find_in_mut(&mut package.tests, &test)
+                    .filter(|_| restart == Restart::Rerun || is_retrying);
+                if let Some(test) = existing_test {
+                    self.summary.remove_test(test, self.config.slow_threshold);
+                    package
+                        .summary
+                        .remove_test(test, self.config.slow_threshold);
+                    if let Some(previous_result) = test.restart(time) {
+                        if is_retrying {
+                            test.earlier_results.push(previous_result);
+                        }
+                    }
+                    self.summary.add_test(test, self.config.slow_threshold);
+                    package.summary.add_test(test, self.config.slow_threshold);
+                } else {
+                    let mut test = TestCase::new(test);
+                    test.started_at = time;
+                    test.attempt = package.next_attempt(&test.name);
+                    self.summary.add_test(&test, self.config.slow_threshold);
+                    package.summary.add_test(&test, self.config.slow_threshold);
+                    package.add_test(test);
+                }
+            }
+            ModelChange::TestPaused { package, test } => {
+                self.set_test_state(&package, &test, RunState::Paused)
+            }
+            ModelChange::TestContinued { package, test } => {
+                self.set_test_state(&package, &test, RunState::Running)
+            }
+            ModelChange::Output {
+                package: package_name,
+                test,
+                output,
+                time,
+            } => {
+                let Some(package) =
+                    find_package_mut(&mut self.tests_screen.packages, &package_name)
+                else {
+                    return;
+                };
+                if FuzzProgress::is_status_line(&output) {
+                    package
+                        .fuzz_progress
+                        .get_or_insert_with(FuzzProgress::default)
+                        .update(&output);
+                    return;
+                }
+                if let (Some(fuzz_progress), Some(_)) =
+                    (&mut package.fuzz_progress, parse_fuzz_input(&output))
+                {
+                    fuzz_progress.crashers += 1;
+                }
+                package.scan_timeout(&output);
+                if let Some(benchmark) = Benchmark::parse(&output) {
+                    package.add_benchmark(benchmark);
+                }
+                if let Some(test) = test.as_deref().and_then(|test| package.find_test_mut(test)) {
+                    if is_data_race_report(&output) {
+                        test.has_data_race = true;
+                    }
+                    if let Some(fuzz_input) = parse_fuzz_input(&output) {
+                        test.fuzz_input = Some(fuzz_input.to_owned());
+                    }
+                    log_times::push(
+                        &mut test.log,
+                        &mut test.log_times,
+                        output,
+                        time,
+                        self.config.log_limits.per_log,
+                    );
+                } else {
+                    if let Some(coverage) = parse_coverage(&output) {
+                        package.coverage = Some(coverage);
+                    }
+                    log_times::push(
+                        &mut package.log,
+                        &mut package.log_times,
+                        output,
+                        time,
+                        self.config.log_limits.per_log,
+                    );
+                }
+                self.count_log_line();
+            }
+            ModelChange::BuildOutput {
+                package,
+                output,
+                time,
+            } => {
+                let per_log_limit = self.config.log_limits.per_log;
+                let package = self.build_package_mut(&package);
+                log_times::push(
+                    &mut package.log,
+                    &mut package.log_times,
+                    output,
+                    time,
+                    per_log_limit,
+                );
+                self.count_log_line();
+            }
+            ModelChange::BuildFailed { package } => {
+                // A building package has no result unless its build failure was reported already
+                self.build_package_mut(&package);
+                let Some(package) = find_package_mut(&mut self.tests_screen.packages, &package)
+                else {
+                    return;
+                };
+                self.summary.remove_package_result(package);
+                package.result = Some(TestResult::BuildFail);
+                self.summary.add_package_result(package);
+            }
+        }
+    }
+
+    fn set_test_state(&mut self, package_name: &str, test_name: &str, state: RunState) {
+        if let Some(test) = find_package_mut(&mut self.tests_screen.packages, package_name)
+            .and_then(|package| package.find_test_mut(test_name))
+        {
+            test.state = state;
+        }
+    }
+
+    /// Counts a line appended to a log, dropping the oldest lines of the longest logs once the
+    /// current run exceeds the total limit
+    fn count_log_line(&mut self) {
+        let limit = self.config.log_limits.total;
+        self.log_line_count += 1;
+        if limit == 0 || self.log_line_count <= limit {
+            return;
+        }
+        let mut logs = self
+            .tests_screen
+            .packages
+            .iter_mut()
+            .flat_map(Package::logs_mut)
+            .collect::<Vec<_>>();
+        let mut total = logs.iter().map(|(log, _)| log.len()).sum::<usize>();
+        if total > limit {
+            let target = log_limit::kept_lines(limit);
+            while total > target {
+                let Some((longest, log_times)) = logs.iter_mut().max_by_key(|(log, _)| log.len())
+                else {
+                    break;
+                };
+                let keep = longest.len() / 2;
+                let removed = log_limit::truncate(longest, keep);
+                if removed == 0 {
+                    break;
+                }
+                log_times.on_lines_dropped();
+                total -= removed;
+            }
+        }
+        self.log_line_count = total;
+    }
+
+    /// Returns the package a build action refers to. A package left over from a previous run
+    /// starts its next attempt, as build actions arrive before the package's `start` action.
+    fn build_package_mut(&mut self, package_name: &str) -> &mut Package {
+        let packages = &mut self.tests_screen.packages;
+        let index = match packages
+            .iter()
+            .position(|package| package.name == package_name)
+        {
+            Some(index) => {
+                if !packages[index].is_building {
+                    start_package_attempt(&mut self.summary, &mut packages[index]);
+                }
+                index
+            }
+            None => {
+                packages.push(Package::new(package_name));
+                packages.len() - 1
+            }
+        };
+        let package = &mut packages[index];
+        package.is_building = true;
+        package
+    }
+
+    /// Drops the results of tests that are about to be run again
+    fn clear_results(&mut self, reruns: &[runner::Rerun]) {
+        for rerun in reruns {
+            let Some(package) = self
+                .tests_screen
+                .packages
+                .iter_mut()
+                .find(|package| package.name == rerun.package)
+            else {
+                continue;
+            };
+            clear_package_result(&mut self.summary, package);
+            let summary = &mut self.summary;
+            let package_summary = &mut package.summary;
+            let slow_threshold = self.config.slow_threshold;
+            package.tests.retain(|test| {
+                let is_rerun = rerun.tests.contains(&test.name);
+                if is_rerun {
+                    summary.remove_test_case(test, slow_threshold);
+                    package_summary.remove_test_case(test, slow_threshold);
+                }
+                !is_rerun
+            });
+            package.count_attempts();
+        }
+        self.tests_screen.mark_packages_changed();
+    }
+}
+
+/// The feature a command of the tests screen uses that needs permissions, copying to the clipboard
+/// doesn't as it's done with OSC 52
+fn required_permission(command: &tests_screen::UpdateCommand) -> Option<&'static str> {
+    match command {
+        tests_screen::UpdateCommand::RerunFailed(_) | tests_screen::UpdateCommand::RerunTest(_) => {
+            Some("Rerunning tests")
+        }
+        tests_screen::UpdateCommand::ShowRunScreen => Some("Running tests"),
+        tests_screen::UpdateCommand::AbortRun => Some("Aborting a run"),
+        tests_screen::UpdateCommand::OpenSource { .. } => Some("Opening files"),
+        _ => None,
+    }
+}
+
+/// The package a change refers to. [`TestStream`] starts the packages and tests of lines arriving
+/// without their start, so only the changes of packages cleared since are left without one.
+fn find_package_mut<'a>(packages: &'a mut [Package], name: &str) -> Option<&'a mut Package> {
+    packages.iter_mut().find(|package| package.name == name)
+}
+
+/// Starts another attempt of a package that ran before, dropping the package's own result while
+/// the tests of the earlier attempts stay next to the ones to come
+fn start_package_attempt(summary: &mut Summary, package: &mut Package) {
+    clear_package_result(summary, package);
+    package.attempt += 1;
+}
+
+/// Drops the package's own result and elapsed time along with their counts in the summary, e.g.
+/// before the package runs again
+fn clear_package_result(summary: &mut Summary, package: &mut Package) {
+    summary.remove_package_result(package);
+    package.result = None;
+    if let Some(elapsed) = package.elapsed.take() {
+        summary.remove_elapsed(elapsed);
+    }
+}
+
+/// Number shown after the name of a package or test that ran repeatedly, e.g. ` #2` for its
+/// second attempt, empty for the first
+fn attempt_suffix(attempt: usize) -> String {
+    if attempt == 0 {
+        String::new()
+    } else {
+        format!(" #{}", attempt + 1)
+    }
+}
+
+/// Packages of the run shown, the viewed run from the history or else the current run. Takes the
+/// fields apart, so a screen borrowed mutably alongside them can read the packages.
+fn shown_packages<'a>(
+    viewed_run: &'a Option<ViewedRun>,
+    tests_screen: &'a TestsScreen,
+) -> &'a [Package] {
+    match viewed_run {
+        Some(viewed_run) => &viewed_run.screen.packages,
+        None => &tests_screen.packages,
+    }
+}
+
+/// Whether any package of a run is still running
+fn is_any_running(packages: &[Package]) -> bool {
+    packages.iter().any(|package| {
+        package.result.is_none() && package.derived_status() == DerivedStatus::Running
+    })
+}
+
+/// Whether go skipped a package for not having test files, e.g. `?   \texample.com/foo\t[no test files]`
+fn has_no_test_files(log: &[String]) -> bool {
+    log.iter()
+        .rev()
+        .any(|line| line.trim_end().ends_with("[no test files]"))
+}
+
+/// Seconds between two timestamps, if both are known
+fn elapsed_since(started_at: Option<f64>, time: Option<f64>) -> Option<f64> {
+    Some((time? - started_at?).max(0.0))
+}
+
+/// Strips the test binary suffix from an import path, e.g. `example.com/foo [example.com/foo.test]`
+fn package_name_from_import_path(import_path: &str) -> &str {
+    import_path
+        .split_once(" [")
+        .map_or(import_path, |(package_name, _)| package_name)
+}
+
+/// Picks the line explaining a failure from a test's log: a testify style `Error:` line, else the
+/// first `t.Error`/`t.Fatal` message, else a panic
+fn failure_message(log: &[String]) -> Option<String> {
+    log.iter()
+        .find_map(|line| {
+            let (_, message) = line.split_once("Error:")?;
+            Some(message.trim())
+        })
+        .filter(|message| !message.is_empty())
+        .or_else(|| {
+            log.iter()
+                .filter_map(|line| SourceLocation::strip_from_line(line))
+                .find(|message| !message.is_empty())
+        })
+        .or_else(|| {
+            log.iter()
+                .map(|line| line.trim())
+                .find(|line| line.starts_with("panic: "))
+        })
+        .map(ansi::strip)
+}
+
+/// Extracts the message of the panic an elapsed `-timeout` causes, e.g. `test timed out after 1s`
+fn timeout_message(output: &str) -> Option<&str> {
+    output
+        .trim()
+        .strip_prefix("panic: ")
+        .filter(|message| message.starts_with("test timed out after "))
+}
+
+/// Extracts the running test from the lines following a timeout's panic: the `running tests:`
+/// list of recent go versions, e.g. `\t\tTestSlow (10m0s)`, else the first test function in the
+/// goroutine dump, e.g. `example.com/pkg.TestSlow.func1()`
+fn timed_out_test(output: &str) -> Option<String> {
+    let line = output.trim_end();
+    if let Some(entry) = line.strip_prefix("\t\t") {
+        return entry
+            .split_once(" (")
+            .map(|(test_name, _)| test_name)
+            .filter(|test_name| test_name.starts_with("Test"))
+            .map(str::to_owned);
+    }
+    let (function, _) = line.split_once('(')?;
+    // Package paths may contain dots, the function name follows the last slash
+    let function = function.rsplit('/').next().unwrap_or(function);
+    let (_, test_name) = function.split_once(".Test")?;
+    let test_name = test_name.split('.').next().unwrap_or(test_name);
+    Some(format!("Test{}", test_name))
+}
+
+/// Extracts the corpus path of a failing fuzz input, e.g. `testdata/fuzz/FuzzParse/582528ddfad69eb5`
+/// of `Failing input written to testdata/fuzz/FuzzParse/582528ddfad69eb5`
+fn parse_fuzz_input(output: &str) -> Option<&str> {
+    let (_, path) = output.split_once("Failing input written to ")?;
+    Some(path.trim()).filter(|path| !path.is_empty())
+}
+
+/// Whether an output line is part of a report of the race detector
+fn is_data_race_report(output: &str) -> bool {
+    output.contains("WARNING: DATA RACE")
+        || output.contains("race detected during execution of test")
+}
+
+/// Extracts the percentage from a coverage line, e.g. `coverage: 73.5% of statements`
+fn parse_coverage(output: &str) -> Option<f32> {
+    let (_, rest) = output.split_once("coverage: ")?;
+    let (percentage, _) = rest.split_once('%')?;
+    percentage.parse().ok()
+}
+
+// The tests run natively, where the plugin's host functions don't link
+#[cfg(not(test))]
+register_plugin!(GoTestsPlugin);
+#[cfg(not(test))]
+register_worker!(search_worker::SearchWorker, search_worker, SEARCH_WORKER);
+
+/// Stands in for the registration in native test builds, so what only the plugin's entry points
+/// reach isn't reported as dead code there
+#[cfg(test)]
+#[allow(dead_code)]
+fn entry_points() {
+    let _ = <GoTestsPlugin as ZellijPlugin>::load;
+    let _ = <GoTestsPlugin as ZellijPlugin>::update;
+    let _ = <GoTestsPlugin as ZellijPlugin>::pipe;
+    let _ = <GoTestsPlugin as ZellijPlugin>::render;
+    let _ = <search_worker::SearchWorker as ZellijWorker>::on_message;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A run of `example.com/api` whose only test fails, then passes when it runs again
+    const RUNS: [[&str; 4]; 2] = [
+        [
+            r#"{"Action":"start","Package":"example.com/api"}"#,
+            r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+            r#"{"Action":"fail","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+            r#"{"Action":"fail","Package":"example.com/api","Elapsed":0.2}"#,
+        ],
+        [
+            r#"{"Action":"start","Package":"example.com/api"}"#,
+            r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+            r#"{"Action":"pass","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+            r#"{"Action":"pass","Package":"example.com/api","Elapsed":0.2}"#,
+        ],
+    ];
+
+    fn feed(plugin: &mut GoTestsPlugin, run: &[&str], restart: Restart) {
+        for line in run {
+            plugin
+                .handle_payload(line, restart)
+                .expect("Line should be valid");
+        }
+    }
+
+    fn feed_runs(restart: Restart) -> GoTestsPlugin {
+        let mut plugin = GoTestsPlugin::default();
+        for run in RUNS {
+            feed(&mut plugin, &run, restart);
+        }
+        plugin
+    }
+
+    #[test]
+    fn keeps_the_tests_of_a_package_started_again_next_to_each_other() {
+        let plugin = feed_runs(Restart::Attempt);
+        let package = &plugin.tests_screen.packages[0];
+        assert_eq!(package.attempt, 1);
+        assert_eq!(package.result, Some(TestResult::Pass));
+        let attempts = package
+            .tests
+            .iter()
+            .map(|test| (test.attempt, test.result))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attempts,
+            [(0, Some(TestResult::Fail)), (1, Some(TestResult::Pass))]
+        );
+        assert!(!package.tests[1].is_flaky());
+        assert_eq!(plugin.summary.failed, 1);
+        assert_eq!(plugin.summary.passed, 1);
+    }
+
+    #[test]
+    fn retries_the_failed_tests_of_gotestsum_in_place() {
+        let plugin = feed_runs(Restart::RetryFailed);
+        let package = &plugin.tests_screen.packages[0];
+        assert_eq!(package.tests.len(), 1);
+        assert_eq!(package.tests[0].result, Some(TestResult::Pass));
+        assert_eq!(package.tests[0].earlier_results, [TestResult::Fail]);
+        assert!(package.tests[0].is_flaky());
+        assert_eq!(plugin.summary.failed, 0);
+    }
+
+    #[test]
+    fn leaves_the_restart_of_a_passed_package_to_another_attempt_with_gotestsum() {
+        let mut plugin = GoTestsPlugin::default();
+        for _ in 0..2 {
+            feed(&mut plugin, &RUNS[1], Restart::RetryFailed);
+        }
+        let package = &plugin.tests_screen.packages[0];
+        assert_eq!(package.tests.len(), 2);
+        assert!(package
+            .tests
+            .iter()
+            .all(|test| test.earlier_results.is_empty()));
+    }
+}
@@ -0,0 +1,48 @@
+//! With `t.Parallel()`, test2json attributes output by the `=== RUN`, `=== CONT` and `=== NAME`
+//! markers go prints when switching between tests, and output printed outside of `t.Log`, e.g. by
+//! goroutines writing to stdout, ends up with no test at all. The markers and the test actions
+//! are tracked to tell which test such output most likely came from.
+
+/// A line of `go test -v` output switching the test the following output belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Marker<'a> {
+    /// `=== RUN`, `=== CONT` or `=== NAME`, the named test writes the following output
+    Enter(&'a str),
+    /// `=== PAUSE`, the named test stops writing output
+    Pause(&'a str),
+}
+
+/// Parses a marker, e.g. `=== CONT  TestFoo/case_a`
+pub(crate) fn marker(output: &str) -> Option<Marker<'_>> {
+    let (kind, test) = output.trim_end().strip_prefix("=== ")?.split_once(' ')?;
+    let test = test.trim_start();
+    if test.is_empty() {
+        return None;
+    }
+    match kind {
+        "RUN" | "CONT" | "NAME" => Some(Marker::Enter(test)),
+        "PAUSE" => Some(Marker::Pause(test)),
+        _ => None,
+    }
+}
+
+/// Whether an output line is printed by the test binary or go itself rather than a test, e.g.
+/// `PASS`, `ok  \texample.com/api\t0.1s`, `coverage: 80.0% of statements` or a panic ending the
+/// run
+pub(crate) fn is_package_output(output: &str) -> bool {
+    let output = output.trim();
+    matches!(output, "PASS" | "FAIL")
+        || [
+            "ok ",
+            "FAIL\t",
+            "?   ",
+            "coverage: ",
+            "exit status ",
+            "testing: ",
+            "panic: ",
+            "=== ",
+            "--- ",
+        ]
+        .iter()
+        .any(|prefix| output.starts_with(prefix))
+}
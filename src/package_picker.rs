@@ -0,0 +1,200 @@
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    keymap::ScreenAction, name::Name, theme, viewport::Viewport, DerivedStatus, Package, RunState,
+};
+
+const MAX_WIDTH: usize = 80;
+const MAX_HEIGHT: usize = 24;
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+    /// Shows only the package in the tests screen
+    Select {
+        package: Name,
+    },
+}
+
+/// Keys of the package picker
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum PickerAction {
+    Exit,
+    Select,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+}
+
+impl ScreenAction for PickerAction {
+    const SCREEN: &'static str = "Package picker";
+
+    fn description(&self) -> &'static str {
+        match self {
+            PickerAction::Exit => "back to the tests",
+            PickerAction::Select => "show only the package in the tests",
+            PickerAction::Down => "select the next package",
+            PickerAction::Up => "select the previous package",
+            PickerAction::PageDown => "move the selection down a page",
+            PickerAction::PageUp => "move the selection up a page",
+            PickerAction::First => "select the first package",
+            PickerAction::Last => "select the last package",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            PickerAction::Exit => &["Esc"],
+            PickerAction::Select => &["Enter"],
+            PickerAction::Down => &["Down", "j"],
+            PickerAction::Up => &["Up", "k"],
+            PickerAction::PageDown => &["PageDown", "f"],
+            PickerAction::PageUp => &["PageUp", "b"],
+            PickerAction::First => &["Home"],
+            PickerAction::Last => &["End"],
+        }
+    }
+}
+
+/// Popup listing the packages with their status, for narrowing the tests screen down to one
+#[derive(Debug, Default)]
+pub(crate) struct PackagePicker {
+    viewport: Viewport,
+}
+
+impl PackagePicker {
+    /// Opens the picker on the package the tests screen is narrowed down to, if any
+    pub(crate) fn new(packages: &[Package], selected: Option<&str>) -> Self {
+        let mut viewport = Viewport::default();
+        viewport.selected = selected
+            .and_then(|name| packages.iter().position(|package| package.name == name))
+            .unwrap_or_default();
+        Self { viewport }
+    }
+
+    pub(crate) fn update(&mut self, event: Event, packages: &[Package]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match PickerAction::from_key(&key)? {
+            PickerAction::Exit => Some(UpdateCommand::ExitScreen),
+            PickerAction::Select => {
+                packages
+                    .get(self.viewport.selected)
+                    .map(|package| UpdateCommand::Select {
+                        package: package.name.clone(),
+                    })
+            }
+            PickerAction::Down => {
+                self.viewport.select_next(1, packages.len());
+                Some(UpdateCommand::Render)
+            }
+            PickerAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            PickerAction::PageDown => {
+                self.viewport
+                    .select_next(self.viewport.page(), packages.len());
+                Some(UpdateCommand::Render)
+            }
+            PickerAction::PageUp => {
+                self.viewport.select_previous(self.viewport.page());
+                Some(UpdateCommand::Render)
+            }
+            PickerAction::First => {
+                self.viewport.select(0, packages.len());
+                Some(UpdateCommand::Render)
+            }
+            PickerAction::Last => {
+                self.viewport.select(usize::MAX, packages.len());
+                Some(UpdateCommand::Render)
+            }
+        }
+    }
+
+    /// Draws the popup centered over the screen below it
+    pub(crate) fn render(&mut self, rows: usize, cols: usize, packages: &[Package]) {
+        let theme = theme::current();
+        let width = cols.saturating_sub(4).min(MAX_WIDTH);
+        let height = rows.saturating_sub(2).min(MAX_HEIGHT);
+        // Room for the borders and a row of packages
+        if width < 10 || height < 3 {
+            return;
+        }
+        let x = (cols - width) / 2;
+        let y = (rows - height) / 2;
+        let inner_width = width - 4;
+        self.viewport.resize(inner_width, height - 2);
+        self.viewport.clamp_selection(packages.len());
+        self.viewport.follow_selection(packages.len());
+
+        let print_line = |text: Text, line: usize| {
+            print_text_with_coordinates(text, x, y + line, Some(width), Some(1));
+        };
+        let title = " packages ";
+        print_line(
+            Text::new(format!(
+                "┌{}{}┐",
+                title,
+                "─".repeat(width.saturating_sub(title.chars().count() + 2))
+            ))
+            .color_range(theme.accent, 1..1 + title.len()),
+            0,
+        );
+        let range = self.viewport.visible_range(packages.len());
+        for line in 0..self.viewport.height() {
+            let index = range.start + line;
+            let text = match packages.get(index).filter(|_| range.contains(&index)) {
+                Some(package) => {
+                    let (marker_color, marker_char) = marker(package);
+                    let counts = format!(
+                        " {} tests, {} failed",
+                        package.summary.tests, package.summary.failed
+                    );
+                    let name_width = inner_width.saturating_sub(counts.chars().count() + 2);
+                    let name = package.name.chars().take(name_width).collect::<String>();
+                    let padding = " ".repeat(name_width.saturating_sub(name.chars().count()));
+                    let mut text =
+                        Text::new(format!("│ {} {}{}{} │", marker_char, name, padding, counts))
+                            .color_range(marker_color, 2..3);
+                    if index == self.viewport.selected {
+                        text = theme.select(text);
+                    }
+                    text
+                }
+                None => Text::new(format!("│{}│", " ".repeat(width - 2))),
+            };
+            print_line(text, 1 + line);
+        }
+        let hint = " [Enter] show only, [Esc] close ";
+        print_line(
+            Text::new(format!(
+                "└{}{}┘",
+                "─".repeat(width.saturating_sub(hint.chars().count() + 2)),
+                hint
+            )),
+            height - 1,
+        );
+    }
+}
+
+/// Marker of the package's result, or of its derived status while it has none
+fn marker(package: &Package) -> (usize, char) {
+    match (package.result_or_derived(), package.derived_status()) {
+        (Some(test_result), _) => (test_result.marker_color(), test_result.marker_char()),
+        (None, DerivedStatus::Aborted) => (
+            RunState::Aborted.marker_color(),
+            RunState::Aborted.marker_char(),
+        ),
+        (None, _) => (
+            RunState::Running.marker_color(),
+            RunState::Running.marker_char(),
+        ),
+    }
+}
@@ -0,0 +1,28 @@
+use std::{collections::BTreeMap, path::Path};
+
+use zellij_tile::prelude::*;
+
+use crate::{export, logs_screen::LogSource, runner};
+
+/// Context value of the command panes showing a log in the user's pager
+pub(crate) const PAGER_CONTEXT: &str = "pager";
+/// Where the paged log gets written, a hidden file in zellij's cwd the pane removes once the pager
+/// quits
+const PAGER_PATH: &str = ".zj-go-tests-{test}.log";
+
+/// Writes the log to a temporary file and opens it in a pane running `$PAGER`, `less` if unset
+pub(crate) fn open(source: &LogSource, contents: &str, cwd: &Path) -> Result<(), String> {
+    let path = export::log_path(PAGER_PATH, source);
+    export::write_report(&path, contents)?;
+    let path = path.to_string_lossy();
+    let mut command = CommandToRun::new_with_args(
+        "sh",
+        vec!["-c", "${PAGER:-less} \"$1\"; rm -f \"$1\"", "sh", &path],
+    );
+    command.cwd = Some(cwd.to_path_buf());
+    open_command_pane(
+        command,
+        BTreeMap::from([(runner::CONTEXT_KEY.to_owned(), PAGER_CONTEXT.to_owned())]),
+    );
+    Ok(())
+}
@@ -0,0 +1,773 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+use crate::{
+    output_scope::{self, Marker},
+    package_name_from_import_path, timestamp, TestResult,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, AsRefStr)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Action {
+    Start,
+    Run,
+    Output,
+    Pass,
+    Fail,
+    Skip,
+    Pause,
+    Cont,
+    #[serde(rename = "build-output")]
+    BuildOutput,
+    #[serde(rename = "build-fail")]
+    BuildFail,
+}
+
+impl TryFrom<Action> for TestResult {
+    type Error = String;
+
+    fn try_from(value: Action) -> Result<Self, Self::Error> {
+        match value {
+            Action::Pass => Ok(TestResult::Pass),
+            Action::Fail => Ok(TestResult::Fail),
+            Action::Skip => Ok(TestResult::Skip),
+            Action::BuildFail => Ok(TestResult::BuildFail),
+            action => Err(format!(
+                "Action `{}` is not a valid TestResult",
+                action.as_ref()
+            )),
+        }
+    }
+}
+
+/// A line of `go test -json` output. Unknown fields are ignored and lowercase keys are accepted,
+/// as emitted by some wrappers of `test2json`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all(deserialize = "PascalCase"))]
+pub(crate) struct TestLine {
+    #[serde(alias = "action")]
+    pub(crate) action: Option<Action>,
+    /// Falls back to `ImportPath` when absent
+    #[serde(alias = "package")]
+    pub(crate) package: Option<String>,
+    #[serde(alias = "test")]
+    pub(crate) test: Option<String>,
+    #[serde(alias = "output")]
+    pub(crate) output: Option<String>,
+    #[serde(alias = "elapsed")]
+    pub(crate) elapsed: Option<f64>,
+    /// RFC 3339 timestamp, used to compute elapsed times when `Elapsed` is absent
+    #[serde(alias = "time")]
+    pub(crate) time: Option<String>,
+    #[serde(alias = "import_path", alias = "importPath")]
+    pub(crate) import_path: Option<String>,
+    #[serde(alias = "failed_build", alias = "failedBuild")]
+    pub(crate) failed_build: Option<String>,
+}
+
+/// A change to the packages and tests, decoded from a line of `go test -json` output. Times are
+/// in seconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ModelChange {
+    /// The test binary of a package started, or its first test did when go doesn't report the
+    /// start, as before go 1.20
+    PackageStarted {
+        package: String,
+        time: Option<f64>,
+    },
+    TestStarted {
+        package: String,
+        test: String,
+        time: Option<f64>,
+    },
+    TestPaused {
+        package: String,
+        test: String,
+    },
+    TestContinued {
+        package: String,
+        test: String,
+    },
+    /// A line of a package's output, or of one of its tests'
+    Output {
+        package: String,
+        test: Option<String>,
+        output: String,
+        time: Option<f64>,
+    },
+    TestFinished {
+        package: String,
+        test: String,
+        result: TestResult,
+        elapsed: Option<f64>,
+        time: Option<f64>,
+    },
+    PackageFinished {
+        package: String,
+        result: TestResult,
+        elapsed: Option<f64>,
+        time: Option<f64>,
+        /// Whether the package failed as one of its dependencies failed to build
+        failed_build: bool,
+    },
+    /// A line of the compiler's output for a package, before its test binary runs
+    BuildOutput {
+        package: String,
+        output: String,
+        time: Option<f64>,
+    },
+    BuildFailed {
+        package: String,
+    },
+}
+
+/// What's known about a package whose test binary is running
+#[derive(Debug, Default)]
+struct PackageScope {
+    /// Tests that started, including the ones that finished since
+    tests: BTreeSet<String>,
+    /// Tests that started and neither paused nor finished
+    running: BTreeSet<String>,
+    /// The test the last marker or test action switched to, see [`output_scope`]
+    output_test: Option<String>,
+}
+
+impl PackageScope {
+    /// Follows the markers and actions switching between tests, which name the test to switch to,
+    /// or the test that paused or finished
+    fn switch_output_test(&mut self, marker: Marker) {
+        match marker {
+            Marker::Enter(test) => self.output_test = Some(test.to_owned()),
+            Marker::Pause(test) if self.output_test.as_deref() == Some(test) => {
+                self.output_test = None
+            }
+            Marker::Pause(_) => (),
+        }
+    }
+
+    /// The running test an output line without a test most likely came from. A panic ends the
+    /// attribution, as the goroutine dump following it is the binary's.
+    fn unattributed_output_test(&mut self, output: &str) -> Option<String> {
+        if output_scope::is_package_output(output) {
+            if output.trim().starts_with("panic: ") {
+                self.output_test = None;
+            }
+            return None;
+        }
+        self.output_test
+            .clone()
+            .filter(|test| self.running.contains(test))
+    }
+}
+
+/// Turns the `go test -json` lines of a stream into changes of the model. Lines arriving out of
+/// order, e.g. the output of a test before its `run` action, or the tests of old go versions
+/// that don't report the start of packages, get the missing start changes first.
+#[derive(Debug, Default)]
+pub(crate) struct TestStream {
+    /// Packages that started and didn't finish yet, by name
+    packages: BTreeMap<String, PackageScope>,
+    /// Packages that finished, whose late output lines don't start them again
+    finished: BTreeSet<String>,
+}
+
+impl TestStream {
+    /// Decodes a line of `go test -json` output
+    pub(crate) fn feed_line(&mut self, line: &str) -> Result<Vec<ModelChange>, String> {
+        let line = serde_json::from_str(line)
+            .map_err(|err| format!("Failed to deserialize Go test line json: {}", err))?;
+        self.feed(line)
+    }
+
+    /// Decodes a deserialized line, or one emitted by the parser of plain output
+    pub(crate) fn feed(&mut self, line: TestLine) -> Result<Vec<ModelChange>, String> {
+        let Some(action) = line.action else {
+            return Ok(Vec::new());
+        };
+        let time = line.time.as_deref().and_then(timestamp::parse);
+        let mut changes = Vec::new();
+        if let Action::BuildOutput | Action::BuildFail = action {
+            let import_path = line
+                .import_path
+                .as_deref()
+                .ok_or_else(|| format!("Expected import path in `{}` action", action.as_ref()))?;
+            let package = package_name_from_import_path(import_path).to_owned();
+            changes.push(match action {
+                Action::BuildOutput => ModelChange::BuildOutput {
+                    package,
+                    output: line
+                        .output
+                        .ok_or("Expected output in `BuildOutput` action")?,
+                    time,
+                },
+                _ => ModelChange::BuildFailed { package },
+            });
+            return Ok(changes);
+        }
+        let package = line
+            .package
+            .or_else(|| {
+                line.import_path
+                    .as_deref()
+                    .map(|import_path| package_name_from_import_path(import_path).to_owned())
+            })
+            .ok_or_else(|| format!("Expected name for package in `{}` action", action.as_ref()))?;
+        match action {
+            Action::Start => {
+                self.packages
+                    .insert(package.clone(), PackageScope::default());
+                changes.push(ModelChange::PackageStarted { package, time });
+            }
+            Action::Run => {
+                let test = line.test.ok_or("Expected test name in `Run` action")?;
+                let scope = self.start_package(&package, time, &mut changes);
+                scope.tests.insert(test.clone());
+                scope.running.insert(test.clone());
+                scope.switch_output_test(Marker::Enter(&test));
+                changes.push(ModelChange::TestStarted {
+                    package,
+                    test,
+                    time,
+                });
+            }
+            Action::Pause | Action::Cont => {
+                let test = line
+                    .test
+                    .ok_or_else(|| format!("Expected test name in `{}` action", action.as_ref()))?;
+                let scope = self.start_test(&package, &test, time, &mut changes);
+                if let Action::Pause = action {
+                    scope.running.remove(&test);
+                    scope.switch_output_test(Marker::Pause(&test));
+                    changes.push(ModelChange::TestPaused { package, test });
+                } else {
+                    scope.running.insert(test.clone());
+                    scope.switch_output_test(Marker::Enter(&test));
+                    changes.push(ModelChange::TestContinued { package, test });
+                }
+            }
+            Action::Output => {
+                let output = line.output.ok_or("Expected output in `Output` action")?;
+                let test = match line.test {
+                    Some(test) => {
+                        let scope = self.start_test(&package, &test, time, &mut changes);
+                        if let Some(marker) = output_scope::marker(&output) {
+                            scope.switch_output_test(marker);
+                        }
+                        Some(test)
+                    }
+                    None if self.finished.contains(&package)
+                        && !self.packages.contains_key(&package) =>
+                    {
+                        None
+                    }
+                    None => {
+                        let scope = self.start_package(&package, time, &mut changes);
+                        match output_scope::marker(&output) {
+                            Some(marker) => {
+                                scope.switch_output_test(marker);
+                                None
+                            }
+                            None => scope.unattributed_output_test(&output),
+                        }
+                    }
+                };
+                changes.push(ModelChange::Output {
+                    package,
+                    test,
+                    output,
+                    time,
+                });
+            }
+            Action::Pass | Action::Fail | Action::Skip => {
+                let result = TestResult::try_from(action)?;
+                match line.test {
+                    Some(test) => {
+                        let scope = self.start_test(&package, &test, time, &mut changes);
+                        scope.running.remove(&test);
+                        scope.switch_output_test(Marker::Pause(&test));
+                        changes.push(ModelChange::TestFinished {
+                            package,
+                            test,
+                            result,
+                            elapsed: line.elapsed,
+                            time,
+                        });
+                    }
+                    None => {
+                        // The test binary of a package failing to build never starts
+                        if line.failed_build.is_none() {
+                            self.start_package(&package, time, &mut changes);
+                        }
+                        self.packages.remove(&package);
+                        self.finished.insert(package.clone());
+                        changes.push(ModelChange::PackageFinished {
+                            package,
+                            result,
+                            elapsed: line.elapsed,
+                            time,
+                            failed_build: line.failed_build.is_some(),
+                        });
+                    }
+                }
+            }
+            Action::BuildOutput | Action::BuildFail => unreachable!("build actions return early"),
+        }
+        Ok(changes)
+    }
+
+    /// The scope of a running package, starting it if it didn't start yet
+    fn start_package(
+        &mut self,
+        package: &str,
+        time: Option<f64>,
+        changes: &mut Vec<ModelChange>,
+    ) -> &mut PackageScope {
+        if !self.packages.contains_key(package) {
+            changes.push(ModelChange::PackageStarted {
+                package: package.to_owned(),
+                time,
+            });
+        }
+        self.packages.entry(package.to_owned()).or_default()
+    }
+
+    /// The scope of a test's package, starting the package and the test if they didn't start yet
+    fn start_test(
+        &mut self,
+        package: &str,
+        test: &str,
+        time: Option<f64>,
+        changes: &mut Vec<ModelChange>,
+    ) -> &mut PackageScope {
+        let scope = self.start_package(package, time, changes);
+        if scope.tests.insert(test.to_owned()) {
+            scope.running.insert(test.to_owned());
+            changes.push(ModelChange::TestStarted {
+                package: package.to_owned(),
+                test: test.to_owned(),
+                time,
+            });
+        }
+        scope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGE: &str = "example.com/api";
+
+    fn feed(stream: &mut TestStream, lines: &[&str]) -> Vec<ModelChange> {
+        lines
+            .iter()
+            .flat_map(|line| stream.feed_line(line).expect("Line should be valid"))
+            .collect()
+    }
+
+    fn started(test: &str) -> ModelChange {
+        ModelChange::TestStarted {
+            package: PACKAGE.to_owned(),
+            test: test.to_owned(),
+            time: None,
+        }
+    }
+
+    fn output(test: Option<&str>, output: &str) -> ModelChange {
+        ModelChange::Output {
+            package: PACKAGE.to_owned(),
+            test: test.map(str::to_owned),
+            output: output.to_owned(),
+            time: None,
+        }
+    }
+
+    fn finished(test: &str, result: TestResult) -> ModelChange {
+        ModelChange::TestFinished {
+            package: PACKAGE.to_owned(),
+            test: test.to_owned(),
+            result,
+            elapsed: Some(0.1),
+            time: None,
+        }
+    }
+
+    fn package_started() -> ModelChange {
+        ModelChange::PackageStarted {
+            package: PACKAGE.to_owned(),
+            time: None,
+        }
+    }
+
+    #[test]
+    fn decodes_a_passing_package() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Test":"TestGet","Output":"=== RUN   TestGet\n"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"PASS\n"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Elapsed":0.3}"#,
+            ],
+        );
+        assert_eq!(
+            changes,
+            [
+                package_started(),
+                started("TestGet"),
+                output(Some("TestGet"), "=== RUN   TestGet\n"),
+                finished("TestGet", TestResult::Pass),
+                output(None, "PASS\n"),
+                ModelChange::PackageFinished {
+                    package: PACKAGE.to_owned(),
+                    result: TestResult::Pass,
+                    elapsed: Some(0.3),
+                    time: None,
+                    failed_build: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_failures_and_skips() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"fail","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestPut"}"#,
+                r#"{"Action":"skip","Package":"example.com/api","Test":"TestPut","Elapsed":0.1}"#,
+            ],
+        );
+        assert_eq!(changes[2], finished("TestGet", TestResult::Fail));
+        assert_eq!(changes[4], finished("TestPut", TestResult::Skip));
+    }
+
+    #[test]
+    fn reports_the_time_of_lines() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[r#"{"Time":"2024-01-02T03:04:05.5Z","Action":"start","Package":"example.com/api"}"#],
+        );
+        assert_eq!(
+            changes,
+            [ModelChange::PackageStarted {
+                package: PACKAGE.to_owned(),
+                time: Some(1_704_164_645.5),
+            }]
+        );
+    }
+
+    #[test]
+    fn accepts_snake_case_fields_and_import_paths() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[r#"{"action":"start","import_path":"example.com/api [example.com/api.test]"}"#],
+        );
+        assert_eq!(changes, [package_started()]);
+    }
+
+    #[test]
+    fn decodes_build_failures() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"ImportPath":"example.com/api [example.com/api.test]","Action":"build-output","Output":"api.go:3:1: syntax error\n"}"#,
+                r#"{"ImportPath":"example.com/api [example.com/api.test]","Action":"build-fail"}"#,
+                r#"{"Action":"fail","Package":"example.com/api","Elapsed":0,"FailedBuild":"example.com/api [example.com/api.test]"}"#,
+            ],
+        );
+        assert_eq!(
+            changes,
+            [
+                ModelChange::BuildOutput {
+                    package: PACKAGE.to_owned(),
+                    output: "api.go:3:1: syntax error\n".to_owned(),
+                    time: None,
+                },
+                ModelChange::BuildFailed {
+                    package: PACKAGE.to_owned(),
+                },
+                ModelChange::PackageFinished {
+                    package: PACKAGE.to_owned(),
+                    result: TestResult::Fail,
+                    elapsed: Some(0.0),
+                    time: None,
+                    failed_build: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_pauses_and_continues() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"pause","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"cont","Package":"example.com/api","Test":"TestGet"}"#,
+            ],
+        );
+        assert_eq!(
+            changes[2..],
+            [
+                ModelChange::TestPaused {
+                    package: PACKAGE.to_owned(),
+                    test: "TestGet".to_owned(),
+                },
+                ModelChange::TestContinued {
+                    package: PACKAGE.to_owned(),
+                    test: "TestGet".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_output_of_benchmarks() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"BenchmarkGet"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Test":"BenchmarkGet","Output":"BenchmarkGet-8   \t 1000\t  1234 ns/op\n"}"#,
+            ],
+        );
+        assert_eq!(
+            changes[2],
+            output(
+                Some("BenchmarkGet"),
+                "BenchmarkGet-8   \t 1000\t  1234 ns/op\n"
+            )
+        );
+    }
+
+    #[test]
+    fn starts_tests_whose_lines_arrive_before_their_run_action() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Test":"TestGet","Output":"get.go:12: ok\n"}"#,
+                r#"{"Action":"cont","Package":"example.com/api","Test":"TestPut"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Test":"TestDelete","Elapsed":0.1}"#,
+            ],
+        );
+        assert_eq!(
+            changes[1..],
+            [
+                started("TestGet"),
+                output(Some("TestGet"), "get.go:12: ok\n"),
+                started("TestPut"),
+                ModelChange::TestContinued {
+                    package: PACKAGE.to_owned(),
+                    test: "TestPut".to_owned(),
+                },
+                started("TestDelete"),
+                finished("TestDelete", TestResult::Pass),
+            ]
+        );
+    }
+
+    #[test]
+    fn starts_packages_of_go_versions_not_reporting_their_start() {
+        let mut stream = TestStream::default();
+        let changes = feed(
+            &mut stream,
+            &[
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Elapsed":0.1}"#,
+            ],
+        );
+        assert_eq!(changes[..2], [package_started(), started("TestGet")]);
+        // The next run starts the package again
+        let changes = feed(
+            &mut stream,
+            &[r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#],
+        );
+        assert_eq!(changes, [package_started(), started("TestGet")]);
+    }
+
+    #[test]
+    fn restarts_tests_run_again() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+            ],
+        );
+        assert_eq!(changes[3], started("TestGet"));
+    }
+
+    #[test]
+    fn starts_packages_whose_lines_arrive_before_their_start() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[r#"{"Action":"output","Package":"example.com/api","Output":"init\n"}"#],
+        );
+        assert_eq!(changes, [package_started(), output(None, "init\n")]);
+        // Attaching to a run after the package's lines, e.g. of a package without test files
+        let changes = feed(
+            &mut TestStream::default(),
+            &[r#"{"Action":"skip","Package":"example.com/api","Elapsed":0}"#],
+        );
+        assert_eq!(changes[0], package_started());
+    }
+
+    #[test]
+    fn leaves_output_after_the_result_to_the_finished_package() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Elapsed":0.1}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"late\n"}"#,
+            ],
+        );
+        assert_eq!(changes[2..], [output(None, "late\n")]);
+    }
+
+    #[test]
+    fn reports_every_attempt_of_a_test_run_with_count() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Test":"TestGet","Output":"first\n"}"#,
+                r#"{"Action":"fail","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Test":"TestGet","Output":"second\n"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+            ],
+        );
+        assert_eq!(
+            changes,
+            [
+                package_started(),
+                started("TestGet"),
+                output(Some("TestGet"), "first\n"),
+                finished("TestGet", TestResult::Fail),
+                started("TestGet"),
+                output(Some("TestGet"), "second\n"),
+                finished("TestGet", TestResult::Pass),
+            ]
+        );
+    }
+
+    #[test]
+    fn starts_a_package_again_for_each_run_piped_in() {
+        let mut stream = TestStream::default();
+        let run = [
+            r#"{"Action":"start","Package":"example.com/api"}"#,
+            r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+            r#"{"Action":"pass","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+            r#"{"Action":"output","Package":"example.com/api","Output":"ok\n"}"#,
+            r#"{"Action":"pass","Package":"example.com/api","Elapsed":0.3}"#,
+        ];
+        let first = feed(&mut stream, &run);
+        let second = feed(&mut stream, &run);
+        assert_eq!(first, second);
+        assert_eq!(
+            second[..4],
+            [
+                package_started(),
+                started("TestGet"),
+                finished("TestGet", TestResult::Pass),
+                output(None, "ok\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_output_without_a_test_to_the_running_test() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"setting up\n"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"fetched 3 rows\n"}"#,
+                r#"{"Action":"pass","Package":"example.com/api","Test":"TestGet","Elapsed":0.1}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"tearing down\n"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"PASS\n"}"#,
+            ],
+        );
+        assert_eq!(changes[1], output(None, "setting up\n"));
+        assert_eq!(changes[3], output(Some("TestGet"), "fetched 3 rows\n"));
+        assert_eq!(changes[5], output(None, "tearing down\n"));
+        assert_eq!(changes[6], output(None, "PASS\n"));
+    }
+
+    #[test]
+    fn follows_the_markers_of_parallel_tests() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"pause","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestPut"}"#,
+                r#"{"Action":"pause","Package":"example.com/api","Test":"TestPut"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"while paused\n"}"#,
+                r#"{"Action":"cont","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"cont","Package":"example.com/api","Test":"TestPut"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"=== NAME  TestGet\n"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"from get\n"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Test":"TestPut","Output":"=== CONT  TestPut\n"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"from put\n"}"#,
+            ],
+        );
+        assert_eq!(changes[5], output(None, "while paused\n"));
+        assert_eq!(changes[9], output(Some("TestGet"), "from get\n"));
+        assert_eq!(changes[11], output(Some("TestPut"), "from put\n"));
+    }
+
+    #[test]
+    fn leaves_panics_to_the_package() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[
+                r#"{"Action":"start","Package":"example.com/api"}"#,
+                r#"{"Action":"run","Package":"example.com/api","Test":"TestGet"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"panic: test timed out after 1s\n"}"#,
+                r#"{"Action":"output","Package":"example.com/api","Output":"goroutine 1 [running]:\n"}"#,
+            ],
+        );
+        assert_eq!(changes[3], output(None, "goroutine 1 [running]:\n"));
+    }
+
+    #[test]
+    fn ignores_lines_without_an_action() {
+        let changes = feed(
+            &mut TestStream::default(),
+            &[r#"{"Package":"example.com/api"}"#],
+        );
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        let mut stream = TestStream::default();
+        assert!(stream.feed_line("not json").is_err());
+        assert!(stream.feed_line(r#"{"Action":"run"}"#).is_err());
+        assert!(stream
+            .feed_line(r#"{"Action":"run","Package":"example.com/api"}"#)
+            .is_err());
+        assert!(stream
+            .feed_line(r#"{"Action":"output","Package":"example.com/api"}"#)
+            .is_err());
+    }
+}
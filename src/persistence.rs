@@ -0,0 +1,104 @@
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use zellij_tile::prelude::*;
+
+use crate::{
+    history_screen::TestRun, summary::Summary, tests_screen::ViewState, timer::TIMER_TOLERANCE,
+    Package,
+};
+
+/// Bumped on incompatible changes, states written by other versions are ignored
+const STATE_VERSION: u32 = 2;
+/// The plugin's data directory survives plugin reloads and session restarts
+const STATE_PATH: &str = "/data/state.json";
+/// Changes are batched into one write per this interval
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Everything needed to show the last results again after a restart, as read back from the
+/// saved [`SavedState`]
+#[derive(Debug, Deserialize)]
+pub(crate) struct State {
+    version: u32,
+    pub(crate) packages: Vec<Package>,
+    pub(crate) summary: Summary,
+    pub(crate) history: Vec<TestRun>,
+    pub(crate) run_number: usize,
+    pub(crate) view_state: ViewState,
+}
+
+impl State {
+    /// Reads the saved state, `None` if there's none or it can't be used
+    pub(crate) fn load() -> Option<Self> {
+        let contents = fs::read_to_string(STATE_PATH).ok()?;
+        serde_json::from_str::<Self>(&contents)
+            .ok()
+            .filter(|state| state.version == STATE_VERSION)
+    }
+}
+
+/// The state to save, borrowing the results instead of copying them for every save
+#[derive(Debug, Serialize)]
+pub(crate) struct SavedState<'a> {
+    version: u32,
+    packages: &'a [Package],
+    summary: Summary,
+    history: &'a [TestRun],
+    run_number: usize,
+    view_state: ViewState,
+}
+
+impl<'a> SavedState<'a> {
+    pub(crate) fn new(
+        packages: &'a [Package],
+        summary: Summary,
+        history: &'a [TestRun],
+        run_number: usize,
+        view_state: ViewState,
+    ) -> Self {
+        Self {
+            version: STATE_VERSION,
+            packages,
+            summary,
+            history,
+            run_number,
+            view_state,
+        }
+    }
+
+    pub(crate) fn save(&self) -> Result<(), String> {
+        let contents = serde_json::to_string(self)
+            .map_err(|err| format!("Failed to serialize plugin state: {}", err))?;
+        fs::write(STATE_PATH, contents)
+            .map_err(|err| format!("Failed to write {}: {}", STATE_PATH, err))
+    }
+}
+
+/// Schedules a save shortly after the state changed, so bursts of pipe messages cause one write
+#[derive(Debug, Default)]
+pub(crate) struct SaveScheduler {
+    save_at: Option<Instant>,
+}
+
+impl SaveScheduler {
+    pub(crate) fn mark_dirty(&mut self) {
+        if self.save_at.is_none() {
+            set_timeout(SAVE_DELAY.as_secs_f64());
+            self.save_at = Some(Instant::now() + SAVE_DELAY);
+        }
+    }
+
+    /// Returns whether the state should be saved now, as other timers share the same event
+    pub(crate) fn on_timer(&mut self) -> bool {
+        match self.save_at {
+            Some(save_at) if Instant::now() + TIMER_TOLERANCE >= save_at => {
+                self.save_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
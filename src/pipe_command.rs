@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Control messages sent through the pipe instead of `go test` output, either as pipe arguments,
+/// e.g. `zellij pipe --name zj-go-tests --args cmd=export,format=json`, or as a json payload like
+/// `{"cmd":"run","args":"./pkg/..."}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "kebab-case")]
+pub(crate) enum PipeCommand {
+    /// Writes a report, `format` being `junit` (the default) or `json`
+    Export { format: Option<String> },
+    /// Moves the current results to the history
+    RunStart,
+    /// Drops the current results and resets the tests screen's filters
+    Clear,
+    /// Runs `go test -json` with the arguments separated by whitespace, `./...` by default
+    Run { args: Option<String> },
+}
+
+impl PipeCommand {
+    /// Parses the command from the pipe arguments, `None` if there's no `cmd` argument
+    pub(crate) fn from_args(args: &BTreeMap<String, String>) -> Option<Result<Self, String>> {
+        args.contains_key("cmd").then(|| {
+            serde_json::to_value(args)
+                .and_then(serde_json::from_value)
+                .map_err(|err| format!("Invalid pipe command: {}", err))
+        })
+    }
+
+    /// Parses the command from a json payload, `None` if the payload isn't a command
+    pub(crate) fn from_payload(payload: &str) -> Option<Result<Self, String>> {
+        // Test lines never have a `cmd` field, which saves parsing them twice
+        if !payload.contains("\"cmd\"") {
+            return None;
+        }
+        let value = serde_json::from_str::<serde_json::Value>(payload).ok()?;
+        value.get("cmd")?;
+        Some(serde_json::from_value(value).map_err(|err| format!("Invalid pipe command: {}", err)))
+    }
+}
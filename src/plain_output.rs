@@ -0,0 +1,211 @@
+use crate::parser::{Action, TestLine};
+
+/// How pipe payloads get parsed, set with the `format` configuration or pipe argument
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum InputFormat {
+    /// `go test -json` lines, falling back to plain output for anything that isn't a JSON object
+    #[default]
+    Auto,
+    Json,
+    /// Classic `go test -v` output
+    Plain,
+    /// The `go test -json` lines of `gotestsum --jsonfile` or `--format standard-json`, parsed
+    /// like `auto`. A failed package starting again is `--rerun-fails` retrying its failed tests.
+    Gotestsum,
+}
+
+impl InputFormat {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(InputFormat::Auto),
+            "json" => Some(InputFormat::Json),
+            "plain" => Some(InputFormat::Plain),
+            "gotestsum" => Some(InputFormat::Gotestsum),
+            _ => None,
+        }
+    }
+
+    /// Whether a payload should be parsed as plain output
+    pub(crate) fn is_plain(&self, payload: &str) -> bool {
+        match self {
+            InputFormat::Auto | InputFormat::Gotestsum => !payload.trim_start().starts_with('{'),
+            InputFormat::Json => false,
+            InputFormat::Plain => true,
+        }
+    }
+}
+
+/// Turns plain `go test -v` output into the lines `go test -json` would have emitted.
+///
+/// Plain output only names the package in its final `ok`/`FAIL` line, so a package's lines are
+/// buffered until then. Test results are emitted after all output, as `go test` without `-v`
+/// prints a test's log after its `--- FAIL` line.
+#[derive(Debug, Default)]
+pub(crate) struct PlainParser {
+    /// Run and output lines of the package being received, lacking the package name
+    pending: Vec<TestLine>,
+    /// Result lines of the tests of the package being received
+    results: Vec<TestLine>,
+    /// Tests whose `run` line was emitted
+    started_tests: Vec<String>,
+    /// Test the following indented output belongs to
+    current_test: Option<String>,
+}
+
+impl PlainParser {
+    /// Parses a line of output, returning the lines of a package once its result arrived
+    pub(crate) fn parse(&mut self, line: &str) -> Vec<TestLine> {
+        let output = format!("{}\n", line);
+        if let Some(package_result) = PackageResult::parse(line) {
+            return self.finish_package(package_result, output);
+        }
+        let trimmed = line.trim_start();
+        if let Some((action, test_name)) = parse_test_event(trimmed) {
+            self.current_test = Some(test_name.to_owned());
+            if matches!(action, Action::Run) {
+                self.start_test(test_name);
+            } else {
+                self.push(action, Some(test_name));
+            }
+            self.push_output(Some(test_name), output);
+        } else if let Some((action, test_name, elapsed)) = parse_test_result(trimmed) {
+            self.current_test = Some(test_name.to_owned());
+            self.start_test(test_name);
+            self.push_output(Some(test_name), output);
+            self.results.push(TestLine {
+                action: Some(action),
+                test: Some(test_name.to_owned()),
+                elapsed,
+                ..TestLine::default()
+            });
+        } else if line.starts_with(char::is_whitespace) && self.current_test.is_some() {
+            let test_name = self.current_test.clone();
+            self.push_output(test_name.as_deref(), output);
+        } else {
+            self.current_test = None;
+            self.push_output(None, output);
+        }
+        Vec::new()
+    }
+
+    fn start_test(&mut self, test_name: &str) {
+        if !self.started_tests.iter().any(|name| name == test_name) {
+            self.started_tests.push(test_name.to_owned());
+            self.push(Action::Run, Some(test_name));
+        }
+    }
+
+    fn push(&mut self, action: Action, test_name: Option<&str>) {
+        self.pending.push(TestLine {
+            action: Some(action),
+            test: test_name.map(str::to_owned),
+            ..TestLine::default()
+        });
+    }
+
+    fn push_output(&mut self, test_name: Option<&str>, output: String) {
+        self.pending.push(TestLine {
+            action: Some(Action::Output),
+            test: test_name.map(str::to_owned),
+            output: Some(output),
+            ..TestLine::default()
+        });
+    }
+
+    fn finish_package(&mut self, package_result: PackageResult, output: String) -> Vec<TestLine> {
+        let package_name = package_result.package;
+        let mut lines = vec![TestLine {
+            action: Some(Action::Start),
+            ..TestLine::default()
+        }];
+        lines.append(&mut self.pending);
+        lines.append(&mut self.results);
+        lines.push(TestLine {
+            action: Some(Action::Output),
+            output: Some(output),
+            ..TestLine::default()
+        });
+        lines.push(TestLine {
+            action: Some(package_result.action),
+            elapsed: package_result.elapsed,
+            failed_build: package_result
+                .is_build_failure
+                .then(|| package_name.to_owned()),
+            ..TestLine::default()
+        });
+        for line in &mut lines {
+            line.package = Some(package_name.to_owned());
+        }
+        self.started_tests.clear();
+        self.current_test = None;
+        lines
+    }
+}
+
+/// Final line of a package, e.g. `ok      example.com/foo 0.012s`
+struct PackageResult<'a> {
+    action: Action,
+    package: &'a str,
+    elapsed: Option<f64>,
+    is_build_failure: bool,
+}
+
+impl<'a> PackageResult<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let action = match fields.next()?.trim_end() {
+            "ok" => Action::Pass,
+            "FAIL" => Action::Fail,
+            "?" => Action::Skip,
+            _ => return None,
+        };
+        let package = fields.next()?;
+        let (package, is_build_failure) = match package
+            .strip_suffix(" [build failed]")
+            .or_else(|| package.strip_suffix(" [setup failed]"))
+        {
+            Some(package) => (package, true),
+            None => (package, false),
+        };
+        let elapsed = fields.next().and_then(parse_seconds);
+        Some(Self {
+            action,
+            package,
+            elapsed,
+            is_build_failure,
+        })
+    }
+}
+
+/// Parses a `=== RUN`, `=== PAUSE` or `=== CONT` line
+fn parse_test_event(line: &str) -> Option<(Action, &str)> {
+    let (action, test_name) = line.strip_prefix("=== ")?.split_once(' ')?;
+    let action = match action {
+        "RUN" => Action::Run,
+        "PAUSE" => Action::Pause,
+        "CONT" => Action::Cont,
+        _ => return None,
+    };
+    Some((action, test_name.trim()))
+}
+
+/// Parses a `--- PASS: TestFoo (0.01s)` line
+fn parse_test_result(line: &str) -> Option<(Action, &str, Option<f64>)> {
+    let (action, rest) = line.strip_prefix("--- ")?.split_once(": ")?;
+    let action = match action {
+        "PASS" => Action::Pass,
+        "FAIL" => Action::Fail,
+        "SKIP" => Action::Skip,
+        _ => return None,
+    };
+    let (test_name, elapsed) = match rest.rsplit_once(" (") {
+        Some((test_name, elapsed)) => (test_name, elapsed.strip_suffix(')')),
+        None => (rest, None),
+    };
+    Some((action, test_name.trim(), elapsed.and_then(parse_seconds)))
+}
+
+/// Parses a duration like `0.012s`
+fn parse_seconds(value: &str) -> Option<f64> {
+    value.trim().strip_suffix('s')?.parse().ok()
+}
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+use zellij_tile::prelude::*;
+
+use crate::timer::TIMER_TOLERANCE;
+
+/// Minimum time between renders caused by pipe messages
+const INTERVAL: Duration = Duration::from_millis(50);
+
+/// Coalesces the renders of bursts of pipe messages. A message arriving shortly after a render
+/// schedules a timer instead of rendering, and that timer renders once for every message received
+/// in the meantime.
+#[derive(Debug, Default)]
+pub(crate) struct RenderThrottle {
+    last_render_at: Option<Instant>,
+    /// When the timer of the pending render fires, `None` if no render is pending
+    render_at: Option<Instant>,
+}
+
+impl RenderThrottle {
+    /// Returns whether a change should be rendered right away, otherwise a render gets scheduled
+    pub(crate) fn request(&mut self) -> bool {
+        if self.render_at.is_some() {
+            return false;
+        }
+        let now = Instant::now();
+        match self
+            .last_render_at
+            .map(|last_render_at| last_render_at + INTERVAL)
+        {
+            Some(render_at) if now < render_at => {
+                set_timeout((render_at - now).as_secs_f64());
+                self.render_at = Some(render_at);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Records a render, which makes a pending render obsolete
+    pub(crate) fn on_render(&mut self) {
+        self.last_render_at = Some(Instant::now());
+        self.render_at = None;
+    }
+
+    /// Returns whether the pending render is due, as other timers share the same event
+    pub(crate) fn on_timer(&mut self) -> bool {
+        match self.render_at {
+            Some(render_at) if Instant::now() + TIMER_TOLERANCE >= render_at => {
+                self.render_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
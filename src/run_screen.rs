@@ -0,0 +1,312 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{keymap::ScreenAction, runner, theme, viewport::Viewport};
+
+/// Context value of the `go list` command listing the packages to pick from
+pub(crate) const PACKAGE_LIST_CONTEXT: &str = "package-list";
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+    /// Runs the tests of the picked packages
+    Run(Vec<String>),
+    /// Replaces the extra `go test` flags
+    SetFlags(String),
+}
+
+/// Keys of the run screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum RunAction {
+    Exit,
+    Down,
+    Up,
+    TogglePick,
+    TogglePickAll,
+    Run,
+    EditFlags,
+}
+
+impl ScreenAction for RunAction {
+    const SCREEN: &'static str = "Run";
+
+    fn description(&self) -> &'static str {
+        match self {
+            RunAction::Exit => "back to the tests",
+            RunAction::Down => "select the next package",
+            RunAction::Up => "select the previous package",
+            RunAction::TogglePick => "pick or unpick the package",
+            RunAction::TogglePickAll => "pick or unpick every package",
+            RunAction::Run => "run the picked packages, or the selected one",
+            RunAction::EditFlags => "edit the go test flags of the session",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            RunAction::Exit => &["Esc"],
+            RunAction::Down => &["Down", "j"],
+            RunAction::Up => &["Up", "k"],
+            RunAction::TogglePick => &["Space"],
+            RunAction::TogglePickAll => &["a"],
+            RunAction::Run => &["Enter"],
+            RunAction::EditFlags => &["f"],
+        }
+    }
+}
+
+/// Keys of the run screen while editing the flags, other keys type them
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum FlagsAction {
+    Cancel,
+    Apply,
+    DeleteChar,
+}
+
+impl ScreenAction for FlagsAction {
+    const SCREEN: &'static str = "Run flags";
+
+    fn description(&self) -> &'static str {
+        match self {
+            FlagsAction::Cancel => "keep the flags as they were",
+            FlagsAction::Apply => "use the edited flags",
+            FlagsAction::DeleteChar => "delete the last char of the flags",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            FlagsAction::Cancel => &["Esc"],
+            FlagsAction::Apply => &["Enter"],
+            FlagsAction::DeleteChar => &["Backspace"],
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Packages {
+    Loading,
+    Loaded(Vec<String>),
+    Failed(String),
+}
+
+/// Lists the packages of the workspace, running the tests of the picked ones
+#[derive(Debug)]
+pub(crate) struct RunScreen {
+    packages: Packages,
+    /// Indices of the picked packages
+    picked: BTreeSet<usize>,
+    viewport: Viewport,
+    /// The `go test` flags being edited, `None` while not editing
+    edited_flags: Option<String>,
+}
+
+impl RunScreen {
+    /// Opens the screen, listing the packages in the background
+    pub(crate) fn new() -> Self {
+        run_command(
+            &["go", "list", "./..."],
+            BTreeMap::from([(
+                runner::CONTEXT_KEY.to_owned(),
+                PACKAGE_LIST_CONTEXT.to_owned(),
+            )]),
+        );
+        Self {
+            packages: Packages::Loading,
+            picked: BTreeSet::new(),
+            viewport: Viewport::default(),
+            edited_flags: None,
+        }
+    }
+
+    /// Handles the result of the `go list` command started by [`RunScreen::new`]
+    pub(crate) fn on_packages_listed(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: &[u8],
+        stderr: &[u8],
+    ) {
+        self.packages = if exit_code == Some(0) {
+            Packages::Loaded(
+                String::from_utf8_lossy(stdout)
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_owned)
+                    .collect(),
+            )
+        } else {
+            Packages::Failed(format!(
+                "Failed to list packages: {}",
+                String::from_utf8_lossy(stderr).trim()
+            ))
+        };
+    }
+
+    pub(crate) fn update(&mut self, event: Event) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        if let Some(edited_flags) = &mut self.edited_flags {
+            return match FlagsAction::from_key(&key) {
+                Some(FlagsAction::Cancel) => {
+                    self.edited_flags = None;
+                    Some(UpdateCommand::Render)
+                }
+                Some(FlagsAction::Apply) => self.edited_flags.take().map(UpdateCommand::SetFlags),
+                Some(FlagsAction::DeleteChar) => {
+                    edited_flags.pop();
+                    Some(UpdateCommand::Render)
+                }
+                None => {
+                    let BareKey::Char(c) = key.bare_key else {
+                        return None;
+                    };
+                    edited_flags.push(c);
+                    Some(UpdateCommand::Render)
+                }
+            };
+        }
+        let action = RunAction::from_key(&key)?;
+        match action {
+            RunAction::EditFlags => {
+                self.edited_flags = Some(runner::flags());
+                return Some(UpdateCommand::Render);
+            }
+            RunAction::Exit => return Some(UpdateCommand::ExitScreen),
+            _ => (),
+        }
+        let Packages::Loaded(packages) = &self.packages else {
+            return None;
+        };
+        match action {
+            RunAction::Down => {
+                self.viewport.select_next(1, packages.len());
+                Some(UpdateCommand::Render)
+            }
+            RunAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            RunAction::TogglePick if self.viewport.selected < packages.len() => {
+                if !self.picked.remove(&self.viewport.selected) {
+                    self.picked.insert(self.viewport.selected);
+                }
+                Some(UpdateCommand::Render)
+            }
+            RunAction::TogglePickAll => {
+                if self.picked.len() == packages.len() {
+                    self.picked.clear();
+                } else {
+                    self.picked = (0..packages.len()).collect();
+                }
+                Some(UpdateCommand::Render)
+            }
+            // Without picked packages the selected one runs
+            RunAction::Run => {
+                let picked = if self.picked.is_empty() {
+                    packages
+                        .get(self.viewport.selected)
+                        .into_iter()
+                        .cloned()
+                        .collect()
+                } else {
+                    self.picked
+                        .iter()
+                        .map(|index| packages[*index].clone())
+                        .collect()
+                };
+                Some(UpdateCommand::Run(picked))
+            }
+            RunAction::TogglePick | RunAction::Exit | RunAction::EditFlags => None,
+        }
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize) {
+        let theme = theme::current();
+        let packages = match &self.packages {
+            Packages::Loading => {
+                print_text_with_coordinates(
+                    Text::new("Listing packages…  [Esc] back"),
+                    0,
+                    0,
+                    Some(cols),
+                    Some(1),
+                );
+                return;
+            }
+            Packages::Failed(err) => {
+                print_text_with_coordinates(
+                    Text::new(err).color_range(theme.fail, ..),
+                    0,
+                    0,
+                    Some(cols),
+                    Some(1),
+                );
+                print_text_with_coordinates(
+                    Text::new("[Esc] back"),
+                    0,
+                    rows.saturating_sub(1),
+                    Some(cols),
+                    Some(1),
+                );
+                return;
+            }
+            Packages::Loaded(packages) => packages,
+        };
+
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(packages.len());
+
+        let table = packages
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec![" ", "package"]),
+                |acc, (i, package)| {
+                    let marker = if self.picked.contains(&i) {
+                        Text::new("[x]").color_range(theme.accent, 1..2)
+                    } else {
+                        Text::new("[ ]")
+                    };
+                    let row = vec![marker, Text::new(package)];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let bottom_text = match &self.edited_flags {
+            Some(edited_flags) => Text::new(format!(
+                "flags: {}_  [Enter] save, [Esc] cancel",
+                edited_flags
+            )),
+            None => {
+                let picked_count = self.picked.len().to_string();
+                let flags = runner::flags();
+                Text::new(format!(
+                    "{} of {} packages picked{} — [Space] pick, [a] all, [f] flags, [Enter] run, [Esc] back",
+                    picked_count,
+                    packages.len(),
+                    if flags.is_empty() {
+                        String::new()
+                    } else {
+                        format!(", flags: {}", flags)
+                    }
+                ))
+                .color_range(theme.accent, ..picked_count.len())
+            }
+        };
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
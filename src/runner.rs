@@ -0,0 +1,193 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use zellij_tile::prelude::*;
+
+use crate::name::Name;
+
+/// Name of the pipe spawned `go test` commands send their json output to
+pub(crate) const PIPE_NAME: &str = "zj-go-tests";
+/// Context key marking the command panes opened by this plugin
+pub(crate) const CONTEXT_KEY: &str = "zj-go-tests";
+/// Pipe argument telling the plugin to merge results into existing packages
+pub(crate) const RERUN_ARG: &str = "rerun";
+/// Pipe argument naming the stream results belong to, defaulting to the pipe's name
+pub(crate) const STREAM_ARG: &str = "stream";
+/// Pipe argument with the directory of the project results belong to, defaulting to the plugin's
+/// working directory
+pub(crate) const PROJECT_ARG: &str = "project";
+/// Pipe command telling the plugin a new test run starts, moving the current results to the history
+pub(crate) const RUN_START_CMD: &str = "run-start";
+
+/// Context value of the command panes running the whole test suite
+pub(crate) const RUN_ALL_CONTEXT: &str = "run-all";
+/// Context value of the command panes rerunning selected tests
+pub(crate) const RERUN_CONTEXT: &str = "rerun";
+/// Context value of the `go list -m` command detecting the module path
+pub(crate) const MODULE_CONTEXT: &str = "module";
+
+thread_local! {
+    static FLAGS: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Extra `go test` flags of every run the plugin spawns, e.g. `-race -count=1`. They're kept for
+/// the session, so reruns use the same flags as the run before.
+pub(crate) fn flags() -> String {
+    FLAGS.with(|flags| flags.borrow().clone())
+}
+
+pub(crate) fn set_flags(new_flags: &str) {
+    FLAGS.with(|flags| *flags.borrow_mut() = new_flags.trim().to_owned());
+}
+
+/// Top-level tests of a package to run again
+#[derive(Debug, Clone)]
+pub(crate) struct Rerun {
+    pub(crate) package: Name,
+    pub(crate) tests: Vec<Name>,
+}
+
+/// Spawns `go test` for the given tests in a background command pane, streaming the results back
+/// through the plugin's pipe into the given stream. Tests of another project run in its directory.
+pub(crate) fn rerun_tests(reruns: &[Rerun], stream: &str, project: Option<&Path>) {
+    let go_test_commands = reruns
+        .iter()
+        .map(|rerun| go_test_command(&[&rerun.package], &rerun.tests, "-json"))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let mut args = format!("{}=true,{}={}", RERUN_ARG, STREAM_ARG, stream);
+    if let Some(project) = project {
+        args.push_str(&format!(",{}={}", PROJECT_ARG, project.display()));
+    }
+    let script = format!(
+        "{{ {}; }} | zellij pipe --name {} --args {}",
+        go_test_commands,
+        PIPE_NAME,
+        shell_quote(&args)
+    );
+    open_background_pane(&script, RERUN_CONTEXT, project);
+}
+
+/// The shell command reproducing a run of the given tests locally, with the session's flags, e.g.
+/// `go test -race -run '^(TestFoo|TestBar)$' 'example.com/api' 'example.com/db'`. Packages without
+/// tests run as a whole by a command of their own, joined with `;` so that one failing doesn't
+/// keep the other from running.
+pub(crate) fn reproduce_command(reruns: &[Rerun]) -> String {
+    let (whole, filtered): (Vec<_>, Vec<_>) =
+        reruns.iter().partition(|rerun| rerun.tests.is_empty());
+    let tests = filtered
+        .iter()
+        .flat_map(|rerun| rerun.tests.iter().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    [(whole, Vec::new()), (filtered, tests)]
+        .into_iter()
+        .filter(|(reruns, _)| !reruns.is_empty())
+        .map(|(reruns, tests)| {
+            let packages = reruns
+                .iter()
+                .map(|rerun| &rerun.package)
+                .collect::<Vec<_>>();
+            go_test_command(&packages, &tests, "")
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// `go test` of the given packages, only running the given tests unless there are none
+fn go_test_command(packages: &[&Name], tests: &[Name], extra_flags: &str) -> String {
+    let run = if tests.is_empty() {
+        String::new()
+    } else {
+        format!("-run {}", shell_quote(&run_pattern(tests)))
+    };
+    let packages = packages
+        .iter()
+        .map(|package| shell_quote(package))
+        .collect::<Vec<_>>()
+        .join(" ");
+    ["go test", extra_flags, &flags(), &run, &packages]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Spawns `go test ./...` in a background command pane, streaming the results back through the
+/// plugin's pipe
+pub(crate) fn run_all_tests() {
+    run_tests(&["./..."]);
+}
+
+/// Spawns `go test` with the given arguments, e.g. `-race` and `./pkg/...`, in a background
+/// command pane as a new run. Each argument is quoted, so none of them can run shell commands.
+pub(crate) fn run_tests<S: AsRef<str>>(args: &[S]) {
+    let args = args
+        .iter()
+        .map(|arg| shell_quote(arg.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let script = format!(
+        "zellij pipe --name {pipe} --args cmd={cmd} < /dev/null; go test -json {flags} {args} | zellij pipe --name {pipe}",
+        pipe = PIPE_NAME,
+        cmd = RUN_START_CMD,
+        flags = flags(),
+        args = args
+    );
+    open_background_pane(&script, RUN_ALL_CONTEXT, None);
+}
+
+/// Runs `go list -m` in the background to detect the module path of the workspace
+pub(crate) fn detect_module() {
+    run_command(
+        &["go", "list", "-m"],
+        BTreeMap::from([(CONTEXT_KEY.to_owned(), MODULE_CONTEXT.to_owned())]),
+    );
+}
+
+/// The module path printed by `go list -m`. Workspaces list all their modules, the first one is
+/// used.
+pub(crate) fn parse_module(stdout: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_owned)
+}
+
+fn open_background_pane(script: &str, context: &str, cwd: Option<&Path>) {
+    let mut command = CommandToRun::new_with_args("sh", vec!["-c", script]);
+    command.cwd = cwd.map(Path::to_path_buf);
+    open_command_pane_background(
+        command,
+        BTreeMap::from([(CONTEXT_KEY.to_owned(), context.to_owned())]),
+    );
+}
+
+/// The `-run` pattern matching exactly the given tests. A single test may be a subtest, whose
+/// name gets matched level by level, e.g. `^TestFoo$/^case_a$`.
+pub(crate) fn run_pattern(tests: &[Name]) -> String {
+    match tests {
+        [test] => test
+            .split('/')
+            .map(|level| format!("^{}$", regex::escape(level)))
+            .collect::<Vec<_>>()
+            .join("/"),
+        tests => format!(
+            "^({})$",
+            tests
+                .iter()
+                .map(|test| regex::escape(test))
+                .collect::<Vec<_>>()
+                .join("|")
+        ),
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,86 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+/// How letter case is treated when searching logs and filtering tests by name. Toggled with Tab
+/// while typing a search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum CaseSensitivity {
+    #[default]
+    Sensitive,
+    Insensitive,
+    /// Insensitive unless the query contains an uppercase letter
+    Smart,
+}
+
+impl CaseSensitivity {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sensitive" => Some(CaseSensitivity::Sensitive),
+            "insensitive" => Some(CaseSensitivity::Insensitive),
+            "smart" => Some(CaseSensitivity::Smart),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            CaseSensitivity::Sensitive => "case-sensitive",
+            CaseSensitivity::Insensitive => "ignore-case",
+            CaseSensitivity::Smart => "smart-case",
+        }
+    }
+
+    pub(crate) fn next(self) -> Self {
+        match self {
+            CaseSensitivity::Sensitive => CaseSensitivity::Insensitive,
+            CaseSensitivity::Insensitive => CaseSensitivity::Smart,
+            CaseSensitivity::Smart => CaseSensitivity::Sensitive,
+        }
+    }
+
+    fn ignores_case(&self, query: &str) -> bool {
+        match self {
+            CaseSensitivity::Sensitive => false,
+            CaseSensitivity::Insensitive => true,
+            CaseSensitivity::Smart => !query.chars().any(char::is_uppercase),
+        }
+    }
+
+    pub(crate) fn contains(&self, haystack: &str, query: &str) -> bool {
+        if self.ignores_case(query) {
+            haystack.to_lowercase().contains(&query.to_lowercase())
+        } else {
+            haystack.contains(query)
+        }
+    }
+
+    /// Column ranges, counted in chars, of the non-overlapping matches of `query` in `haystack`
+    pub(crate) fn find_matches(&self, haystack: &str, query: &str) -> Vec<Range<usize>> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let ignores_case = self.ignores_case(query);
+        // Lowercasing char by char keeps the columns of both strings aligned
+        let normalize = |c: char| {
+            if ignores_case {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                c
+            }
+        };
+        let haystack = haystack.chars().map(normalize).collect::<Vec<_>>();
+        let query = query.chars().map(normalize).collect::<Vec<_>>();
+        let mut matches = Vec::new();
+        let mut start = 0;
+        while start + query.len() <= haystack.len() {
+            if haystack[start..start + query.len()] == query[..] {
+                matches.push(start..start + query.len());
+                start += query.len();
+            } else {
+                start += 1;
+            }
+        }
+        matches
+    }
+}
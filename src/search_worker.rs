@@ -0,0 +1,138 @@
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+use zellij_tile::prelude::*;
+
+use crate::{ansi::StyledLine, diff, search::CaseSensitivity};
+
+/// Namespace the worker is registered as, see `register_worker!`
+pub(crate) const WORKER_NAME: &str = "search";
+/// Message appending lines to the worker's copy of the log, or replacing it
+const INDEX_MESSAGE: &str = "index";
+/// Message searching the worker's copy of the log
+const SEARCH_MESSAGE: &str = "search";
+/// Message the worker posts the matches of a search back to the plugin with
+pub(crate) const MATCHES_MESSAGE: &str = "search_matches";
+
+/// Id of the last search, shared by the logs screens so the matches of a log closed since never
+/// pass for the matches of the log searched next
+static LAST_SEARCH_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexRequest {
+    /// Index of the first line, the lines from there on get replaced
+    start: usize,
+    /// The lines as logged, with their ANSI escapes
+    lines: Vec<String>,
+    /// Whether each line is part of an assertion diff, whose tabs are expanded as shown
+    diff_lines: Vec<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchRequest {
+    id: u64,
+    query: String,
+    case_sensitivity: CaseSensitivity,
+    /// Number of lines searched, lines indexed later are searched by the plugin
+    line_count: usize,
+}
+
+/// Matches of a search, line index and column range of each
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SearchMatches {
+    pub(crate) id: u64,
+    pub(crate) line_count: usize,
+    pub(crate) matches: Vec<(usize, Range<usize>)>,
+}
+
+/// Searches the log shown by the logs screen in the background, so typing a search in a log of
+/// many megabytes doesn't block the plugin. The log's lines are sent once as they arrive, stripped
+/// of their styling here and searched for every query typed after.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct SearchWorker {
+    lines: Vec<String>,
+}
+
+impl ZellijWorker<'_> for SearchWorker {
+    fn on_message(&mut self, message: String, payload: String) {
+        match message.as_str() {
+            INDEX_MESSAGE => {
+                let Ok(request) = serde_json::from_str::<IndexRequest>(&payload) else {
+                    return;
+                };
+                self.lines.truncate(request.start);
+                self.lines
+                    .extend(request.lines.iter().enumerate().map(|(index, line)| {
+                        let styled_line = StyledLine::parse(line);
+                        match request.diff_lines.get(index) {
+                            Some(true) => styled_line.expand_tabs(diff::TAB_WIDTH).text,
+                            _ => styled_line.text,
+                        }
+                    }));
+            }
+            SEARCH_MESSAGE => {
+                let Ok(request) = serde_json::from_str::<SearchRequest>(&payload) else {
+                    return;
+                };
+                let line_count = request.line_count.min(self.lines.len());
+                let matches = self.lines[..line_count]
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, line)| {
+                        request
+                            .case_sensitivity
+                            .find_matches(line, &request.query)
+                            .into_iter()
+                            .map(move |range| (index, range))
+                    })
+                    .collect();
+                let response = SearchMatches {
+                    id: request.id,
+                    line_count,
+                    matches,
+                };
+                if let Ok(payload) = serde_json::to_string(&response) {
+                    post_message_to_plugin(PluginMessage::new_to_plugin(MATCHES_MESSAGE, &payload));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Sends lines to the worker, replacing its lines from `start` on
+pub(crate) fn index(start: usize, lines: Vec<String>, diff_lines: Vec<bool>) {
+    let request = IndexRequest {
+        start,
+        lines,
+        diff_lines,
+    };
+    post_to_worker(INDEX_MESSAGE, &request);
+}
+
+/// Searches the first `line_count` lines sent to the worker, the matches arrive as a
+/// `MATCHES_MESSAGE` custom message. Returns the search's id, which the matches carry.
+pub(crate) fn search(query: &str, case_sensitivity: CaseSensitivity, line_count: usize) -> u64 {
+    let id = LAST_SEARCH_ID.fetch_add(1, Ordering::Relaxed) + 1;
+    let request = SearchRequest {
+        id,
+        query: query.to_owned(),
+        case_sensitivity,
+        line_count,
+    };
+    post_to_worker(SEARCH_MESSAGE, &request);
+    id
+}
+
+fn post_to_worker(message: &str, request: &impl Serialize) {
+    if let Ok(payload) = serde_json::to_string(request) {
+        post_message_to(PluginMessage::new_to_worker(WORKER_NAME, message, &payload));
+    }
+}
+
+/// Native test builds have no zellij to pass messages to the worker, which isn't registered there
+#[cfg(test)]
+fn post_message_to(_message: PluginMessage) {}
@@ -0,0 +1,181 @@
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    keymap::ScreenAction, name::Name, tests_screen::format_elapsed, theme, viewport::Viewport,
+    Package, TestCase,
+};
+
+/// Eighths of a block, for bars ending between two cells
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const FULL_BLOCK: char = '█';
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+    OpenLogs {
+        package: Name,
+        test: Name,
+        attempt: usize,
+    },
+}
+
+/// Keys of the slowest tests screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum SlowestAction {
+    Exit,
+    Down,
+    Up,
+    OpenLogs,
+}
+
+impl ScreenAction for SlowestAction {
+    const SCREEN: &'static str = "Slowest tests";
+
+    fn description(&self) -> &'static str {
+        match self {
+            SlowestAction::Exit => "back to the tests",
+            SlowestAction::Down => "select the next test",
+            SlowestAction::Up => "select the previous test",
+            SlowestAction::OpenLogs => "open the logs of the test",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            SlowestAction::Exit => &["Esc"],
+            SlowestAction::Down => &["Down", "j"],
+            SlowestAction::Up => &["Up", "k"],
+            SlowestAction::OpenLogs => &["Enter"],
+        }
+    }
+}
+
+/// Lists the slowest tests of the run, slowest first, with a bar chart of their durations
+#[derive(Debug)]
+pub(crate) struct SlowestScreen {
+    /// Number of tests listed, set with `slowest_count`
+    count: usize,
+    viewport: Viewport,
+}
+
+impl SlowestScreen {
+    pub(crate) fn new(count: usize) -> Self {
+        Self {
+            count,
+            viewport: Viewport::default(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, event: Event, packages: &[Package]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let tests = self.slowest_tests(packages);
+        match SlowestAction::from_key(&key)? {
+            SlowestAction::Exit => Some(UpdateCommand::ExitScreen),
+            SlowestAction::Down => {
+                self.viewport.select_next(1, tests.len());
+                Some(UpdateCommand::Render)
+            }
+            SlowestAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            SlowestAction::OpenLogs => {
+                tests.get(self.viewport.selected).map(|(package, test, _)| {
+                    UpdateCommand::OpenLogs {
+                        package: package.name.clone(),
+                        test: test.name.clone(),
+                        attempt: test.attempt,
+                    }
+                })
+            }
+        }
+    }
+
+    /// The finished tests of all packages, slowest first
+    fn slowest_tests<'a>(&self, packages: &'a [Package]) -> Vec<(&'a Package, &'a TestCase, f64)> {
+        let mut tests = packages
+            .iter()
+            .flat_map(|package| {
+                TestCase::flatten(&package.tests)
+                    .into_iter()
+                    .filter_map(move |test| Some((package, test, test.elapsed?)))
+            })
+            .collect::<Vec<_>>();
+        tests.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+        tests.truncate(self.count);
+        tests
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize, packages: &[Package]) {
+        let theme = theme::current();
+        let tests = self.slowest_tests(packages);
+        let test_count = packages
+            .iter()
+            .map(|package| TestCase::flatten(&package.tests).len())
+            .sum::<usize>();
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(tests.len());
+
+        // The slowest test fills the bar column, the others are scaled to it
+        let max_elapsed = tests.first().map_or(0.0, |(_, _, elapsed)| *elapsed);
+        let bar_width = (cols / 3).clamp(10, 50);
+        let table = tests
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["elapsed", "duration", "test"]),
+                |acc, (i, (package, test, elapsed))| {
+                    let marker_color = test
+                        .result
+                        .map_or(theme.running, |result| result.marker_color());
+                    let row = vec![
+                        Text::new(format_elapsed(*elapsed)),
+                        Text::new(bar(*elapsed, max_elapsed, bar_width))
+                            .color_range(marker_color, ..),
+                        Text::new(format!("{} {}", package.name, test.name))
+                            .color_range(theme.accent, ..package.name.len()),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let shown_count = tests.len().to_string();
+        let bottom_text = Text::new(format!(
+            "{} slowest of {} tests — [Enter] logs, [Esc] back",
+            shown_count, test_count
+        ))
+        .color_range(theme.accent, ..shown_count.len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+/// A bar of `width` cells at most whose length is proportional to `value`
+fn bar(value: f64, max: f64, width: usize) -> String {
+    if max <= 0.0 {
+        return " ".to_owned();
+    }
+    let eighths = ((value / max).clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let mut bar = FULL_BLOCK.to_string().repeat(eighths / 8);
+    let remainder = eighths % 8;
+    if remainder > 0 {
+        bar.push(PARTIAL_BLOCKS[remainder]);
+    }
+    // Tests too fast for a cell still show they were measured
+    if bar.is_empty() {
+        bar.push(PARTIAL_BLOCKS[1]);
+    }
+    bar
+}
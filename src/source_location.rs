@@ -0,0 +1,138 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use zellij_tile::prelude::*;
+
+use crate::{runner, TestCase};
+
+/// Context value of the `go list` commands resolving a package's directory
+pub(crate) const PACKAGE_DIR_CONTEXT: &str = "package-dir";
+
+/// A `file.go:line` reference found in test output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceLocation {
+    pub(crate) file: String,
+    pub(crate) line: usize,
+}
+
+impl SourceLocation {
+    /// Finds the first reference in a test's log, falling back to its subtests' logs
+    pub(crate) fn find_in_test(test_case: &TestCase) -> Option<Self> {
+        Self::find_in_log(&test_case.log).or_else(|| {
+            test_case
+                .subtests
+                .iter()
+                .find_map(SourceLocation::find_in_test)
+        })
+    }
+
+    pub(crate) fn find_in_log(log: &[String]) -> Option<Self> {
+        log.iter().find_map(|line| Self::parse(line))
+    }
+
+    /// Parses the first `file.go:line` reference of an output line, e.g. `foo_test.go:42: want 1`
+    /// or a `/path/to/foo.go:42 +0x1d` panic frame
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        line.match_indices(".go:").find_map(|(index, _)| {
+            let file = line[..index].rsplit(char::is_whitespace).next()?;
+            let line_number: String = line[index + 4..]
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            if file.is_empty() || line_number.is_empty() {
+                return None;
+            }
+            Some(SourceLocation {
+                file: format!("{}.go", file),
+                line: line_number.parse().ok()?,
+            })
+        })
+    }
+
+    /// Returns the message following a leading reference, e.g. `want 1` for
+    /// `    foo_test.go:42: want 1`
+    pub(crate) fn strip_from_line(line: &str) -> Option<&str> {
+        let trimmed = line.trim_start();
+        let location = Self::parse(trimmed)?;
+        let message = trimmed
+            .strip_prefix(&location.file)?
+            .strip_prefix(&format!(":{}: ", location.line))?;
+        Some(message.trim_end())
+    }
+
+    fn is_absolute(&self) -> bool {
+        Path::new(&self.file).is_absolute()
+    }
+}
+
+/// Opens source locations in the user's editor, resolving file names relative to the directory of
+/// the package they were printed by
+#[derive(Debug, Default)]
+pub(crate) struct SourceOpener {
+    package_dirs: BTreeMap<String, PathBuf>,
+}
+
+impl SourceOpener {
+    pub(crate) fn open(&mut self, package: &str, location: SourceLocation) {
+        if location.is_absolute() {
+            open_location(PathBuf::from(&location.file), location.line);
+        } else if let Some(package_dir) = self.package_dirs.get(package) {
+            open_location(package_dir.join(&location.file), location.line);
+        } else {
+            run_command(
+                &["go", "list", "-f", "{{.Dir}}", package],
+                BTreeMap::from([
+                    (
+                        runner::CONTEXT_KEY.to_owned(),
+                        PACKAGE_DIR_CONTEXT.to_owned(),
+                    ),
+                    ("package".to_owned(), package.to_owned()),
+                    ("file".to_owned(), location.file),
+                    ("line".to_owned(), location.line.to_string()),
+                ]),
+            );
+        }
+    }
+
+    /// Handles the result of a `go list` command started by [`SourceOpener::open`]
+    pub(crate) fn on_package_dir_resolved(
+        &mut self,
+        exit_code: Option<i32>,
+        stdout: &[u8],
+        stderr: &[u8],
+        context: &BTreeMap<String, String>,
+    ) -> Result<(), String> {
+        let (Some(package), Some(file), Some(line)) = (
+            context.get("package"),
+            context.get("file"),
+            context.get("line").and_then(|line| line.parse().ok()),
+        ) else {
+            return Err("Missing context of `go list` command".to_owned());
+        };
+        if exit_code != Some(0) {
+            return Err(format!(
+                "Failed to resolve directory of {}: {}",
+                package,
+                String::from_utf8_lossy(stderr).trim()
+            ));
+        }
+        let package_dir = PathBuf::from(String::from_utf8_lossy(stdout).trim());
+        open_location(package_dir.join(file), line);
+        self.package_dirs.insert(package.clone(), package_dir);
+        Ok(())
+    }
+}
+
+fn open_location(path: PathBuf, line: usize) {
+    open_file_floating(
+        FileToOpen {
+            path,
+            line_number: Some(line),
+            cwd: None,
+        },
+        None,
+        BTreeMap::new(),
+    );
+}
@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+use zellij_tile::prelude::*;
+
+use crate::timer::TIMER_TOLERANCE;
+
+const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const INTERVAL: Duration = Duration::from_millis(120);
+
+/// Animates the marker of running packages and tests by scheduling a timer for each frame
+#[derive(Debug, Default)]
+pub(crate) struct Spinner {
+    frame: usize,
+    /// When the timer of the next frame fires, `None` while the spinner is stopped
+    next_frame_at: Option<Instant>,
+}
+
+impl Spinner {
+    pub(crate) fn frame(&self) -> char {
+        FRAMES[self.frame % FRAMES.len()]
+    }
+
+    pub(crate) fn start(&mut self) {
+        if self.next_frame_at.is_none() {
+            self.schedule();
+        }
+    }
+
+    /// Advances the animation when the timer of the next frame fired, as other timers share the
+    /// same event. Returns whether the frame changed.
+    pub(crate) fn on_timer(&mut self, is_active: bool) -> bool {
+        match self.next_frame_at {
+            Some(next_frame_at) if Instant::now() + TIMER_TOLERANCE >= next_frame_at => {
+                self.frame = self.frame.wrapping_add(1);
+                self.next_frame_at = None;
+                if is_active {
+                    self.schedule();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn schedule(&mut self) {
+        set_timeout(INTERVAL.as_secs_f64());
+        self.next_frame_at = Some(Instant::now() + INTERVAL);
+    }
+}
@@ -0,0 +1,56 @@
+use zellij_tile::prelude::*;
+
+use crate::summary::Summary;
+
+/// Broadcasts a compact summary of the current run to other plugins whenever it changes, so
+/// status bar plugins can show it
+#[derive(Debug, Default)]
+pub(crate) struct StatusPublisher {
+    /// Name of the pipe messages, publishing is disabled without one
+    pipe_name: Option<String>,
+    /// URL of the plugin the messages are sent to, all plugins receive them without one
+    plugin_url: Option<String>,
+    last_status: Option<String>,
+}
+
+impl StatusPublisher {
+    pub(crate) fn new(pipe_name: Option<String>, plugin_url: Option<String>) -> Self {
+        Self {
+            pipe_name,
+            plugin_url,
+            last_status: None,
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.pipe_name.is_some()
+    }
+
+    pub(crate) fn publish(&mut self, summary: &Summary, is_running: bool) {
+        let Some(pipe_name) = &self.pipe_name else {
+            return;
+        };
+        let status = status_text(summary, is_running);
+        if self.last_status.as_ref() == Some(&status) {
+            return;
+        }
+        let mut message = MessageToPlugin::new(pipe_name).with_payload(status.as_str());
+        if let Some(plugin_url) = &self.plugin_url {
+            message = message.with_plugin_url(plugin_url);
+        }
+        pipe_message_to_plugin(message);
+        self.last_status = Some(status);
+    }
+}
+
+/// E.g. `3F/120P running`, skipped tests are only listed if there are any
+fn status_text(summary: &Summary, is_running: bool) -> String {
+    let mut status = format!("{}F/{}P", summary.failed, summary.passed);
+    if summary.skipped > 0 {
+        status.push_str(&format!("/{}S", summary.skipped));
+    }
+    if is_running {
+        status.push_str(" running");
+    }
+    status
+}
@@ -0,0 +1,47 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    history_screen::TestRun, parser::TestStream, plain_output::PlainParser, summary::Summary,
+    tests_screen::TestsScreen,
+};
+
+/// Results piped in under one name, set with the `stream` pipe argument or the pipe's name, e.g.
+/// `go test -json ./api/... | zellij pipe --name zj-go-tests --args stream=api`. Pipes without a
+/// `stream` argument running at the same time get numbered streams of their own.
+#[derive(Debug, Default)]
+pub(crate) struct Stream {
+    pub(crate) name: String,
+    /// Id of the CLI pipe feeding the stream when it came without a `stream` argument
+    pub(crate) source: Option<String>,
+    /// Directory of the project the results belong to, set with the `project` pipe argument, e.g.
+    /// `--args project=$PWD`. `None` is the plugin's own working directory.
+    pub(crate) project: Option<PathBuf>,
+    /// Holds the stream's results while another stream is shown
+    pub(crate) tests_screen: TestsScreen,
+    pub(crate) summary: Summary,
+    pub(crate) plain_parser: PlainParser,
+    pub(crate) test_stream: TestStream,
+    /// The stream's previous runs, oldest first
+    pub(crate) history: Vec<TestRun>,
+    pub(crate) run_number: usize,
+}
+
+impl Stream {
+    pub(crate) fn new<T: Into<String>>(name: T, project: Option<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            project,
+            run_number: 1,
+            ..Self::default()
+        }
+    }
+}
+
+/// Name shown for the project in a directory, its last component
+pub(crate) fn project_label(directory: &Path) -> String {
+    directory
+        .file_name()
+        .unwrap_or(directory.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
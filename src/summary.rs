@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Package, TestCase, TestResult};
+
+/// Aggregate results of the tests received so far, updated as pipe messages arrive
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct Summary {
+    /// Tests and subtests received, running ones included
+    #[serde(default)]
+    pub(crate) tests: usize,
+    pub(crate) passed: usize,
+    pub(crate) failed: usize,
+    pub(crate) skipped: usize,
+    /// Failed tests that raced or timed out, counted in `failed` as well
+    #[serde(default)]
+    pub(crate) raced: usize,
+    #[serde(default)]
+    pub(crate) timed_out: usize,
+    /// Packages that failed to build, and packages without test files
+    #[serde(default)]
+    pub(crate) build_failed: usize,
+    #[serde(default)]
+    pub(crate) without_tests: usize,
+    /// Packages with a result of their own
+    #[serde(default)]
+    pub(crate) finished_packages: usize,
+    /// Tests slower than the slow filter's threshold
+    #[serde(default)]
+    pub(crate) slow: usize,
+    /// Elapsed times of the packages summed up
+    pub(crate) elapsed: f64,
+    /// Timestamp of the first `start` or `run` action, in seconds since the Unix epoch
+    #[serde(default)]
+    pub(crate) started_at: Option<f64>,
+    /// Timestamp of the last `pass`, `fail` or `skip` action
+    #[serde(default)]
+    pub(crate) finished_at: Option<f64>,
+}
+
+impl Summary {
+    /// Counts a test, its result and whether it's slow, before it changes it has to be removed
+    pub(crate) fn add_test(&mut self, test_case: &TestCase, slow_threshold: f64) {
+        for count in self
+            .test_counts_mut(test_case, slow_threshold)
+            .into_iter()
+            .flatten()
+        {
+            *count += 1;
+        }
+    }
+
+    pub(crate) fn remove_test(&mut self, test_case: &TestCase, slow_threshold: f64) {
+        for count in self
+            .test_counts_mut(test_case, slow_threshold)
+            .into_iter()
+            .flatten()
+        {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// Counts the result of a package, before it changes it has to be removed
+    pub(crate) fn add_package_result(&mut self, package: &Package) {
+        for count in self.package_counts_mut(package).into_iter().flatten() {
+            *count += 1;
+        }
+    }
+
+    pub(crate) fn remove_package_result(&mut self, package: &Package) {
+        for count in self.package_counts_mut(package).into_iter().flatten() {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// Counts the results of the packages and of their tests again, e.g. of results saved by an
+    /// older version or with another slow threshold
+    pub(crate) fn recount(&mut self, packages: &mut [Package], slow_threshold: f64) {
+        *self = Self {
+            elapsed: self.elapsed,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            ..Self::default()
+        };
+        for package in packages {
+            package.summary = Self::default();
+            self.add_package_result(package);
+            for test_case in TestCase::flatten(&package.tests) {
+                self.add_test(test_case, slow_threshold);
+                package.summary.add_test(test_case, slow_threshold);
+            }
+        }
+    }
+
+    pub(crate) fn add_elapsed(&mut self, elapsed: f64) {
+        self.elapsed += elapsed;
+    }
+
+    pub(crate) fn remove_elapsed(&mut self, elapsed: f64) {
+        self.elapsed = (self.elapsed - elapsed).max(0.0);
+    }
+
+    pub(crate) fn add_start_time(&mut self, time: f64) {
+        self.started_at = Some(
+            self.started_at
+                .map_or(time, |started_at| started_at.min(time)),
+        );
+    }
+
+    pub(crate) fn add_finish_time(&mut self, time: f64) {
+        self.finished_at = Some(
+            self.finished_at
+                .map_or(time, |finished_at| finished_at.max(time)),
+        );
+    }
+
+    /// Wall-clock time of the run, from its first start to its last result. Shorter than the
+    /// summed elapsed times when packages run in parallel.
+    pub(crate) fn wall_clock(&self) -> Option<f64> {
+        Some((self.finished_at? - self.started_at?).max(0.0))
+    }
+
+    /// Removes a test together with its subtests
+    pub(crate) fn remove_test_case(&mut self, test_case: &TestCase, slow_threshold: f64) {
+        self.remove_test(test_case, slow_threshold);
+        for subtest in &test_case.subtests {
+            self.remove_test_case(subtest, slow_threshold);
+        }
+    }
+
+    /// Failed tests and packages shown by the fail filter, races and timeouts have filters of
+    /// their own
+    pub(crate) fn fail_filter_count(&self) -> usize {
+        self.failed.saturating_sub(self.raced + self.timed_out) + self.build_failed
+    }
+
+    /// Number of finished and total packages, `None` once all packages finished
+    pub(crate) fn progress(&self, package_count: usize) -> Option<(usize, usize)> {
+        (self.finished_packages < package_count).then_some((self.finished_packages, package_count))
+    }
+
+    /// Short outcome of a completed run, e.g. `✓ 120 passed` or `✗ 3 failed, 117 passed`
+    pub(crate) fn outcome(&self, has_failure: bool) -> String {
+        if has_failure {
+            format!("✗ {} failed, {} passed", self.failed, self.passed)
+        } else {
+            format!("✓ {} passed", self.passed)
+        }
+    }
+
+    /// The counts a test contributes to: the tests, its result and a race or timeout, and the
+    /// slow tests
+    fn test_counts_mut(
+        &mut self,
+        test_case: &TestCase,
+        slow_threshold: f64,
+    ) -> [Option<&mut usize>; 4] {
+        let Self {
+            tests,
+            passed,
+            failed,
+            skipped,
+            raced,
+            timed_out,
+            slow,
+            ..
+        } = self;
+        let (result, failure) = match test_case.result {
+            Some(TestResult::Pass) => (Some(passed), None),
+            Some(TestResult::Fail | TestResult::BuildFail) => (Some(failed), None),
+            Some(TestResult::Race) => (Some(failed), Some(raced)),
+            Some(TestResult::Timeout) => (Some(failed), Some(timed_out)),
+            Some(TestResult::Skip) => (Some(skipped), None),
+            // Only packages end without test files, a test never does
+            Some(TestResult::NoTests) | None => (None, None),
+        };
+        let slow = test_case.is_slow(slow_threshold).then_some(slow);
+        [Some(tests), result, failure, slow]
+    }
+
+    /// The counts a package's result contributes to: the finished packages, and the build
+    /// failures or packages without tests
+    fn package_counts_mut(&mut self, package: &Package) -> [Option<&mut usize>; 2] {
+        let Self {
+            build_failed,
+            without_tests,
+            finished_packages,
+            ..
+        } = self;
+        let result = match package.result {
+            Some(TestResult::BuildFail) => Some(build_failed),
+            Some(TestResult::NoTests) => Some(without_tests),
+            _ => None,
+        };
+        [package.result.map(|_| finished_packages), result]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOW_THRESHOLD: f64 = 1.0;
+
+    fn finish(summary: &mut Summary, test: &mut TestCase, result: TestResult, elapsed: f64) {
+        summary.remove_test(test, SLOW_THRESHOLD);
+        test.result = Some(result);
+        test.elapsed = Some(elapsed);
+        summary.add_test(test, SLOW_THRESHOLD);
+    }
+
+    fn restart(summary: &mut Summary, test: &mut TestCase) -> Option<TestResult> {
+        summary.remove_test(test, SLOW_THRESHOLD);
+        let previous_result = test.restart(None);
+        summary.add_test(test, SLOW_THRESHOLD);
+        previous_result
+    }
+
+    fn set_package_result(summary: &mut Summary, package: &mut Package, result: TestResult) {
+        summary.remove_package_result(package);
+        package.result = Some(result);
+        summary.add_package_result(package);
+    }
+
+    #[test]
+    fn replaces_the_counts_of_a_test_whose_result_changes() {
+        let mut summary = Summary::default();
+        let mut test = TestCase::new("TestGet");
+        summary.add_test(&test, SLOW_THRESHOLD);
+        assert_eq!((summary.tests, summary.passed, summary.failed), (1, 0, 0));
+
+        finish(&mut summary, &mut test, TestResult::Fail, 2.0);
+        assert_eq!((summary.tests, summary.failed, summary.slow), (1, 1, 1));
+
+        finish(&mut summary, &mut test, TestResult::Pass, 0.1);
+        assert_eq!(
+            (summary.tests, summary.passed, summary.failed, summary.slow),
+            (1, 1, 0, 0)
+        );
+    }
+
+    #[test]
+    fn counts_a_retried_test_once_with_its_latest_result() {
+        let mut summary = Summary::default();
+        let mut test = TestCase::new("TestGet");
+        summary.add_test(&test, SLOW_THRESHOLD);
+        finish(&mut summary, &mut test, TestResult::Race, 0.1);
+        assert_eq!((summary.failed, summary.raced), (1, 1));
+        assert_eq!(summary.fail_filter_count(), 0);
+
+        assert_eq!(restart(&mut summary, &mut test), Some(TestResult::Race));
+        assert_eq!((summary.tests, summary.failed, summary.raced), (1, 0, 0));
+
+        finish(&mut summary, &mut test, TestResult::Pass, 0.1);
+        assert_eq!(
+            (summary.tests, summary.passed, summary.failed, summary.raced),
+            (1, 1, 0, 0)
+        );
+    }
+
+    #[test]
+    fn removes_a_rerun_test_together_with_its_subtests() {
+        let mut package = Package::new("example.com/api");
+        let mut test = TestCase::new("TestGet");
+        test.result = Some(TestResult::Fail);
+        for (name, result) in [
+            ("TestGet/a", TestResult::Pass),
+            ("TestGet/b", TestResult::Fail),
+        ] {
+            let mut subtest = TestCase::new(name);
+            subtest.result = Some(result);
+            test.subtests.push(subtest);
+        }
+        package.tests.push(test);
+        let mut other = TestCase::new("TestList");
+        other.result = Some(TestResult::Pass);
+        package.tests.push(other);
+        let mut packages = [package];
+        let mut summary = Summary::default();
+        summary.recount(&mut packages, SLOW_THRESHOLD);
+        assert_eq!((summary.tests, summary.passed, summary.failed), (4, 2, 2));
+        assert_eq!(
+            (packages[0].summary.tests, packages[0].summary.failed),
+            (4, 2)
+        );
+
+        summary.remove_test_case(&packages[0].tests[0], SLOW_THRESHOLD);
+        assert_eq!((summary.tests, summary.passed, summary.failed), (1, 1, 0));
+    }
+
+    #[test]
+    fn counts_a_build_failure_reported_twice_once() {
+        let mut summary = Summary::default();
+        let mut package = Package::new("example.com/api");
+        summary.add_package_result(&package);
+        assert_eq!(summary.progress(1), Some((0, 1)));
+
+        set_package_result(&mut summary, &mut package, TestResult::BuildFail);
+        set_package_result(&mut summary, &mut package, TestResult::BuildFail);
+        assert_eq!((summary.build_failed, summary.finished_packages), (1, 1));
+        assert_eq!(summary.fail_filter_count(), 1);
+        assert_eq!(summary.progress(1), None);
+
+        summary.remove_package_result(&package);
+        package.result = None;
+        assert_eq!((summary.build_failed, summary.finished_packages), (0, 0));
+        assert_eq!(summary.progress(1), Some((0, 1)));
+    }
+}
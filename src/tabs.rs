@@ -0,0 +1,64 @@
+use strum::{EnumIter, IntoEnumIterator};
+use zellij_tile::prelude::*;
+
+/// The screens listed in the tab bar. Other screens, e.g. the logs, open on top of them and hide
+/// the tab bar until they're closed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub(crate) enum Tab {
+    #[default]
+    Tests,
+    /// The tests screen showing failures only
+    Failures,
+    Benchmarks,
+    Coverage,
+    History,
+}
+
+impl Tab {
+    fn label(&self) -> &'static str {
+        match self {
+            Tab::Tests => "Tests",
+            Tab::Failures => "Failures",
+            Tab::Benchmarks => "Benchmarks",
+            Tab::Coverage => "Coverage",
+            Tab::History => "History",
+        }
+    }
+
+    /// The tab a key switches to: Alt and the tab's number, or Shift Tab for the next tab
+    pub(crate) fn from_key(key: &KeyWithModifier, active_tab: Tab) -> Option<Tab> {
+        match key.bare_key {
+            BareKey::Tab if key.has_modifiers(&[KeyModifier::Shift]) => Tab::iter()
+                .cycle()
+                .skip_while(|tab| *tab != active_tab)
+                .nth(1),
+            BareKey::Char(c) if key.has_modifiers(&[KeyModifier::Alt]) => {
+                let index = c.to_digit(10)?.checked_sub(1)?;
+                Tab::iter().nth(usize::try_from(index).ok()?)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Renders the tab bar as a line of ribbons, the active tab selected
+pub(crate) fn render_tab_bar(active_tab: Tab, y: usize) {
+    let mut x = 0;
+    for (index, tab) in Tab::iter().enumerate() {
+        let label = format!("<{}> {}", index + 1, tab.label());
+        let width = label.chars().count() + 4;
+        let ribbon = Text::new(label);
+        print_ribbon_with_coordinates(
+            if tab == active_tab {
+                ribbon.selected()
+            } else {
+                ribbon
+            },
+            x,
+            y,
+            None,
+            None,
+        );
+        x += width;
+    }
+}
@@ -1,261 +1,2163 @@
+use std::{
+    borrow::Cow,
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
 use zellij_tile::prelude::*;
 
-use crate::{logs_screen::LogsScreen, Package, TestCase, TestResult};
+use crate::{
+    ansi,
+    config::{Config, CoverageThresholds, AUTO_MODULE_PREFIX},
+    count_prefix::{CountPrefix, Input},
+    export::ExportFormat,
+    fuzz::FuzzProgress,
+    keymap::{Keymap, TestsAction},
+    log_limit,
+    logs_screen::{LogSource, LogsScreen},
+    name::Name,
+    runner::{self, Rerun},
+    search::CaseSensitivity,
+    source_location::SourceLocation,
+    summary::Summary,
+    theme,
+    viewport::Viewport,
+    DerivedStatus, Package, RunState, TestCase, TestKind, TestResult,
+};
+
+/// Longer skip reasons and failure messages get cut off with an ellipsis
+const MAX_DETAILS_WIDTH: usize = 80;
+/// The details of the selected row are only shown in screens at least this high
+const MIN_DETAILS_ROWS: usize = 15;
+/// The selection's location gets a line of its own only in screens at least this high, smaller ones
+/// spell out shortened package names in the status bar instead
+const MIN_LOCATION_ROWS: usize = 8;
+/// Columns a ribbon takes besides its text: the padding around it, followed by a gap
+const RIBBON_PADDING: usize = 5;
+/// Two clicks on the same row within this interval open its logs
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Debug)]
 pub(crate) enum UpdateCommand {
-    ShowLogsScreen(LogsScreen),
+    ShowLogsScreen(Box<LogsScreen>),
+    ShowErrorsScreen,
+    ShowHistoryScreen,
+    ShowRunScreen,
+    ShowBenchmarksScreen,
+    ShowTriageScreen,
+    ShowLintScreen,
+    ShowSlowestScreen,
+    ShowGrepScreen,
+    ClearResults,
+    ShowHelpScreen,
+    NextStream,
+    NextProject,
+    ShowFinder,
+    ShowPackagePicker,
+    RerunFailed(Vec<Rerun>),
+    /// Reruns a single test, replacing its result in place
+    RerunTest(Rerun),
+    AbortRun,
+    ToggleWatch,
+    Export(ExportFormat),
+    OpenSource {
+        package: Name,
+        location: SourceLocation,
+    },
+    Notice(String),
+    CopyToClipboard(String),
+    /// Opens the log of a package or test in the user's pager
+    OpenPager(LogSource),
+    /// Writes the logs of the marked packages and tests to the files set with `log_path`
+    SaveLogs(Vec<LogSource>),
+    /// Esc was pressed without a name filter to clear
+    ExitScreen,
     Render,
 }
 
-#[derive(Debug, Default, Clone, Copy)]
+/// State rendered in the bar below the tests table
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StatusBar<'a> {
+    pub(crate) summary: Summary,
+    pub(crate) is_watching: bool,
+    pub(crate) error_count: usize,
+    pub(crate) notice: Option<&'a str>,
+    /// Marker of running packages and tests
+    pub(crate) spinner: char,
+    /// Names of the active project's streams, only listed when there's more than one
+    pub(crate) streams: &'a [String],
+    pub(crate) active_stream: usize,
+    /// Names of the projects results were piped in for, only listed when there's more than one
+    pub(crate) projects: &'a [String],
+    pub(crate) active_project: usize,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct ResultFilters {
     pass: bool,
     fail: bool,
     skip: bool,
+    race: bool,
+    timeout: bool,
+    #[serde(default)]
+    no_tests: bool,
+}
+
+impl ResultFilters {
+    /// Parses a comma separated list of results, e.g. `fail, race`
+    pub(crate) fn from_names(names: &str) -> Option<Self> {
+        let mut filters = Self::default();
+        for name in names.split(',') {
+            match name.trim() {
+                "pass" => filters.pass = true,
+                "fail" => filters.fail = true,
+                "skip" => filters.skip = true,
+                "race" => filters.race = true,
+                "timeout" => filters.timeout = true,
+                "no-tests" => filters.no_tests = true,
+                _ => return None,
+            }
+        }
+        Some(filters)
+    }
+}
+
+/// A column of the tests table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Column {
+    Result,
+    /// The tree of groups, packages and tests, with the result marker before each name
+    Package,
+    /// Full name of a test, and the test counts of a package
+    Test,
+    Elapsed,
+    Coverage,
+    /// Number of output lines, only shown while toggled on
+    OutputLines,
+    /// Failure message, skip reason or other notes on the row
+    FailureMessage,
+}
+
+impl Column {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "result" => Some(Column::Result),
+            "package" => Some(Column::Package),
+            "test" => Some(Column::Test),
+            "elapsed" => Some(Column::Elapsed),
+            "coverage" => Some(Column::Coverage),
+            "output-lines" => Some(Column::OutputLines),
+            "failure-message" => Some(Column::FailureMessage),
+            _ => None,
+        }
+    }
+
+    fn header(&self) -> &'static str {
+        match self {
+            Column::Result => "result",
+            Column::Package => "package",
+            Column::Test => "test",
+            Column::Elapsed => "elapsed",
+            Column::Coverage => "coverage",
+            Column::OutputLines => "output lines",
+            Column::FailureMessage => "failure message",
+        }
+    }
+}
+
+/// The columns of the tests table and their order, set with the `columns` option, e.g.
+/// `columns "result, package, elapsed, failure-message"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ColumnSpec(Vec<Column>);
+
+impl Default for ColumnSpec {
+    fn default() -> Self {
+        Self(vec![
+            Column::Package,
+            Column::Elapsed,
+            Column::OutputLines,
+            Column::Coverage,
+            Column::FailureMessage,
+        ])
+    }
+}
+
+impl ColumnSpec {
+    /// Reads the `columns` option, unknown names are reported and left out. The default columns
+    /// stay when none is left besides the output lines, which aren't always shown.
+    pub(crate) fn from_names(names: &str) -> (Self, Vec<String>) {
+        let mut columns = Vec::new();
+        let mut errors = Vec::new();
+        for name in names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            match Column::from_name(name) {
+                Some(column) if !columns.contains(&column) => columns.push(column),
+                Some(_) => (),
+                None => errors.push(format!(
+                    "Invalid `columns` = \"{}\": unknown column `{}`",
+                    names, name
+                )),
+            }
+        }
+        if columns.iter().all(|column| *column == Column::OutputLines) {
+            errors.push(format!(
+                "Invalid `columns` = \"{}\": no column besides `output-lines`",
+                names
+            ));
+            return (Self::default(), errors);
+        }
+        (Self(columns), errors)
+    }
+
+    /// The columns shown, the output lines only while toggled on. They follow the elapsed time,
+    /// or else the package tree, unless placed elsewhere.
+    fn visible(&self, show_log_lines: bool) -> Vec<Column> {
+        let mut columns = self.0.clone();
+        match columns
+            .iter()
+            .position(|column| *column == Column::OutputLines)
+        {
+            Some(_) if !show_log_lines => columns.retain(|column| *column != Column::OutputLines),
+            None if show_log_lines => {
+                let index = [Column::Elapsed, Column::Package]
+                    .iter()
+                    .find_map(|shown| columns.iter().position(|column| column == shown))
+                    .map_or(columns.len(), |index| index + 1);
+                columns.insert(index, Column::OutputLines);
+            }
+            _ => (),
+        }
+        columns
+    }
+}
+
+/// Which kinds of tests are shown, cycled through independently of the result filters so e.g.
+/// only failed fuzz targets can be shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum KindFilter {
+    #[default]
+    All,
+    /// Regular tests and benchmarks, hiding examples and fuzz targets
+    Tests,
+    Examples,
+    Fuzz,
+}
+
+impl KindFilter {
+    fn next(self) -> Self {
+        match self {
+            KindFilter::All => KindFilter::Tests,
+            KindFilter::Tests => KindFilter::Examples,
+            KindFilter::Examples => KindFilter::Fuzz,
+            KindFilter::Fuzz => KindFilter::All,
+        }
+    }
+
+    fn matches(&self, kind: TestKind) -> bool {
+        match self {
+            KindFilter::All => true,
+            KindFilter::Tests => matches!(kind, TestKind::Test | TestKind::Benchmark),
+            KindFilter::Examples => kind == TestKind::Example,
+            KindFilter::Fuzz => kind == TestKind::Fuzz,
+        }
+    }
+
+    fn label(&self) -> Option<&'static str> {
+        match self {
+            KindFilter::All => None,
+            KindFilter::Tests => Some("tests only"),
+            KindFilter::Examples => Some("examples only"),
+            KindFilter::Fuzz => Some("fuzz only"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum SortKey {
+    /// The order the packages and tests started in
+    #[default]
+    RunOrder,
+    Name,
+    /// Failures first, then running, skipped and passed tests
+    Result,
+    Elapsed,
+    /// Number of lines written to the logs
+    LogLines,
+}
+
+impl SortKey {
+    fn next(self) -> Self {
+        match self {
+            SortKey::RunOrder => SortKey::Name,
+            SortKey::Name => SortKey::Result,
+            SortKey::Result => SortKey::Elapsed,
+            SortKey::Elapsed => SortKey::LogLines,
+            SortKey::LogLines => SortKey::RunOrder,
+        }
+    }
+
+    fn compare<T: Sortable>(&self, a: &T, b: &T) -> Ordering {
+        match self {
+            SortKey::RunOrder => Ordering::Equal,
+            SortKey::Name => a.name().cmp(b.name()),
+            SortKey::Result => result_rank(a.result()).cmp(&result_rank(b.result())),
+            SortKey::Elapsed => a
+                .elapsed()
+                .unwrap_or_default()
+                .total_cmp(&b.elapsed().unwrap_or_default()),
+            SortKey::LogLines => a.log_lines().cmp(&b.log_lines()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Sort {
+    key: SortKey,
+    is_descending: bool,
+}
+
+impl Sort {
+    /// Sorts items along with their indices stably, so items comparing equal keep their run order
+    /// as results stream in
+    fn apply<T: Sortable>(&self, items: &mut [(usize, &T)]) {
+        items.sort_by(|(_, a), (_, b)| {
+            let ordering = self.key.compare(*a, *b);
+            if self.is_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    fn arrow(&self) -> char {
+        if self.is_descending {
+            '▼'
+        } else {
+            '▲'
+        }
+    }
+}
+
+/// Fields of packages and tests the tests table can be sorted by
+trait Sortable {
+    fn name(&self) -> &str;
+    fn result(&self) -> Option<TestResult>;
+    fn elapsed(&self) -> Option<f64>;
+    /// Lines written to the logs, including those of the tests and subtests below
+    fn log_lines(&self) -> usize;
+}
+
+impl Sortable for Package {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn result(&self) -> Option<TestResult> {
+        self.result
+    }
+
+    fn elapsed(&self) -> Option<f64> {
+        self.elapsed
+    }
+
+    fn log_lines(&self) -> usize {
+        log_limit::line_count(&self.log)
+            + TestCase::flatten(&self.tests)
+                .iter()
+                .map(|test| log_limit::line_count(&test.log))
+                .sum::<usize>()
+    }
+}
+
+impl Sortable for TestCase {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn result(&self) -> Option<TestResult> {
+        self.result
+    }
+
+    fn elapsed(&self) -> Option<f64> {
+        self.elapsed
+    }
+
+    fn log_lines(&self) -> usize {
+        TestCase::flatten(std::slice::from_ref(self))
+            .iter()
+            .map(|test| log_limit::line_count(&test.log))
+            .sum()
+    }
+}
+
+/// The counters of a fuzzing package, in the failure color once a crasher was found
+fn render_fuzz_progress(fuzz_progress: Option<&FuzzProgress>) -> Text {
+    match fuzz_progress {
+        Some(fuzz_progress) if fuzz_progress.crashers > 0 => {
+            Text::new(fuzz_progress.summary()).color_range(TestResult::Fail.marker_color(), ..)
+        }
+        Some(fuzz_progress) => Text::new(fuzz_progress.summary()),
+        None => Text::new(" "),
+    }
+}
+
+/// A package's import path relative to the module, e.g. `internal/foo`, or `.` for the module's
+/// root package. Packages outside the module keep their full path.
+fn relative_name<'a>(name: &'a str, module_prefix: Option<&str>) -> &'a str {
+    match module_prefix.and_then(|module_prefix| name.strip_prefix(module_prefix)) {
+        Some("") => ".",
+        Some(rest) => rest.strip_prefix('/').unwrap_or(name),
+        None => name,
+    }
+}
+
+fn is_failed_package(package: &Package) -> bool {
+    package
+        .result_or_derived()
+        .is_some_and(|result| result.is_failure())
+}
+
+/// Number of leading path segments all names share
+fn shared_segments<'a>(mut names: impl Iterator<Item = &'a str>) -> usize {
+    let Some(first) = names.next() else {
+        return 0;
+    };
+    let first = first.split('/').collect::<Vec<_>>();
+    names.fold(first.len(), |shared, name| {
+        name.split('/')
+            .zip(&first)
+            .take(shared)
+            .take_while(|(segment, first_segment)| segment == *first_segment)
+            .count()
+    })
+}
+
+/// Whether zen mode hides a package: it completed without failing
+fn is_hidden_by_zen(package: &Package) -> bool {
+    package.result.is_some_and(|result| !result.is_failure())
+}
+
+fn result_rank(result: Option<TestResult>) -> u8 {
+    match result {
+        Some(result) if result.is_failure() => 0,
+        None => 1,
+        Some(TestResult::Skip | TestResult::NoTests) => 2,
+        Some(_) => 3,
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) enum Mode {
+    #[default]
+    Normal,
+    Search,
+}
+
+/// Filters, sorting and folding of the tests screen, persisted across plugin restarts
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ViewState {
+    result_filters: ResultFilters,
+    collapsed_packages: BTreeSet<Name>,
+    collapsed_tests: BTreeMap<Name, BTreeSet<Name>>,
+    name_filter: String,
+    sort: Sort,
+    #[serde(default)]
+    show_log_lines: bool,
+    #[serde(default)]
+    kind_filter: KindFilter,
+    #[serde(default)]
+    group_by_path: bool,
+    #[serde(default)]
+    collapsed_groups: BTreeSet<String>,
+    #[serde(default)]
+    show_details: bool,
+    #[serde(default)]
+    zen: bool,
+    #[serde(default)]
+    slow_only: bool,
+}
+
+/// A row of the list as indices into the packages, so rows are only materialized once they're
+/// scrolled into view
+#[derive(Debug, Clone)]
+struct ListEntry {
+    package: usize,
+    /// Index of each ancestor and finally the test itself among its siblings, empty for packages
+    test_path: Vec<usize>,
+    is_last: Vec<bool>,
+    collapsed: bool,
+    /// Number of group headers the row is nested below
+    depth: usize,
+    /// Set for the header rows of packages grouped by their path
+    group: Option<GroupEntry>,
+}
+
+/// Header of packages sharing a path prefix
+#[derive(Debug, Clone)]
+struct GroupEntry {
+    /// The shared path prefix, identifying the group
+    path: String,
+    /// The path segments below the parent group's, e.g. `internal/`
+    label: String,
+    packages: Vec<usize>,
+}
+
+/// What the list entries were computed from, they're recomputed once it changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ListKey {
+    packages_generation: u64,
+    view_generation: u64,
+}
+
+/// What identifies a row across changes of the list, so the selection stays on it while rows get
+/// added, removed or reordered
+#[derive(Debug, Clone, PartialEq)]
+enum RowId {
+    Group {
+        path: String,
+    },
+    Package {
+        name: Name,
+    },
+    TestCase {
+        package: Name,
+        test: Name,
+        attempt: usize,
+    },
+}
+
+#[derive(Debug, Default)]
+struct ListIndex {
+    key: Option<ListKey>,
+    entries: Vec<ListEntry>,
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct TestsScreen {
     pub(crate) packages: Vec<Package>,
-    selected_index: usize,
-    scroll_x: usize,
-    scroll_y: usize,
-    screen_width: Option<usize>,
-    screen_height: Option<usize>,
+    /// Bumped whenever the packages change, see `mark_packages_changed`
+    packages_generation: u64,
+    /// Bumped whenever the filters, sorting or folding change, see `mark_view_changed`
+    view_generation: u64,
+    /// The filtered, sorted and folded list, computed on demand
+    list_index: RefCell<ListIndex>,
+    viewport: Viewport,
     result_filters: ResultFilters,
+    /// Filters the screen starts with, and returns to when cleared
+    default_result_filters: ResultFilters,
+    kind_filter: KindFilter,
+    /// Whether only tests slower than `slow_threshold` are shown
+    slow_only: bool,
+    /// Seconds a test takes at least to be shown by the slow filter
+    slow_threshold: f64,
+    /// Whether moving past the last or the first row continues at the other end
+    wrap_navigation: bool,
+    /// Whether packages are nested below headers of the path prefixes they share
+    group_by_path: bool,
+    /// Paths of the collapsed package groups
+    collapsed_groups: BTreeSet<String>,
+    collapsed_packages: BTreeSet<Name>,
+    /// Names of the collapsed tests, keyed by package name
+    collapsed_tests: BTreeMap<Name, BTreeSet<Name>>,
+    mode: Mode,
+    /// Only packages and tests whose name contains this string are visible
+    name_filter: String,
+    case_sensitivity: CaseSensitivity,
+    sort: Sort,
+    /// Whether the column of log line counts is shown
+    show_log_lines: bool,
+    columns: ColumnSpec,
+    /// Whether the bottom of the screen previews the selected row
+    show_details: bool,
+    /// Whether packages that completed without failing are hidden, leaving the failures and the
+    /// packages still running
+    zen: bool,
+    /// The package picked to be shown alone, not persisted as nothing would tell it's restored
+    package_filter: Option<Name>,
+    /// Packages and tests marked for the bulk actions, kept by name so they stay marked while the
+    /// list gets sorted and filtered
+    marked: BTreeSet<LogSource>,
+    /// Set while shown as the failures tab, which overrides the result filters
+    failures_only: bool,
+    /// Import path stripped from the package names shown, the full name of the selected row's
+    /// package is shown by its location instead, or by the status bar in small screens
+    module_prefix: Option<String>,
+    /// List index and time of the last left click, to detect double clicks
+    last_click: Option<(usize, Instant)>,
+    count_prefix: CountPrefix,
+    /// The selected row, found again at its new index once the list changed
+    selection: Option<RowId>,
+    /// What the list was computed from and the selected index when the selection was last found
+    selection_key: Option<(ListKey, usize)>,
 }
 
 impl TestsScreen {
-    pub(crate) fn update(&mut self, event: Event) -> Option<UpdateCommand> {
-        match event {
-            Event::Key(KeyWithModifier {
-                bare_key: BareKey::Down | BareKey::Char('j'),
-                ..
-            }) => {
-                self.selected_index = self
-                    .selected_index
-                    .saturating_add(1)
-                    .min(self.visible_list_items().len().saturating_sub(1));
+    pub(crate) fn new(packages: Vec<Package>) -> Self {
+        Self {
+            packages,
+            ..Default::default()
+        }
+    }
+
+    /// Applies the options of the tests screen, shared by the screens of every stream and run.
+    /// Applying them again keeps the filters picked since.
+    pub(crate) fn configure(&mut self, config: &Config) {
+        self.case_sensitivity = config.case_sensitivity;
+        self.slow_threshold = config.slow_threshold;
+        self.wrap_navigation = config.wrap_navigation;
+        self.columns = config.columns.clone();
+        if let Some(result_filters) = config
+            .default_filters
+            .filter(|result_filters| *result_filters != self.default_result_filters)
+        {
+            self.default_result_filters = result_filters;
+            self.result_filters = result_filters;
+        }
+        self.module_prefix = config
+            .module_prefix
+            .clone()
+            .filter(|module_prefix| module_prefix != AUTO_MODULE_PREFIX);
+        self.mark_view_changed();
+        self.clamp_selected_index();
+    }
+
+    pub(crate) fn set_failures_only(&mut self, failures_only: bool) {
+        self.failures_only = failures_only;
+        self.mark_view_changed();
+        self.clamp_selected_index();
+    }
+
+    pub(crate) fn view_state(&self) -> ViewState {
+        ViewState {
+            result_filters: self.result_filters,
+            collapsed_packages: self.collapsed_packages.clone(),
+            collapsed_tests: self.collapsed_tests.clone(),
+            name_filter: self.name_filter.clone(),
+            sort: self.sort,
+            show_log_lines: self.show_log_lines,
+            kind_filter: self.kind_filter,
+            group_by_path: self.group_by_path,
+            collapsed_groups: self.collapsed_groups.clone(),
+            show_details: self.show_details,
+            zen: self.zen,
+            slow_only: self.slow_only,
+        }
+    }
+
+    pub(crate) fn restore_view_state(&mut self, view_state: ViewState) {
+        self.result_filters = view_state.result_filters;
+        self.collapsed_packages = view_state.collapsed_packages;
+        self.collapsed_tests = view_state.collapsed_tests;
+        self.name_filter = view_state.name_filter;
+        self.sort = view_state.sort;
+        self.show_log_lines = view_state.show_log_lines;
+        self.kind_filter = view_state.kind_filter;
+        self.group_by_path = view_state.group_by_path;
+        self.collapsed_groups = view_state.collapsed_groups;
+        self.show_details = view_state.show_details;
+        self.zen = view_state.zen;
+        self.slow_only = view_state.slow_only;
+        self.mark_view_changed();
+        self.clamp_selected_index();
+    }
+
+    /// Invalidates the list after packages were added, removed or changed
+    pub(crate) fn mark_packages_changed(&mut self) {
+        self.packages_generation = self.packages_generation.wrapping_add(1);
+    }
+
+    /// Invalidates the list after the filters, sorting or folding changed
+    fn mark_view_changed(&mut self) {
+        self.view_generation = self.view_generation.wrapping_add(1);
+    }
+
+    /// Drops all packages and resets the selection, scrolling and filters
+    pub(crate) fn clear(&mut self) {
+        self.packages.clear();
+        self.mark_packages_changed();
+        self.viewport = Viewport::default();
+        self.result_filters = self.default_result_filters;
+        self.kind_filter = KindFilter::All;
+        self.slow_only = false;
+        self.collapsed_groups.clear();
+        self.collapsed_packages.clear();
+        self.collapsed_tests.clear();
+        self.mode = Mode::Normal;
+        self.name_filter.clear();
+        self.package_filter = None;
+        self.mark_view_changed();
+        self.marked.clear();
+        self.selection = None;
+    }
+
+    /// Takes the packages out of the screen, leaving it empty for the next run
+    pub(crate) fn take_packages(&mut self) -> Vec<Package> {
+        self.viewport.selected = 0;
+        self.viewport.scroll_y = 0;
+        self.selection = None;
+        self.marked.clear();
+        self.mark_packages_changed();
+        std::mem::take(&mut self.packages)
+    }
+
+    pub(crate) fn update(&mut self, event: Event, keymap: &Keymap) -> Option<UpdateCommand> {
+        self.track_selection();
+        let command = self.handle_event(event, keymap);
+        self.track_selection();
+        command
+    }
+
+    fn handle_event(&mut self, event: Event, keymap: &Keymap) -> Option<UpdateCommand> {
+        if let Mode::Search = self.mode {
+            return self.update_search(event);
+        }
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => return self.update_mouse(mouse),
+            _ => return None,
+        };
+        let (action, count) = match self
+            .count_prefix
+            .feed(key, |key| keymap.tests_action(key).is_some())
+        {
+            Input::Pending => return Some(UpdateCommand::Render),
+            Input::First(count) => (TestsAction::First, count),
+            Input::Last(count) => (TestsAction::Last, count),
+            Input::Key {
+                key,
+                count,
+                was_pending,
+            } => match keymap.tests_action(&key) {
+                Some(action) if action.is_countable() => (action, count),
+                // Other actions drop the count
+                action => {
+                    return action
+                        .and_then(|action| self.run_action(action, None))
+                        .or(was_pending.then_some(UpdateCommand::Render));
+                }
+            },
+        };
+        self.run_action(action, count)
+    }
+
+    /// Runs an action, motions get repeated or jump to the row of the count typed before them
+    fn run_action(&mut self, action: TestsAction, count: Option<usize>) -> Option<UpdateCommand> {
+        let repeat = count.unwrap_or(1);
+        match action {
+            TestsAction::Down => {
+                let visible_count = self.visible_count();
+                if self.wrap_navigation && visible_count > 0 {
+                    let index = (self.viewport.selected + repeat) % visible_count;
+                    self.viewport.select(index, visible_count);
+                } else {
+                    self.viewport.select_next(repeat, visible_count);
+                }
                 Some(UpdateCommand::Render)
             }
-            Event::Key(KeyWithModifier {
-                bare_key: BareKey::Up | BareKey::Char('k'),
-                ..
-            }) => {
-                self.selected_index = self.selected_index.saturating_sub(1);
+            TestsAction::Up => {
+                let visible_count = self.visible_count();
+                if self.wrap_navigation && visible_count > 0 {
+                    let index = (self.viewport.selected + visible_count - repeat % visible_count)
+                        % visible_count;
+                    self.viewport.select(index, visible_count);
+                } else {
+                    self.viewport.select_previous(repeat);
+                }
                 Some(UpdateCommand::Render)
             }
-            Event::Key(KeyWithModifier {
-                bare_key: BareKey::Left | BareKey::Char('h'),
-                ..
-            }) => {
-                self.scroll_x = self.scroll_x.saturating_sub(1);
+            TestsAction::First | TestsAction::Last => {
+                let index = match (action, count) {
+                    (_, Some(row)) => row.saturating_sub(1),
+                    (TestsAction::First, None) => 0,
+                    (_, None) => usize::MAX,
+                };
+                self.viewport.select(index, self.visible_count());
                 Some(UpdateCommand::Render)
             }
-            Event::Key(KeyWithModifier {
-                bare_key: BareKey::Right | BareKey::Char('l'),
-                ..
-            }) => {
-                self.scroll_x = (self.scroll_x + 1).min(1);
+            TestsAction::ScrollLeft => {
+                self.viewport.scroll_left(repeat);
                 Some(UpdateCommand::Render)
             }
-            Event::Key(KeyWithModifier {
-                bare_key: BareKey::PageDown | BareKey::Char('d'),
-                ..
-            }) => {
-                if let Some(height) = self.screen_height {
-                    self.selected_index = self
-                        .selected_index
-                        .saturating_add(height / 2)
-                        .min(self.visible_list_items().len().saturating_sub(1));
+            TestsAction::ScrollRight => {
+                self.viewport
+                    .scroll_right(repeat, self.visible_columns().len().saturating_sub(1));
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::HalfPageDown => {
+                let lines = self.viewport.half_page() * repeat;
+                self.viewport.select_next(lines, self.visible_count());
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::HalfPageUp => {
+                self.viewport
+                    .select_previous(self.viewport.half_page() * repeat);
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::PageDown => {
+                let lines = self.viewport.page() * repeat;
+                self.viewport.select_next(lines, self.visible_count());
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::PageUp => {
+                self.viewport.select_previous(self.viewport.page() * repeat);
+                Some(UpdateCommand::Render)
+            }
+            // Stops at the last failure found when fewer than the count are left
+            TestsAction::NextFailure => {
+                for step in 0..repeat {
+                    let range = self.viewport.selected + 1..self.visible_count();
+                    match self.find_failure_stop(range, false) {
+                        Some(index) => self.viewport.selected = index,
+                        None if step == 0 => {
+                            return Some(UpdateCommand::Notice("No failures below".to_owned()))
+                        }
+                        None => break,
+                    }
                 }
                 Some(UpdateCommand::Render)
             }
-            Event::Key(KeyWithModifier {
-                bare_key: BareKey::PageUp | BareKey::Char('u'),
-                ..
-            }) => {
-                if let Some(height) = self.screen_height {
-                    self.selected_index = self.selected_index.saturating_sub(height / 2);
+            TestsAction::PreviousFailure => {
+                for step in 0..repeat {
+                    let end = self.viewport.selected.min(self.visible_count());
+                    match self.find_failure_stop(0..end, true) {
+                        Some(index) => self.viewport.selected = index,
+                        None if step == 0 => {
+                            return Some(UpdateCommand::Notice("No failures above".to_owned()))
+                        }
+                        None => break,
+                    }
                 }
                 Some(UpdateCommand::Render)
             }
+            TestsAction::OpenLogs => self
+                .selected_logs_screen()
+                .map(|logs_screen| UpdateCommand::ShowLogsScreen(Box::new(logs_screen))),
+            TestsAction::CycleSort => {
+                self.sort.key = self.sort.key.next();
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ReverseSort => {
+                self.sort.is_descending = !self.sort.is_descending;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::FilterPass => {
+                self.result_filters.pass = !self.result_filters.pass;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::FilterFail => {
+                self.result_filters.fail = !self.result_filters.fail;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::FilterSkip => {
+                self.result_filters.skip = !self.result_filters.skip;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::FilterRace => {
+                self.result_filters.race = !self.result_filters.race;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::FilterTimeout => {
+                self.result_filters.timeout = !self.result_filters.timeout;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::FilterNoTests => {
+                self.result_filters.no_tests = !self.result_filters.no_tests;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::FilterSlow => {
+                self.slow_only = !self.slow_only;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::CycleKindFilter => {
+                self.kind_filter = self.kind_filter.next();
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::RerunFailed => {
+                let reruns = self.failed_tests();
+                if reruns.is_empty() {
+                    None
+                } else {
+                    Some(UpdateCommand::RerunFailed(reruns))
+                }
+            }
+            TestsAction::RerunSelected if !self.marked.is_empty() => {
+                let reruns = self.marked_reruns();
+                if reruns.is_empty() {
+                    Some(UpdateCommand::Notice(
+                        "The marked packages have no tests to rerun".to_owned(),
+                    ))
+                } else {
+                    Some(UpdateCommand::RerunFailed(reruns))
+                }
+            }
+            TestsAction::RerunSelected => match self.list_item(self.viewport.selected)? {
+                ListItem::TestCase {
+                    package, test_case, ..
+                } => Some(UpdateCommand::RerunTest(Rerun {
+                    package: package.name.clone(),
+                    tests: vec![test_case.name.clone()],
+                })),
+                ListItem::Package { .. } | ListItem::Group { .. } => Some(UpdateCommand::Notice(
+                    "Select a test to rerun it".to_owned(),
+                )),
+            },
+            TestsAction::AbortRun => Some(UpdateCommand::AbortRun),
+            TestsAction::Search => {
+                self.mode = Mode::Search;
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ClearSearch if !self.name_filter.is_empty() => {
+                self.name_filter.clear();
+                self.mark_view_changed();
+                self.clamp_selected_index();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ClearSearch if self.package_filter.is_some() => {
+                self.package_filter = None;
+                self.mark_view_changed();
+                self.clamp_selected_index();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ClearSearch if !self.marked.is_empty() => {
+                self.marked.clear();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ShowErrors => Some(UpdateCommand::ShowErrorsScreen),
+            TestsAction::ShowHistory => Some(UpdateCommand::ShowHistoryScreen),
+            TestsAction::ShowRunScreen => Some(UpdateCommand::ShowRunScreen),
+            TestsAction::ShowBenchmarks => Some(UpdateCommand::ShowBenchmarksScreen),
+            TestsAction::ShowTriage => Some(UpdateCommand::ShowTriageScreen),
+            TestsAction::ShowLint => Some(UpdateCommand::ShowLintScreen),
+            TestsAction::ShowSlowest => Some(UpdateCommand::ShowSlowestScreen),
+            TestsAction::GlobalSearch => Some(UpdateCommand::ShowGrepScreen),
+            TestsAction::FindTest => Some(UpdateCommand::ShowFinder),
+            TestsAction::PickPackage => Some(UpdateCommand::ShowPackagePicker),
+            TestsAction::ClearResults => Some(UpdateCommand::ClearResults),
+            TestsAction::ShowHelp => Some(UpdateCommand::ShowHelpScreen),
+            TestsAction::NextStream => Some(UpdateCommand::NextStream),
+            TestsAction::NextProject => Some(UpdateCommand::NextProject),
+            TestsAction::ExportJunit => Some(UpdateCommand::Export(ExportFormat::Junit)),
+            TestsAction::ExportJson => Some(UpdateCommand::Export(ExportFormat::Json)),
+            TestsAction::OpenPager => self
+                .selected_log_source()
+                .map(|(source, _)| UpdateCommand::OpenPager(source)),
+            TestsAction::ToggleMark => {
+                let Some((source, _)) = self.selected_log_source() else {
+                    return Some(UpdateCommand::Notice(
+                        "Select a package or test to mark it".to_owned(),
+                    ));
+                };
+                if !self.marked.remove(&source) {
+                    self.marked.insert(source);
+                }
+                self.viewport.select_next(1, self.visible_count());
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::CopyRunPattern => {
+                let tests = if self.marked.is_empty() {
+                    match self.list_item(self.viewport.selected)? {
+                        ListItem::TestCase { test_case, .. } => vec![test_case.name.clone()],
+                        ListItem::Package { .. } | ListItem::Group { .. } => {
+                            return Some(UpdateCommand::Notice(
+                                "Select or mark tests to copy their -run pattern".to_owned(),
+                            ))
+                        }
+                    }
+                } else {
+                    self.marked_reruns()
+                        .into_iter()
+                        .flat_map(|rerun| rerun.tests)
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .collect()
+                };
+                Some(UpdateCommand::CopyToClipboard(runner::run_pattern(&tests)))
+            }
+            TestsAction::CopyCommand => {
+                let reruns = if self.marked.is_empty() {
+                    match self.list_item(self.viewport.selected)? {
+                        ListItem::TestCase {
+                            package, test_case, ..
+                        } => vec![Rerun {
+                            package: package.name.clone(),
+                            tests: vec![test_case.name.clone()],
+                        }],
+                        ListItem::Package { package, .. } => vec![Rerun {
+                            package: package.name.clone(),
+                            tests: Vec::new(),
+                        }],
+                        ListItem::Group { packages, .. } => packages
+                            .iter()
+                            .map(|package| Rerun {
+                                package: package.name.clone(),
+                                tests: Vec::new(),
+                            })
+                            .collect(),
+                    }
+                } else {
+                    self.marked_runs()
+                };
+                Some(UpdateCommand::CopyToClipboard(runner::reproduce_command(
+                    &reruns,
+                )))
+            }
+            TestsAction::SaveLogs => {
+                let sources = if self.marked.is_empty() {
+                    vec![self.selected_log_source()?.0]
+                } else {
+                    self.marked.iter().cloned().collect()
+                };
+                Some(UpdateCommand::SaveLogs(sources))
+            }
+            TestsAction::CopyLog => self.list_item(self.viewport.selected).map(|list_item| {
+                UpdateCommand::CopyToClipboard(match list_item {
+                    ListItem::Package { package, .. } => package.log.concat(),
+                    ListItem::TestCase { test_case, .. } => {
+                        TestCase::flatten(std::slice::from_ref(test_case))
+                            .iter()
+                            .map(|test| test.log.concat())
+                            .collect()
+                    }
+                    ListItem::Group { packages, .. } => packages
+                        .iter()
+                        .map(|package| package.log.concat())
+                        .collect(),
+                })
+            }),
+            TestsAction::OpenSource => self.list_item(self.viewport.selected).map(|list_item| {
+                let (package, location) = match list_item {
+                    ListItem::Package { package, .. } => {
+                        (Some(package), SourceLocation::find_in_log(&package.log))
+                    }
+                    ListItem::TestCase {
+                        package, test_case, ..
+                    } => (Some(package), SourceLocation::find_in_test(test_case)),
+                    ListItem::Group { .. } => (None, None),
+                };
+                match (package, location) {
+                    (Some(package), Some(location)) => UpdateCommand::OpenSource {
+                        package: package.name.clone(),
+                        location,
+                    },
+                    _ => {
+                        UpdateCommand::Notice("No file:line reference found in the log".to_owned())
+                    }
+                }
+            }),
+            TestsAction::ToggleWatch => Some(UpdateCommand::ToggleWatch),
+            TestsAction::ToggleLogLines => {
+                self.show_log_lines = !self.show_log_lines;
+                self.mark_view_changed();
+                self.viewport.scroll_x = self
+                    .viewport
+                    .scroll_x
+                    .min(self.visible_columns().len().saturating_sub(1));
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ToggleDetails => {
+                self.show_details = !self.show_details;
+                self.mark_view_changed();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ToggleGrouping => {
+                self.group_by_path = !self.group_by_path;
+                self.mark_view_changed();
+                self.clamp_selected_index();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ToggleZen => {
+                self.zen = !self.zen;
+                self.mark_view_changed();
+                self.clamp_selected_index();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ToggleCollapse => {
+                self.toggle_collapsed();
+                self.clamp_selected_index();
+                Some(UpdateCommand::Render)
+            }
+            TestsAction::ClearSearch => Some(UpdateCommand::ExitScreen),
+        }
+    }
+
+    fn update_mouse(&mut self, mouse: Mouse) -> Option<UpdateCommand> {
+        match mouse {
+            Mouse::ScrollDown(lines) => {
+                self.viewport.select_next(lines, self.visible_count());
+                Some(UpdateCommand::Render)
+            }
+            Mouse::ScrollUp(lines) => {
+                self.viewport.select_previous(lines);
+                Some(UpdateCommand::Render)
+            }
+            Mouse::LeftClick(line, _) => {
+                // The first line of the table is the header
+                let row = usize::try_from(line).ok()?.checked_sub(1)?;
+                if row >= self.viewport.page() {
+                    return None;
+                }
+                let index = self.viewport.scroll_y + row;
+                if index >= self.visible_count() {
+                    return None;
+                }
+                let now = Instant::now();
+                let is_double_click = self.last_click.is_some_and(|(last_index, last_time)| {
+                    last_index == index && now.duration_since(last_time) < DOUBLE_CLICK_INTERVAL
+                });
+                self.viewport.selected = index;
+                if is_double_click {
+                    self.last_click = None;
+                    self.selected_logs_screen()
+                        .map(|logs_screen| UpdateCommand::ShowLogsScreen(Box::new(logs_screen)))
+                } else {
+                    self.last_click = Some((index, now));
+                    Some(UpdateCommand::Render)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Selects the first failure of the list, returning the logs screen showing it
+    pub(crate) fn open_first_failure(&mut self) -> Option<LogsScreen> {
+        let index = self.find_failure_stop(0..self.visible_count(), false)?;
+        self.select_row(index);
+        self.selected_logs_screen()
+    }
+
+    pub(crate) fn package_filter(&self) -> Option<&str> {
+        self.package_filter.as_deref()
+    }
+
+    /// Shows only the package, selecting its row
+    pub(crate) fn show_only_package(&mut self, package: Name) {
+        self.package_filter = Some(package);
+        self.mark_view_changed();
+        self.select_row(0);
+    }
+
+    /// Selects the row of a package or test, expanding the groups and tests it's folded into.
+    /// Clears the filters hiding it, returns whether it's shown after all.
+    pub(crate) fn reveal(&mut self, package: &str, test: Option<&str>) -> bool {
+        self.collapsed_groups
+            .retain(|path| !package.starts_with(&format!("{}/", path)));
+        self.collapsed_packages.remove(package);
+        if let (Some(test), Some(collapsed_tests)) = (test, self.collapsed_tests.get_mut(package)) {
+            // Subtests are named after their parents, e.g. `TestA/sub` below `TestA`
+            collapsed_tests.retain(|name| !test.starts_with(&format!("{}/", name)));
+        }
+        self.mark_view_changed();
+        if self.find_row(package, test).is_none() {
+            self.package_filter = None;
+            self.result_filters = ResultFilters::default();
+            self.kind_filter = KindFilter::All;
+            self.slow_only = false;
+            self.name_filter.clear();
+            self.mode = Mode::Normal;
+            self.mark_view_changed();
+        }
+        match self.find_row(package, test) {
+            Some(index) => {
+                self.select_row(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Selects a row, which the selection stays on even if the list changed since it was tracked
+    fn select_row(&mut self, index: usize) {
+        self.viewport.selected = index;
+        self.selection = self.row_id(index);
+        self.selection_key = Some((self.list_key(), index));
+    }
+
+    /// Moves the selection to the new index of its row once the list changed, the index is kept
+    /// when the row is gone. Nothing is looked up while neither the list nor the index changed.
+    fn track_selection(&mut self) {
+        let key = self.list_key();
+        let is_list_changed = match self.selection_key {
+            Some((selection_key, index)) if selection_key == key => {
+                if index == self.viewport.selected {
+                    return;
+                }
+                false
+            }
+            _ => true,
+        };
+        if is_list_changed {
+            if let Some(index) = self
+                .selection
+                .as_ref()
+                .and_then(|row| self.find_row_id(row))
+            {
+                self.viewport.selected = index;
+            }
+            self.clamp_selected_index();
+        }
+        self.selection = self.row_id(self.viewport.selected);
+        self.selection_key = Some((key, self.viewport.selected));
+    }
+
+    fn row_id(&self, index: usize) -> Option<RowId> {
+        Some(match self.list_item(index)? {
+            ListItem::Group { path, .. } => RowId::Group { path },
+            ListItem::Package { package, .. } => RowId::Package {
+                name: package.name.clone(),
+            },
+            ListItem::TestCase {
+                package, test_case, ..
+            } => RowId::TestCase {
+                package: package.name.clone(),
+                test: test_case.name.clone(),
+                attempt: test_case.attempt,
+            },
+        })
+    }
+
+    fn find_row_id(&self, row_id: &RowId) -> Option<usize> {
+        let entries = self.list_entries();
+        entries
+            .iter()
+            .position(|entry| match (self.resolve(entry), row_id) {
+                (Some(ListItem::Group { path, .. }), RowId::Group { path: row_path }) => {
+                    path == *row_path
+                }
+                (Some(ListItem::Package { package, .. }), RowId::Package { name }) => {
+                    package.name == *name
+                }
+                (
+                    Some(ListItem::TestCase {
+                        package, test_case, ..
+                    }),
+                    RowId::TestCase {
+                        package: package_name,
+                        test,
+                        attempt,
+                    },
+                ) => {
+                    package.name == *package_name
+                        && test_case.name == *test
+                        && test_case.attempt == *attempt
+                }
+                _ => false,
+            })
+    }
+
+    fn find_row(&self, package_name: &str, test_name: Option<&str>) -> Option<usize> {
+        let entries = self.list_entries();
+        entries
+            .iter()
+            .position(|entry| match (self.resolve(entry), test_name) {
+                (Some(ListItem::Package { package, .. }), None) => package.name == package_name,
+                (
+                    Some(ListItem::TestCase {
+                        package, test_case, ..
+                    }),
+                    Some(test_name),
+                ) => package.name == package_name && test_case.name == test_name,
+                _ => false,
+            })
+    }
+
+    fn selected_logs_screen(&self) -> Option<LogsScreen> {
+        self.selected_log_source().map(|(source, is_running)| {
+            let mut logs_screen = LogsScreen::new(source, self.case_sensitivity);
+            logs_screen.set_following(is_running);
+            logs_screen
+        })
+    }
+
+    /// The log of the selected package or test, and whether it's still running
+    fn selected_log_source(&self) -> Option<(LogSource, bool)> {
+        self.list_item(self.viewport.selected)
+            .and_then(|list_item| {
+                let (source, is_running) = match list_item {
+                    ListItem::Package { package, .. } => (
+                        LogSource {
+                            package: package.name.clone(),
+                            test: None,
+                            attempt: 0,
+                        },
+                        package.result.is_none(),
+                    ),
+                    ListItem::TestCase {
+                        package, test_case, ..
+                    } => (
+                        LogSource {
+                            package: package.name.clone(),
+                            test: Some(test_case.name.clone()),
+                            attempt: test_case.attempt,
+                        },
+                        test_case.result.is_none(),
+                    ),
+                    ListItem::Group { .. } => return None,
+                };
+                Some((source, is_running))
+            })
+    }
+
+    fn update_search(&mut self, event: Event) -> Option<UpdateCommand> {
+        match event {
             Event::Key(KeyWithModifier {
-                bare_key: BareKey::Char('f'),
+                bare_key: BareKey::Enter,
                 ..
             }) => {
-                if let Some(height) = self.screen_height {
-                    self.selected_index = self
-                        .selected_index
-                        .saturating_add(height)
-                        .min(self.visible_list_items().len().saturating_sub(1));
-                }
+                self.mode = Mode::Normal;
                 Some(UpdateCommand::Render)
             }
             Event::Key(KeyWithModifier {
-                bare_key: BareKey::Char('b'),
+                bare_key: BareKey::Esc,
                 ..
             }) => {
-                if let Some(height) = self.screen_height {
-                    self.selected_index = self.selected_index.saturating_sub(height);
-                }
+                self.mode = Mode::Normal;
+                self.name_filter.clear();
+                self.mark_view_changed();
+                self.clamp_selected_index();
                 Some(UpdateCommand::Render)
             }
             Event::Key(KeyWithModifier {
-                bare_key: BareKey::Enter,
-                ..
-            }) => self
-                .visible_list_items()
-                .get(self.selected_index)
-                .map(|list_item| {
-                    UpdateCommand::ShowLogsScreen(match list_item {
-                        ListItem::Package(package) => LogsScreen::new(package.log.clone()),
-                        ListItem::TestCase(test_case) => LogsScreen::new(test_case.log.clone()),
-                    })
-                }),
-            Event::Key(KeyWithModifier {
-                bare_key: BareKey::Char('1'),
+                bare_key: BareKey::Tab,
                 ..
             }) => {
-                self.result_filters.pass = !self.result_filters.pass;
+                self.case_sensitivity = self.case_sensitivity.next();
+                self.mark_view_changed();
+                self.clamp_selected_index();
                 Some(UpdateCommand::Render)
             }
             Event::Key(KeyWithModifier {
-                bare_key: BareKey::Char('2'),
+                bare_key: BareKey::Backspace,
                 ..
             }) => {
-                self.result_filters.fail = !self.result_filters.fail;
+                self.name_filter.pop();
+                self.mark_view_changed();
+                self.clamp_selected_index();
                 Some(UpdateCommand::Render)
             }
             Event::Key(KeyWithModifier {
-                bare_key: BareKey::Char('3'),
+                bare_key: BareKey::Char(c),
                 ..
             }) => {
-                self.result_filters.skip = !self.result_filters.skip;
+                self.name_filter.push(c);
+                self.mark_view_changed();
+                self.clamp_selected_index();
                 Some(UpdateCommand::Render)
             }
             _ => None,
         }
     }
 
-    pub(crate) fn render(&mut self, rows: usize, cols: usize) {
-        self.screen_width = Some(cols);
-        self.screen_height = Some(rows - 3);
-        let bottom_index = self.scroll_y + self.screen_height.unwrap();
-        if self.selected_index > bottom_index {
-            self.scroll_y = self
-                .scroll_y
-                .saturating_add(self.selected_index - bottom_index);
-        } else if self.selected_index < self.scroll_y {
-            self.scroll_y = self
-                .scroll_y
-                .saturating_sub(self.scroll_y - self.selected_index);
-        }
+    fn clamp_selected_index(&mut self) {
+        self.viewport.clamp_selection(self.visible_count());
+    }
+
+    pub(crate) fn render(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        coverage_thresholds: Option<CoverageThresholds>,
+        status_bar: &StatusBar,
+    ) {
+        // The details take the bottom third, unless the list would get too short
+        let details_height = if self.show_details && rows >= MIN_DETAILS_ROWS {
+            rows / 3
+        } else {
+            0
+        };
+        let location_height = usize::from(rows >= MIN_LOCATION_ROWS);
+        let table_height = rows.saturating_sub(2 + location_height + details_height);
+        // The first row of the table is the header
+        self.viewport.resize(cols, table_height.saturating_sub(1));
+        self.track_selection();
+        self.viewport.follow_selection(self.visible_count());
 
-        let table_rows = self.render_list_items();
-        let headers = ["package", "elapsed"];
-        let table = Table::new().add_row(Vec::from(&headers[self.scroll_x..]));
+        let theme = theme::current();
+        let table_rows = self.render_list_items(
+            coverage_thresholds,
+            status_bar.spinner,
+            self.viewport.height(),
+        );
+        let columns = self.visible_columns();
+        let mut headers = columns
+            .iter()
+            .map(|column| column.header().to_owned())
+            .collect::<Vec<_>>();
+        // The labels of the view go to the package tree, or to the first column when it's hidden
+        let label_index = columns
+            .iter()
+            .position(|column| *column == Column::Package)
+            .unwrap_or(0);
+        // The arrow goes to the sorted column, or to the labels when that column isn't shown
+        let sorted_column = match self.sort.key {
+            SortKey::RunOrder => None,
+            SortKey::Name => Some(Column::Package),
+            SortKey::Result => Some(Column::Result),
+            SortKey::Elapsed => Some(Column::Elapsed),
+            SortKey::LogLines => Some(Column::OutputLines),
+        };
+        let arrow = self.sort.arrow();
+        match sorted_column.map(|column| (column, columns.iter().position(|c| *c == column))) {
+            None => (),
+            Some((_, Some(index))) => headers[index] = format!("{} {}", headers[index], arrow),
+            Some((column, None)) => {
+                headers[label_index] = format!(
+                    "{} (by {} {})",
+                    headers[label_index],
+                    column.header(),
+                    arrow
+                )
+            }
+        }
+        if let Some(label) = self.kind_filter.label() {
+            headers[label_index] = format!("{} [{}]", headers[label_index], label);
+        }
+        if self.zen {
+            let hidden_count = self
+                .packages
+                .iter()
+                .filter(|package| is_hidden_by_zen(package))
+                .count();
+            headers[label_index] = format!(
+                "{} [zen, {} done hidden]",
+                headers[label_index], hidden_count
+            );
+        }
+        if self.package_filter.is_some() {
+            headers[label_index] = format!("{} [one package, Esc all]", headers[label_index]);
+        }
+        let table = Table::new().add_row(Vec::from(&headers[self.viewport.scroll_x..]));
 
         let table = table_rows
             .into_iter()
             .enumerate()
-            .skip(self.scroll_y)
-            .take(rows - 2)
             .fold(table, |acc, (i, row)| {
-                if i == self.selected_index {
+                if self.viewport.scroll_y + i == self.viewport.selected {
                     acc.add_styled_row(
                         row.into_iter()
-                            .skip(self.scroll_x)
-                            .map(|column| column.selected())
+                            .skip(self.viewport.scroll_x)
+                            .map(|column| theme.select(column))
                             .collect(),
                     )
                 } else {
-                    acc.add_styled_row(row.into_iter().skip(self.scroll_x).collect())
+                    acc.add_styled_row(row.into_iter().skip(self.viewport.scroll_x).collect())
                 }
             });
-        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows));
-
-        let pass_ribbon = Text::new("[1] pass");
-        let fail_ribbon = Text::new("[2] fail");
-        let skip_ribbon = Text::new("[3] skip");
-        print_ribbon_with_coordinates(
-            if self.result_filters.pass {
-                pass_ribbon.selected()
-            } else {
-                pass_ribbon
-            },
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(table_height));
+        if details_height > 0 {
+            self.render_details(table_height, details_height, cols);
+        }
+        // Cut at the start when it's too long, as the end names the selected test
+        if let Some(location) = self.location().filter(|_| location_height > 0) {
+            print_text_with_coordinates(
+                Text::new(truncate_start(&location, cols)),
+                0,
+                rows.saturating_sub(3),
+                Some(cols),
+                Some(1),
+            );
+        }
+        // Shortened names are spelled out for the selected row when its location isn't shown
+        let selected_path = self
+            .module_prefix
+            .as_ref()
+            .filter(|_| location_height == 0)
+            .and_then(|_| self.list_item(self.viewport.selected))
+            .map(|list_item| match list_item {
+                ListItem::Group { path, .. } => Cow::Owned(path),
+                ListItem::Package { package, .. } | ListItem::TestCase { package, .. } => {
+                    Cow::Borrowed(package.name.as_str())
+                }
+            });
+        print_text_with_coordinates(
+            render_status_bar(
+                status_bar,
+                status_bar.summary.progress(self.packages.len()),
+                selected_path.as_deref(),
+                self.count_prefix.pending().as_deref(),
+            ),
             0,
-            rows - 1,
-            None,
-            None,
-        );
-        print_ribbon_with_coordinates(
-            if self.result_filters.fail {
-                fail_ribbon.selected()
-            } else {
-                fail_ribbon
-            },
-            13,
-            rows - 1,
-            None,
-            None,
-        );
-        print_ribbon_with_coordinates(
-            if self.result_filters.skip {
-                skip_ribbon.selected()
-            } else {
-                skip_ribbon
-            },
-            26,
-            rows - 1,
-            None,
-            None,
+            rows.saturating_sub(2),
+            Some(cols),
+            Some(1),
         );
+
+        // The counts are kept up to date by the summary as results arrive
+        let summary = &status_bar.summary;
+        let slow_label = format!("[7] slow >{}", format_threshold(self.slow_threshold));
+        let ribbons = [
+            ("[1] pass", self.result_filters.pass, summary.passed),
+            (
+                "[2] fail",
+                self.result_filters.fail,
+                summary.fail_filter_count(),
+            ),
+            ("[3] skip", self.result_filters.skip, summary.skipped),
+            ("[4] race", self.result_filters.race, summary.raced),
+            (
+                "[5] timeout",
+                self.result_filters.timeout,
+                summary.timed_out,
+            ),
+            (
+                "[6] no tests",
+                self.result_filters.no_tests,
+                summary.without_tests,
+            ),
+            (slow_label.as_str(), self.slow_only, summary.slow),
+        ];
+        // The hint is followed by the number of chars to emphasize at its start
+        let hint = if let Mode::Search = self.mode {
+            Some((
+                format!(
+                    "/{}  [Tab] {}",
+                    self.name_filter,
+                    self.case_sensitivity.label()
+                ),
+                0,
+            ))
+        } else if !self.name_filter.is_empty() {
+            Some((format!("filter: {} [Esc] clear", self.name_filter), 0))
+        } else if !self.marked.is_empty() {
+            let count = self.marked.len().to_string();
+            Some((format!("{} marked [Esc] unmark", count), count.len()))
+        } else {
+            None
+        };
+        // The ribbons make room for the hint, which is cut to what's left after the keys
+        let hint_width = hint
+            .as_ref()
+            .map_or(0, |(hint, _)| hint.chars().count() + 2);
+        let labels = fit_ribbons(&ribbons, cols.saturating_sub(hint_width));
+        let mut x = 0;
+        for (label, (_, is_selected, _)) in labels.into_iter().zip(ribbons) {
+            let width = label.chars().count() + RIBBON_PADDING;
+            let ribbon = Text::new(label);
+            print_ribbon_with_coordinates(
+                if is_selected {
+                    ribbon.selected()
+                } else {
+                    ribbon
+                },
+                x,
+                rows.saturating_sub(1),
+                None,
+                None,
+            );
+            x += width;
+        }
+        if let Some((hint, emphasized)) = hint {
+            // The end of the search is kept, where the query is typed
+            let hint = match self.mode {
+                Mode::Search => truncate_start(&hint, cols.saturating_sub(x)),
+                _ => truncate(&hint, cols.saturating_sub(x)),
+            };
+            let text = Text::new(hint);
+            print_text_with_coordinates(
+                if emphasized > 0 {
+                    text.color_range(theme.emphasis, ..emphasized)
+                } else {
+                    text
+                },
+                x,
+                rows.saturating_sub(1),
+                None,
+                Some(1),
+            );
+        }
+    }
+
+    /// Where the selection is: the full name of the selected row and its position in the list,
+    /// e.g. `example.com/api › TestGet/missing — 12/245`
+    fn location(&self) -> Option<String> {
+        let name = match self.list_item(self.viewport.selected)? {
+            ListItem::Group { path, .. } => format!("{}/", path),
+            ListItem::Package { package, .. } => {
+                format!("{}{}", package.name, crate::attempt_suffix(package.attempt))
+            }
+            ListItem::TestCase {
+                package, test_case, ..
+            } => format!(
+                "{} › {}{}",
+                package.name,
+                test_case.name,
+                crate::attempt_suffix(test_case.attempt)
+            ),
+        };
+        Some(format!(
+            "{} — {}/{}",
+            name,
+            self.viewport.selected + 1,
+            self.visible_count()
+        ))
+    }
+
+    /// Previews the selected row below the list: its full name, result and elapsed time, why it
+    /// failed or was skipped, and the start of its log
+    fn render_details(&self, y: usize, height: usize, cols: usize) {
+        let Some(list_item) = self.list_item(self.viewport.selected) else {
+            return;
+        };
+        let theme = theme::current();
+        let result_text = |result: Option<TestResult>, elapsed: Option<f64>| {
+            let result = result.as_ref().map_or("running", |result| result.as_ref());
+            let text = match elapsed {
+                Some(elapsed) => format!("{} in {}", result, format_elapsed(elapsed)),
+                None => result.to_owned(),
+            };
+            Text::new(text)
+        };
+        let mut lines = vec![Text::new("─".repeat(cols)).color_range(theme.accent, ..)];
+        let log: &[String] = match list_item {
+            ListItem::Group { path, packages, .. } => {
+                let failed_count = packages
+                    .iter()
+                    .filter(|package| is_failed_package(package))
+                    .count();
+                lines.push(Text::new(&path).color_range(theme.accent, ..));
+                lines.push(Text::new(format!(
+                    "{} packages, {} failed",
+                    packages.len(),
+                    failed_count
+                )));
+                &[]
+            }
+            ListItem::Package { package, .. } => {
+                lines.push(Text::new(&package.name).color_range(theme.accent, ..));
+                lines.push(result_text(package.result_or_derived(), package.elapsed));
+                if let Some(test_time) = package.test_time() {
+                    lines.push(Text::new(format!(
+                        "tests took {} in total",
+                        format_elapsed(test_time)
+                    )));
+                }
+                if let Some(timeout) = &package.timeout {
+                    lines.push(Text::new(timeout).color_range(theme.fail, ..));
+                }
+                &package.log
+            }
+            ListItem::TestCase {
+                package, test_case, ..
+            } => {
+                let name = format!("{} {}", package.name, test_case.name);
+                lines.push(Text::new(name).color_range(theme.accent, ..package.name.len()));
+                lines.push(result_text(test_case.result, test_case.elapsed));
+                if test_case.result.is_some_and(|result| result.is_failure()) {
+                    if let Some(message) = &test_case.failure_message {
+                        lines.push(Text::new(message).color_range(theme.fail, ..));
+                    }
+                } else if let Some(reason) = &test_case.skip_reason {
+                    lines.push(Text::new(reason).color_range(theme.skip, ..));
+                }
+                &test_case.log
+            }
+        };
+        let log_height = height.saturating_sub(lines.len());
+        lines.extend(log.iter().take(log_height).map(|line| {
+            let line = ansi::StyledLine::parse(line.trim_end());
+            line.colors
+                .into_iter()
+                .fold(Text::new(line.text), |text, (color, range)| {
+                    text.color_range(color, range)
+                })
+        }));
+        for (i, line) in lines.into_iter().take(height).enumerate() {
+            print_text_with_coordinates(line, 0, y + i, Some(cols), Some(1));
+        }
     }
 
-    fn render_list_items(&self) -> Vec<Vec<Text>> {
-        let list_items = self.visible_list_items();
-        list_items
+    /// Renders the rows of the list items scrolled into view, the others aren't styled at all
+    fn render_list_items(
+        &self,
+        coverage_thresholds: Option<CoverageThresholds>,
+        spinner: char,
+        count: usize,
+    ) -> Vec<Vec<Text>> {
+        let columns = self.visible_columns();
+        self.list_items(self.viewport.scroll_y..self.viewport.scroll_y.saturating_add(count))
             .iter()
-            .enumerate()
-            .map(|(i, item)| {
+            .map(|item| {
                 item.render(
-                    list_items
-                        .get(i + 1)
-                        .map(|next_item| {
-                            if let ListItem::Package(_) = next_item {
-                                true
-                            } else {
-                                false
-                            }
-                        })
-                        .unwrap_or(true),
+                    &columns,
+                    coverage_thresholds,
+                    spinner,
+                    self.module_prefix.as_deref(),
+                    self.is_marked(item),
                 )
             })
             .collect()
     }
 
-    fn visible_list_items(&self) -> Vec<ListItem> {
+    fn visible_columns(&self) -> Vec<Column> {
+        self.columns.visible(self.show_log_lines)
+    }
+
+    /// Renders the status bar alone, for panes too small to show the list
+    pub(crate) fn render_compact(&self, rows: usize, cols: usize, status_bar: &StatusBar) {
+        if rows > 0 {
+            let text = render_status_bar(
+                status_bar,
+                status_bar.summary.progress(self.packages.len()),
+                None,
+                None,
+            );
+            print_text_with_coordinates(text, 0, 0, Some(cols), Some(1));
+        }
+    }
+
+    /// The list's entries, recomputed if the packages or the view state changed since the last call
+    fn list_entries(&self) -> Ref<'_, [ListEntry]> {
+        let key = self.list_key();
+        if self.list_index.borrow().key != Some(key) {
+            let entries = self.build_list_entries();
+            *self.list_index.borrow_mut() = ListIndex {
+                key: Some(key),
+                entries,
+            };
+        }
+        Ref::map(self.list_index.borrow(), |list_index| {
+            list_index.entries.as_slice()
+        })
+    }
+
+    fn list_key(&self) -> ListKey {
+        ListKey {
+            packages_generation: self.packages_generation,
+            view_generation: self.view_generation,
+        }
+    }
+
+    /// Number of rows of the list
+    fn visible_count(&self) -> usize {
+        self.list_entries().len()
+    }
+
+    fn list_item(&self, index: usize) -> Option<ListItem<'_>> {
+        self.list_entries()
+            .get(index)
+            .and_then(|entry| self.resolve(entry))
+    }
+
+    /// The list items of a range of rows
+    fn list_items(&self, range: Range<usize>) -> Vec<ListItem<'_>> {
+        let entries = self.list_entries();
+        let range = range.start.min(entries.len())..range.end.min(entries.len());
+        entries[range]
+            .iter()
+            .filter_map(|entry| self.resolve(entry))
+            .collect()
+    }
+
+    /// The first row in a range, or the last if searching backwards, to stop at when jumping
+    /// between failures
+    fn find_failure_stop(&self, mut range: Range<usize>, is_backwards: bool) -> Option<usize> {
+        let entries = self.list_entries();
+        let is_failure_stop = |index: &usize| {
+            entries
+                .get(*index)
+                .and_then(|entry| self.resolve(entry))
+                .is_some_and(|list_item| list_item.is_failure_stop())
+        };
+        if is_backwards {
+            range.rev().find(is_failure_stop)
+        } else {
+            range.find(is_failure_stop)
+        }
+    }
+
+    fn resolve(&self, entry: &ListEntry) -> Option<ListItem<'_>> {
+        if let Some(group) = &entry.group {
+            return Some(ListItem::Group {
+                path: group.path.clone(),
+                label: group.label.clone(),
+                packages: group
+                    .packages
+                    .iter()
+                    .filter_map(|index| self.packages.get(*index))
+                    .collect(),
+                collapsed: entry.collapsed,
+                depth: entry.depth,
+            });
+        }
+        let package = self.packages.get(entry.package)?;
+        let Some((first, rest)) = entry.test_path.split_first() else {
+            return Some(ListItem::Package {
+                package,
+                collapsed: entry.collapsed,
+                depth: entry.depth,
+            });
+        };
+        let test_case = rest
+            .iter()
+            .try_fold(package.tests.get(*first)?, |test_case, index| {
+                test_case.subtests.get(*index)
+            })?;
+        Some(ListItem::TestCase {
+            package,
+            test_case,
+            is_last: entry.is_last.clone(),
+            collapsed: entry.collapsed,
+            depth: entry.depth,
+        })
+    }
+
+    fn build_list_entries(&self) -> Vec<ListEntry> {
+        let mut packages = self.packages.iter().enumerate().collect::<Vec<_>>();
+        self.sort.apply(&mut packages);
+        let packages = packages
+            .into_iter()
+            .filter(|(_, package)| !self.zen || !is_hidden_by_zen(package))
+            .filter(|(_, package)| {
+                self.package_filter
+                    .as_ref()
+                    .is_none_or(|name| *name == package.name)
+            })
+            .filter(|(_, package)| {
+                self.is_test_visible(package.result_or_derived().unwrap_or_default())
+            })
+            .filter(|(_, package)| {
+                self.matches_name_filter(&package.name)
+                    || package
+                        .tests
+                        .iter()
+                        .any(|test| self.is_test_case_visible(test, false))
+            })
+            .filter(|(_, package)| {
+                (self.kind_filter == KindFilter::All && !self.slow_only)
+                    || package
+                        .tests
+                        .iter()
+                        .any(|test| self.is_test_case_visible(test, true))
+            })
+            .collect::<Vec<_>>();
+        let mut entries = Vec::new();
+        if self.group_by_path {
+            // The module path shared by all packages doesn't get a header of its own
+            let names = self.packages.iter().map(|package| package.name.as_str());
+            let shortest = names
+                .clone()
+                .map(|name| name.split('/').count())
+                .min()
+                .unwrap_or(0);
+            let module_segments = shared_segments(names).min(shortest.saturating_sub(1));
+            self.push_grouped_packages(&mut entries, &packages, module_segments, 0);
+        } else {
+            for package in packages {
+                self.push_package_entries(&mut entries, package, 0);
+            }
+        }
+        entries
+    }
+
+    /// Pushes the entries of packages sharing their first `segment` path segments. Packages that
+    /// share further segments are nested below a header of the shared path, merging directories
+    /// that hold nothing but a single directory.
+    fn push_grouped_packages(
+        &self,
+        entries: &mut Vec<ListEntry>,
+        packages: &[(usize, &Package)],
+        segment: usize,
+        depth: usize,
+    ) {
+        let mut groups: Vec<Vec<(usize, &Package)>> = Vec::new();
+        for &(package_index, package) in packages {
+            let key = package.name.split('/').nth(segment);
+            // A package named like the group's path isn't grouped with its subpackages
+            let group = groups
+                .iter_mut()
+                .find(|group| key.is_some() && group[0].1.name.split('/').nth(segment) == key);
+            match group {
+                Some(group) => group.push((package_index, package)),
+                None => groups.push(vec![(package_index, package)]),
+            }
+        }
+        for group in groups {
+            if let [package] = group.as_slice() {
+                self.push_package_entries(entries, *package, depth);
+                continue;
+            }
+            let shared = shared_segments(group.iter().map(|(_, package)| package.name.as_str()));
+            let path = group[0].1.name.split('/').take(shared).collect::<Vec<_>>();
+            let label = format!("{}/", path[segment..].join("/"));
+            let path = path.join("/");
+            let collapsed = self.collapsed_groups.contains(&path);
+            entries.push(ListEntry {
+                package: group[0].0,
+                test_path: Vec::new(),
+                is_last: Vec::new(),
+                collapsed,
+                depth,
+                group: Some(GroupEntry {
+                    path,
+                    label,
+                    packages: group
+                        .iter()
+                        .map(|(package_index, _)| *package_index)
+                        .collect(),
+                }),
+            });
+            if !collapsed {
+                self.push_grouped_packages(entries, &group, shared, depth + 1);
+            }
+        }
+    }
+
+    /// Pushes the entry of a package followed by those of its visible tests
+    fn push_package_entries(
+        &self,
+        entries: &mut Vec<ListEntry>,
+        (package_index, package): (usize, &Package),
+        depth: usize,
+    ) {
+        let start = entries.len();
+        let collapsed = self.collapsed_packages.contains(&package.name);
+        entries.push(ListEntry {
+            package: package_index,
+            test_path: Vec::new(),
+            is_last: Vec::new(),
+            collapsed,
+            depth,
+            group: None,
+        });
+        if !collapsed {
+            let package_matches = self.matches_name_filter(&package.name);
+            self.push_visible_tests(
+                entries,
+                (package_index, package),
+                &package.tests,
+                (&[], &[]),
+                package_matches,
+            );
+        }
+        for entry in &mut entries[start..] {
+            entry.depth = depth;
+        }
+    }
+
+    /// Pushes the entries of the visible tests among siblings, given the index path and whether
+    /// each ancestor is the last visible sibling
+    fn push_visible_tests(
+        &self,
+        entries: &mut Vec<ListEntry>,
+        (package_index, package): (usize, &Package),
+        tests: &[TestCase],
+        (ancestors_path, ancestors_last): (&[usize], &[bool]),
+        package_matches: bool,
+    ) {
+        let mut visible_tests: Vec<(usize, &TestCase)> = tests
+            .iter()
+            .enumerate()
+            .filter(|(_, test)| self.is_test_case_visible(test, package_matches))
+            .collect();
+        self.sort.apply(&mut visible_tests);
+        for (i, (test_index, test_case)) in visible_tests.iter().enumerate() {
+            let mut test_path = ancestors_path.to_vec();
+            test_path.push(*test_index);
+            let mut is_last = ancestors_last.to_vec();
+            is_last.push(i + 1 == visible_tests.len());
+            let collapsed = self.is_collapsed(&package.name, &test_case.name);
+            entries.push(ListEntry {
+                package: package_index,
+                test_path: test_path.clone(),
+                is_last: is_last.clone(),
+                collapsed,
+                depth: 0,
+                group: None,
+            });
+            if !collapsed {
+                self.push_visible_tests(
+                    entries,
+                    (package_index, package),
+                    &test_case.subtests,
+                    (&test_path, &is_last),
+                    package_matches,
+                );
+            }
+        }
+    }
+
+    fn failed_tests(&self) -> Vec<Rerun> {
         self.packages
             .iter()
-            .filter(|package| self.is_test_visible(package.result.unwrap_or_default()))
-            .flat_map(|package| {
-                let mut list_items = Vec::new();
-                list_items.push(ListItem::Package(package));
-                for test in package
+            .filter_map(|package| {
+                let tests: Vec<Name> = package
                     .tests
                     .iter()
-                    .filter(|test| self.is_test_visible(test.result.unwrap_or_default()))
-                {
-                    list_items.push(ListItem::TestCase(test));
+                    .filter(|test| test.result.is_some_and(|result| result.is_failure()))
+                    .map(|test| test.name.clone())
+                    .collect();
+                (!tests.is_empty()).then(|| Rerun {
+                    package: package.name.clone(),
+                    tests,
+                })
+            })
+            .collect()
+    }
+
+    /// The top-level tests of the marked tests, and all the tests of the marked packages. Subtests
+    /// rerun with their top-level test, as `-run` patterns of several tests can't match subtests.
+    fn marked_reruns(&self) -> Vec<Rerun> {
+        self.marked_runs()
+            .into_iter()
+            .filter_map(|mut rerun| {
+                if rerun.tests.is_empty() {
+                    let package = self
+                        .packages
+                        .iter()
+                        .find(|package| package.name == rerun.package)?;
+                    rerun.tests = package.tests.iter().map(|test| test.name.clone()).collect();
+                }
+                (!rerun.tests.is_empty()).then_some(rerun)
+            })
+            .collect()
+    }
+
+    /// The top-level tests of the marked tests by package, with no tests for the packages marked
+    /// as a whole
+    fn marked_runs(&self) -> Vec<Rerun> {
+        let mut tests = BTreeMap::<&Name, Option<BTreeSet<Name>>>::new();
+        for source in &self.marked {
+            let Some(package) = self
+                .packages
+                .iter()
+                .find(|package| package.name == source.package)
+            else {
+                continue;
+            };
+            let package_tests = tests
+                .entry(&package.name)
+                .or_insert_with(|| Some(BTreeSet::new()));
+            match (&source.test, package_tests) {
+                (Some(test), Some(package_tests)) => {
+                    let top_level = test.split_once('/').map_or(test.as_str(), |(test, _)| test);
+                    package_tests.insert(Name::new(top_level));
                 }
-                list_items
+                (Some(_), None) => (),
+                (None, package_tests) => *package_tests = None,
+            }
+        }
+        tests
+            .into_iter()
+            .map(|(package, tests)| Rerun {
+                package: package.clone(),
+                tests: tests.unwrap_or_default().into_iter().collect(),
             })
             .collect()
     }
 
+    fn is_marked(&self, list_item: &ListItem) -> bool {
+        match list_item {
+            ListItem::Package { package, .. } => self.marked.contains(&LogSource {
+                package: package.name.clone(),
+                test: None,
+                attempt: 0,
+            }),
+            ListItem::TestCase {
+                package, test_case, ..
+            } => self.marked.contains(&LogSource {
+                package: package.name.clone(),
+                test: Some(test_case.name.clone()),
+                attempt: test_case.attempt,
+            }),
+            ListItem::Group { .. } => false,
+        }
+    }
+
+    fn is_collapsed(&self, package_name: &str, test_name: &str) -> bool {
+        self.collapsed_tests
+            .get(package_name)
+            .is_some_and(|tests| tests.contains(test_name))
+    }
+
+    fn toggle_collapsed(&mut self) {
+        let group_path = match self.list_item(self.viewport.selected) {
+            Some(ListItem::Group { path, .. }) => Some(path),
+            _ => None,
+        };
+        if let Some(group_path) = group_path {
+            if !self.collapsed_groups.remove(&group_path) {
+                self.collapsed_groups.insert(group_path);
+            }
+            self.mark_view_changed();
+            return;
+        }
+        let Some((package_name, test_name)) =
+            self.list_item(self.viewport.selected)
+                .and_then(|list_item| match list_item {
+                    ListItem::Package { package, .. } => Some((package.name.clone(), None)),
+                    ListItem::TestCase {
+                        package, test_case, ..
+                    } if !test_case.subtests.is_empty() => {
+                        Some((package.name.clone(), Some(test_case.name.clone())))
+                    }
+                    _ => None,
+                })
+        else {
+            return;
+        };
+        match test_name {
+            Some(test_name) => {
+                let collapsed_tests = self.collapsed_tests.entry(package_name).or_default();
+                if !collapsed_tests.remove(&test_name) {
+                    collapsed_tests.insert(test_name);
+                }
+            }
+            None => {
+                if !self.collapsed_packages.remove(&package_name) {
+                    self.collapsed_packages.insert(package_name);
+                }
+            }
+        }
+        self.mark_view_changed();
+    }
+
+    /// A test is visible if it passes the filters itself or any of its subtests do. Tests of a
+    /// package that matches the name filter only need to pass the result filters. Subtests share
+    /// the kind of their top level test.
+    fn is_test_case_visible(&self, test_case: &TestCase, package_matches: bool) -> bool {
+        // Subtests take no longer than their parents, so they're hidden along with them
+        if !self.kind_filter.matches(test_case.kind())
+            || (self.slow_only && !self.is_slow(test_case))
+        {
+            return false;
+        }
+        (self.is_test_visible(test_case.result.unwrap_or_default())
+            && (package_matches || self.matches_name_filter(&test_case.name)))
+            || test_case
+                .subtests
+                .iter()
+                .any(|subtest| self.is_test_case_visible(subtest, package_matches))
+    }
+
+    fn is_slow(&self, test_case: &TestCase) -> bool {
+        test_case.is_slow(self.slow_threshold)
+    }
+
+    fn matches_name_filter(&self, name: &str) -> bool {
+        self.case_sensitivity.contains(name, &self.name_filter)
+    }
+
     fn is_test_visible(&self, test_result: TestResult) -> bool {
+        if self.failures_only {
+            return test_result.is_failure();
+        }
         matches!(
             (self.result_filters, test_result),
             (
@@ -263,57 +2165,567 @@ impl TestsScreen {
                     pass: false,
                     fail: false,
                     skip: false,
+                    race: false,
+                    timeout: false,
+                    no_tests: false,
                 },
                 _,
             ) | (ResultFilters { pass: true, .. }, TestResult::Pass)
-                | (ResultFilters { fail: true, .. }, TestResult::Fail)
+                | (
+                    ResultFilters { fail: true, .. },
+                    TestResult::Fail | TestResult::BuildFail
+                )
                 | (ResultFilters { skip: true, .. }, TestResult::Skip)
+                | (ResultFilters { race: true, .. }, TestResult::Race)
+                | (ResultFilters { timeout: true, .. }, TestResult::Timeout)
+                | (ResultFilters { no_tests: true, .. }, TestResult::NoTests)
         )
     }
 }
 
 #[derive(Debug)]
 enum ListItem<'a> {
-    Package(&'a Package),
-    TestCase(&'a TestCase),
+    Group {
+        path: String,
+        label: String,
+        packages: Vec<&'a Package>,
+        collapsed: bool,
+        depth: usize,
+    },
+    Package {
+        package: &'a Package,
+        collapsed: bool,
+        depth: usize,
+    },
+    TestCase {
+        package: &'a Package,
+        test_case: &'a TestCase,
+        /// Whether each ancestor and finally the test itself is the last visible sibling
+        is_last: Vec<bool>,
+        collapsed: bool,
+        depth: usize,
+    },
 }
 
 impl<'a> ListItem<'a> {
-    fn render(&self, is_last_element: bool) -> Vec<Text> {
-        let mut row = Vec::new();
+    /// Whether jumping between failures stops at the item: failed tests, and failed packages
+    /// whose failure isn't shown by a failed test below them, e.g. build failures
+    fn is_failure_stop(&self) -> bool {
         match self {
-            ListItem::Package(package) => {
-                let test_result = package.result.unwrap_or(TestResult::Skip);
-                let marker_color = test_result.marker_color();
-                let marker_char = test_result.marker_char();
-                row.push(
-                    Text::new(format!("{} {}", marker_char, package.name))
-                        .color_range(marker_color, ..1),
-                );
-                row.push(
-                    package
-                        .elapsed
-                        .map(|elapsed| Text::new(format!("{}s", elapsed)))
-                        .unwrap_or(Text::new(" ")),
-                );
+            ListItem::Group {
+                packages,
+                collapsed,
+                ..
+            } => *collapsed && packages.iter().any(|package| is_failed_package(package)),
+            ListItem::Package {
+                package, collapsed, ..
+            } => {
+                package
+                    .result_or_derived()
+                    .is_some_and(|result| result.is_failure())
+                    && (*collapsed || package.summary.failed == 0)
             }
-            ListItem::TestCase(test_case) => {
-                let border = if is_last_element { '└' } else { '├' };
-                let test_result = test_case.result.unwrap_or(TestResult::Skip);
-                let marker_color = test_result.marker_color();
-                let marker_char = test_result.marker_char();
-                row.push(
-                    Text::new(format!("{} {} {}", border, marker_char, test_case.name))
-                        .color_range(marker_color, 2..3),
-                );
-                row.push(
-                    test_case
-                        .elapsed
-                        .map(|elapsed| Text::new(format!("{}s", elapsed)))
-                        .unwrap_or(Text::new(" ")),
+            ListItem::TestCase {
+                test_case,
+                collapsed,
+                ..
+            } => {
+                test_case.result.is_some_and(|result| result.is_failure())
+                    && (*collapsed || test_case.failed_count() == 1)
+            }
+        }
+    }
+
+    /// Renders the cells of the columns in their order
+    fn render(
+        &self,
+        columns: &[Column],
+        coverage_thresholds: Option<CoverageThresholds>,
+        spinner: char,
+        module_prefix: Option<&str>,
+        is_marked: bool,
+    ) -> Vec<Text> {
+        columns
+            .iter()
+            .map(|column| match column {
+                Column::Package => self.render_name(spinner, module_prefix, is_marked),
+                column => self.render_column(*column, coverage_thresholds),
+            })
+            .collect()
+    }
+
+    /// The tree of groups, packages and tests, with the result marker before each name
+    fn render_name(&self, spinner: char, module_prefix: Option<&str>, is_marked: bool) -> Text {
+        // Marked rows get a bullet between their marker and name
+        let mark = if is_marked { "● " } else { "" };
+        let mark_width = usize::from(is_marked);
+        match self {
+            ListItem::Group {
+                label,
+                packages,
+                collapsed,
+                depth,
+                ..
+            } => {
+                let indent = "  ".repeat(*depth);
+                let failed_count = packages
+                    .iter()
+                    .filter(|package| is_failed_package(package))
+                    .count();
+                let counts = if failed_count > 0 {
+                    format!(" ({} packages, {} failed)", packages.len(), failed_count)
+                } else {
+                    format!(" ({} packages)", packages.len())
+                };
+                let text = Text::new(format!(
+                    "{}{} {}{}",
+                    indent,
+                    if *collapsed { '▸' } else { '▾' },
+                    label,
+                    counts
+                ));
+                if failed_count > 0 {
+                    text.color_range(
+                        TestResult::Fail.marker_color(),
+                        indent.len()..indent.len() + 1,
+                    )
+                } else {
+                    text
+                }
+            }
+            ListItem::Package {
+                package,
+                collapsed,
+                depth,
+            } => {
+                let indent = "  ".repeat(*depth);
+                let collapsed_suffix = if *collapsed {
+                    format!(
+                        " ({} tests, {} failed)",
+                        package.summary.tests, package.summary.failed
+                    )
+                } else {
+                    String::new()
+                };
+                // Derived statuses use lowercase markers to tell them apart from go's results
+                let (marker_color, marker_char) = match (package.result, package.derived_status()) {
+                    (Some(test_result), _) => {
+                        (test_result.marker_color(), test_result.marker_char())
+                    }
+                    (None, DerivedStatus::Running) => (RunState::Running.marker_color(), spinner),
+                    (None, DerivedStatus::Aborted) => (
+                        RunState::Aborted.marker_color(),
+                        RunState::Aborted.marker_char(),
+                    ),
+                    (None, DerivedStatus::Pass) => (TestResult::Pass.marker_color(), 'p'),
+                    (None, DerivedStatus::Fail) => (TestResult::Fail.marker_color(), 'f'),
+                };
+                Text::new(format!(
+                    "{}{} {}{}{}{}",
+                    indent,
+                    marker_char,
+                    mark,
+                    relative_name(&package.name, module_prefix),
+                    crate::attempt_suffix(package.attempt),
+                    collapsed_suffix
+                ))
+                .color_range(marker_color, indent.len()..indent.len() + 1)
+                .color_range(
+                    theme::current().emphasis,
+                    indent.len() + 2..indent.len() + 2 + mark_width,
+                )
+            }
+            ListItem::TestCase {
+                test_case,
+                is_last,
+                collapsed,
+                depth,
+                ..
+            } => {
+                let (is_self_last, ancestors_last) = is_last.split_last().unwrap_or((&true, &[]));
+                let mut prefix = "  ".repeat(*depth);
+                prefix.extend(
+                    ancestors_last
+                        .iter()
+                        .map(|is_last| if *is_last { "  " } else { "│ " }),
                 );
+                prefix.push(if *is_self_last { '└' } else { '├' });
+                prefix.push(' ');
+                let marker_index = prefix.chars().count();
+                // Subtests share the kind of their top level test, only that one gets a badge
+                let name = match test_case.kind().badge() {
+                    Some(badge) if ancestors_last.is_empty() => {
+                        Cow::Owned(format!("{} [{}]", test_case.name, badge))
+                    }
+                    _ if ancestors_last.is_empty() => Cow::Borrowed(test_case.name.as_str()),
+                    _ => Cow::Borrowed(test_case.short_name()),
+                };
+                let collapsed_suffix = if *collapsed {
+                    format!(" (+{})", test_case.subtest_count())
+                } else {
+                    String::new()
+                };
+                let marker_color = test_case.marker_color();
+                let marker_char = if test_case.is_running() {
+                    spinner
+                } else {
+                    test_case.marker_char()
+                };
+                Text::new(format!(
+                    "{}{} {}{}{}{}",
+                    prefix,
+                    marker_char,
+                    mark,
+                    name,
+                    crate::attempt_suffix(test_case.attempt),
+                    collapsed_suffix
+                ))
+                .color_range(marker_color, marker_index..marker_index + 1)
+                .color_range(
+                    theme::current().emphasis,
+                    marker_index + 2..marker_index + 2 + mark_width,
+                )
+            }
+        }
+    }
+
+    /// A cell of one of the columns besides the package tree, a space when the column doesn't
+    /// apply to the item as empty cells collapse the row
+    fn render_column(
+        &self,
+        column: Column,
+        coverage_thresholds: Option<CoverageThresholds>,
+    ) -> Text {
+        match (column, self) {
+            (Column::Result, ListItem::Package { package, .. }) => {
+                match package.result_or_derived() {
+                    Some(test_result) => {
+                        Text::new(test_result.as_ref()).color_range(test_result.marker_color(), ..)
+                    }
+                    None if package.is_aborted => Text::new("aborted"),
+                    None => Text::new("running").color_range(RunState::Running.marker_color(), ..),
+                }
+            }
+            (Column::Result, ListItem::TestCase { test_case, .. }) => match test_case.result {
+                Some(test_result) => {
+                    Text::new(test_result.as_ref()).color_range(test_result.marker_color(), ..)
+                }
+                None => {
+                    Text::new(test_case.state.as_ref()).color_range(test_case.marker_color(), ..)
+                }
+            },
+            (Column::Test, ListItem::Package { package, .. }) => Text::new(format!(
+                "{} tests, {} failed",
+                package.summary.tests, package.summary.failed
+            )),
+            (Column::Test, ListItem::TestCase { test_case, .. }) => Text::new(format!(
+                "{}{}",
+                test_case.name,
+                crate::attempt_suffix(test_case.attempt)
+            )),
+            // The package's own time is followed by the time spent in its tests
+            (Column::Elapsed, ListItem::Package { package, .. }) => {
+                Text::new(match (package.elapsed, package.test_time()) {
+                    (Some(elapsed), Some(test_time)) => {
+                        format!("{} Σ{}", format_elapsed(elapsed), format_elapsed(test_time))
+                    }
+                    (Some(elapsed), None) => format_elapsed(elapsed),
+                    (None, Some(test_time)) => format!("Σ{}", format_elapsed(test_time)),
+                    (None, None) => " ".to_owned(),
+                })
+            }
+            (Column::Elapsed, ListItem::TestCase { test_case, .. }) => test_case
+                .elapsed
+                .map(|elapsed| Text::new(format_elapsed(elapsed)))
+                .unwrap_or(Text::new(" ")),
+            (Column::OutputLines, ListItem::Group { packages, .. }) => Text::new(
+                packages
+                    .iter()
+                    .map(|package| package.log_lines())
+                    .sum::<usize>()
+                    .to_string(),
+            ),
+            (Column::OutputLines, ListItem::Package { package, .. }) => {
+                Text::new(package.log_lines().to_string())
+            }
+            (Column::OutputLines, ListItem::TestCase { test_case, .. }) => {
+                Text::new(test_case.log_lines().to_string())
+            }
+            (Column::Coverage, ListItem::Package { package, .. }) => {
+                package.coverage.map_or(Text::new(" "), |coverage| {
+                    let text = Text::new(format!("{:.1}%", coverage));
+                    match coverage_thresholds {
+                        Some(thresholds) => text.color_range(thresholds.color(coverage), ..),
+                        None => text,
+                    }
+                })
+            }
+            (Column::FailureMessage, ListItem::Package { package, .. }) => {
+                match (package.result, package.derived_status()) {
+                    _ if package.fuzz_progress.is_some() => {
+                        render_fuzz_progress(package.fuzz_progress.as_ref())
+                    }
+                    (Some(TestResult::Timeout), _) => {
+                        package.timeout.as_ref().map_or(Text::new(" "), |timeout| {
+                            Text::new(timeout).color_range(TestResult::Timeout.marker_color(), ..)
+                        })
+                    }
+                    (None, DerivedStatus::Aborted) => Text::new("aborted"),
+                    (None, DerivedStatus::Pass | DerivedStatus::Fail) => {
+                        Text::new("no package result, derived from its tests")
+                    }
+                    _ => Text::new(" "),
+                }
+            }
+            (Column::FailureMessage, ListItem::TestCase { test_case, .. }) => {
+                let is_failure = test_case.result.is_some_and(|result| result.is_failure());
+                let details = match (&test_case.failure_message, &test_case.skip_reason) {
+                    (Some(message), _) if is_failure => {
+                        Some((message.clone(), TestResult::Fail.marker_color()))
+                    }
+                    (_, Some(reason)) if test_case.result == Some(TestResult::Skip) => {
+                        Some((reason.clone(), TestResult::Skip.marker_color()))
+                    }
+                    _ if test_case.is_flaky() => {
+                        let retries = test_case.earlier_results.len();
+                        let retries = match retries {
+                            1 => "1 retry".to_owned(),
+                            retries => format!("{} retries", retries),
+                        };
+                        Some((
+                            format!("flaky pass ({})", retries),
+                            TestResult::Skip.marker_color(),
+                        ))
+                    }
+                    _ => None,
+                };
+                // The failing input of a fuzz target is what's needed to reproduce the failure, so
+                // it goes first before long messages get cut off
+                let details = match (details, &test_case.fuzz_input) {
+                    (Some((message, color)), Some(fuzz_input)) if is_failure => {
+                        Some((format!("input: {}, {}", fuzz_input, message), color))
+                    }
+                    (None, Some(fuzz_input)) if is_failure => Some((
+                        format!("input: {}", fuzz_input),
+                        TestResult::Fail.marker_color(),
+                    )),
+                    (details, _) => details,
+                };
+                details.map_or(Text::new(" "), |(details, color)| {
+                    Text::new(truncate(&details, MAX_DETAILS_WIDTH)).color_range(color, ..)
+                })
+            }
+            _ => Text::new(" "),
+        }
+    }
+}
+
+fn render_status_bar(
+    status_bar: &StatusBar,
+    progress: Option<(usize, usize)>,
+    selected_path: Option<&str>,
+    pending_count: Option<&str>,
+) -> Text {
+    let theme = theme::current();
+    let summary = &status_bar.summary;
+    let counts = [
+        (summary.passed, "passed", TestResult::Pass),
+        (summary.failed, "failed", TestResult::Fail),
+        (summary.skipped, "skipped", TestResult::Skip),
+    ];
+    let mut text = String::new();
+    let mut color_ranges = Vec::new();
+    for (count, label, test_result) in counts {
+        if !text.is_empty() {
+            text.push_str(", ");
+        }
+        let start = text.chars().count();
+        text.push_str(&count.to_string());
+        color_ranges.push((test_result.marker_color(), start..text.chars().count()));
+        text.push(' ');
+        text.push_str(label);
+    }
+    text.push_str(" — ");
+    match summary.wall_clock() {
+        // Packages run in parallel, so the wall-clock time is the one waited for
+        Some(wall_clock) => text.push_str(&format!(
+            "{} wall clock, {} summed",
+            format_elapsed(wall_clock),
+            format_elapsed(summary.elapsed)
+        )),
+        None => text.push_str(&format_elapsed(summary.elapsed)),
+    }
+    if let Some((complete, total)) = progress {
+        text.push_str(&format!(
+            " — {} {}/{} packages complete",
+            status_bar.spinner, complete, total
+        ));
+    }
+    if status_bar.is_watching {
+        let start = text.chars().count() + 1;
+        text.push_str(" [watching]");
+        color_ranges.push((theme.emphasis, start..text.chars().count()));
+    }
+    if status_bar.error_count > 0 {
+        let start = text.chars().count() + 1;
+        text.push_str(&format!(" [E] {} errors", status_bar.error_count));
+        color_ranges.push((theme.fail, start..text.chars().count()));
+    }
+    if status_bar.streams.len() > 1 {
+        text.push_str(" — [Tab]");
+        for (index, stream) in status_bar.streams.iter().enumerate() {
+            text.push(' ');
+            let start = text.chars().count();
+            text.push_str(stream);
+            if index == status_bar.active_stream {
+                color_ranges.push((theme.emphasis, start..text.chars().count()));
+            }
+        }
+    }
+    if status_bar.projects.len() > 1 {
+        text.push_str(" — [P]");
+        for (index, project) in status_bar.projects.iter().enumerate() {
+            text.push(' ');
+            let start = text.chars().count();
+            text.push_str(project);
+            if index == status_bar.active_project {
+                color_ranges.push((theme.emphasis, start..text.chars().count()));
             }
         }
-        row
+    }
+    if let Some(notice) = status_bar.notice {
+        text.push_str(" — ");
+        text.push_str(notice);
+    }
+    if let Some(selected_path) = selected_path {
+        text.push_str(" — ");
+        text.push_str(selected_path);
+    }
+    if let Some(pending_count) = pending_count {
+        let start = text.chars().count() + 3;
+        text.push_str(" — ");
+        text.push_str(pending_count);
+        color_ranges.push((theme.accent, start..text.chars().count()));
+    }
+    color_ranges
+        .into_iter()
+        .fold(Text::new(text), |text, (color, range)| {
+            text.color_range(color, range)
+        })
+}
+
+/// Labels of the filter ribbons fitting in `width` columns. The counts are dropped first, then the
+/// names down to the keys, e.g. `[2]`, and the ribbons whose keys don't fit either are left out.
+fn fit_ribbons(ribbons: &[(&str, bool, usize)], width: usize) -> Vec<String> {
+    let with_counts = ribbons
+        .iter()
+        .map(|(label, _, count)| format!("{} ({})", label, count))
+        .collect::<Vec<_>>();
+    let without_counts = ribbons
+        .iter()
+        .map(|(label, ..)| label.to_string())
+        .collect();
+    let keys = ribbons
+        .iter()
+        .map(|(label, ..)| {
+            label
+                .split_once(' ')
+                .map_or(*label, |(key, _)| key)
+                .to_owned()
+        })
+        .collect::<Vec<_>>();
+    let ribbons_width = |labels: &[String]| -> usize {
+        labels
+            .iter()
+            .map(|label| label.chars().count() + RIBBON_PADDING)
+            .sum()
+    };
+    for labels in [with_counts, without_counts] {
+        if ribbons_width(&labels) <= width {
+            return labels;
+        }
+    }
+    let mut x = 0;
+    keys.into_iter()
+        .take_while(|key| {
+            x += key.chars().count() + RIBBON_PADDING;
+            x <= width
+        })
+        .collect()
+}
+
+fn truncate(text: &str, max_width: usize) -> String {
+    if text.chars().count() <= max_width {
+        text.to_owned()
+    } else {
+        let mut truncated: String = text.chars().take(max_width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Keeps the end of a text that doesn't fit
+fn truncate_start(text: &str, max_width: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_width {
+        text.to_owned()
+    } else {
+        let mut truncated = "…".to_owned();
+        truncated.extend(text.chars().skip(count + 1 - max_width));
+        truncated
+    }
+}
+
+/// Formats a threshold as configured when it's whole seconds, e.g. `1s` instead of `1.00s`
+fn format_threshold(threshold: f64) -> String {
+    if threshold >= 1.0 && threshold.fract() == 0.0 {
+        format!("{}s", threshold)
+    } else {
+        format_elapsed(threshold)
+    }
+}
+
+pub(crate) fn format_elapsed(elapsed: f64) -> String {
+    if elapsed < 1.0 {
+        format!("{}ms", (elapsed * 1000.0).round() as u64)
+    } else {
+        format!("{:.2}s", elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unknown_columns_and_keeps_the_known_ones_in_their_order() {
+        let (column_spec, errors) = ColumnSpec::from_names("elapsed, name, package, elapsed");
+        assert_eq!(column_spec.0, [Column::Elapsed, Column::Package]);
+        assert_eq!(
+            errors,
+            ["Invalid `columns` = \"elapsed, name, package, elapsed\": unknown column `name`"]
+        );
+    }
+
+    #[test]
+    fn keeps_the_default_columns_without_one_besides_the_output_lines() {
+        let (column_spec, errors) = ColumnSpec::from_names("output-lines, details");
+        assert_eq!(column_spec, ColumnSpec::default());
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn shows_the_output_lines_after_the_elapsed_time_unless_placed_elsewhere() {
+        let (column_spec, _) = ColumnSpec::from_names("failure-message, package");
+        assert_eq!(
+            column_spec.visible(true),
+            [Column::FailureMessage, Column::Package, Column::OutputLines]
+        );
+        let (column_spec, _) = ColumnSpec::from_names("output-lines, test, elapsed");
+        assert_eq!(
+            column_spec.visible(true),
+            [Column::OutputLines, Column::Test, Column::Elapsed]
+        );
+        assert_eq!(column_spec.visible(false), [Column::Test, Column::Elapsed]);
     }
 }
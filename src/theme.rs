@@ -0,0 +1,237 @@
+use std::{cell::Cell, collections::BTreeMap};
+
+use zellij_tile::prelude::*;
+
+thread_local! {
+    static THEME: Cell<Theme> = Cell::new(Theme::default());
+}
+
+/// The theme derived from the session's palette and the configuration, used by the render code
+/// of all screens
+pub(crate) fn current() -> Theme {
+    THEME.with(Cell::get)
+}
+
+pub(crate) fn set(theme: Theme) {
+    THEME.with(|cell| cell.set(theme));
+}
+
+/// How the selected row stands out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Selection {
+    /// zellij's selection background
+    Background,
+    /// Colors the row's text instead
+    Color(usize),
+}
+
+/// Colors are indices into the four highlight colors zellij's ui components support, which are
+/// the orange, cyan, green and magenta of the session's palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Theme {
+    pub(crate) pass: usize,
+    pub(crate) fail: usize,
+    pub(crate) skip: usize,
+    pub(crate) running: usize,
+    /// Build failures, data races and other states that need attention
+    pub(crate) emphasis: usize,
+    /// Counts and headings
+    pub(crate) accent: usize,
+    pub(crate) search_match: usize,
+    pub(crate) current_match: usize,
+    pub(crate) selection: Selection,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            pass: 2,
+            fail: 0,
+            skip: 1,
+            running: 1,
+            emphasis: 3,
+            accent: 0,
+            search_match: 3,
+            current_match: 0,
+            selection: Selection::Background,
+        }
+    }
+}
+
+impl Theme {
+    /// Picks the highlight colors closest to red for failures, green for passes and magenta for
+    /// emphasis, so markers look right in light and dark themes alike. Skips get the remaining
+    /// color.
+    pub(crate) fn from_palette(palette: &Palette) -> Self {
+        let highlights = [palette.orange, palette.cyan, palette.green, palette.magenta].map(rgb);
+        if highlights
+            .iter()
+            .all(|highlight| *highlight == highlights[0])
+        {
+            return Self::default();
+        }
+        let mut available = vec![0, 1, 2, 3];
+        let mut closest = |target: PaletteColor| {
+            let target = rgb(target);
+            let (position, _) = available
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, index)| distance(highlights[**index], target))
+                .expect("Fewer roles than highlight colors are assigned");
+            available.remove(position)
+        };
+        let fail = closest(palette.red);
+        let pass = closest(palette.green);
+        let emphasis = closest(palette.magenta);
+        let skip = closest(palette.yellow);
+        Self {
+            pass,
+            fail,
+            skip,
+            running: skip,
+            emphasis,
+            accent: fail,
+            search_match: emphasis,
+            current_match: fail,
+            selection: Selection::Background,
+        }
+    }
+
+    /// Styles a cell of the selected row
+    pub(crate) fn select(&self, text: Text) -> Text {
+        match self.selection {
+            Selection::Background => text.selected(),
+            Selection::Color(color) => text.color_range(color, ..),
+        }
+    }
+}
+
+/// Colors set through `color_<role>` entries holding an index or a palette name, e.g.
+/// `color_fail = "magenta"`. They take precedence over the colors picked from the palette.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ThemeConfig {
+    pass: Option<usize>,
+    fail: Option<usize>,
+    skip: Option<usize>,
+    running: Option<usize>,
+    emphasis: Option<usize>,
+    accent: Option<usize>,
+    search_match: Option<usize>,
+    current_match: Option<usize>,
+    /// `background` or a color
+    selection: Option<Selection>,
+}
+
+impl ThemeConfig {
+    /// Reads the `color_<role>` configuration entries. Invalid entries are ignored and reported in
+    /// the returned errors.
+    pub(crate) fn new(configuration: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut color = |role: &str| {
+            let config_key = format!("color_{}", role);
+            let value = configuration.get(&config_key)?;
+            let color = parse_color(value);
+            if color.is_none() {
+                errors.push(format!("Invalid `{}` = \"{}\"", config_key, value));
+            }
+            color
+        };
+        let mut theme_config = Self {
+            pass: color("pass"),
+            fail: color("fail"),
+            skip: color("skip"),
+            running: color("running"),
+            emphasis: color("emphasis"),
+            accent: color("accent"),
+            search_match: color("search_match"),
+            current_match: color("current_match"),
+            selection: None,
+        };
+        if let Some(value) = configuration.get("color_selection") {
+            theme_config.selection = match value.as_str() {
+                "background" => Some(Selection::Background),
+                value => {
+                    let color = parse_color(value).map(Selection::Color);
+                    if color.is_none() {
+                        errors.push(format!("Invalid `color_selection` = \"{}\"", value));
+                    }
+                    color
+                }
+            };
+        }
+        (theme_config, errors)
+    }
+
+    /// Overrides the colors of a theme with the configured ones
+    pub(crate) fn apply(&self, theme: Theme) -> Theme {
+        Theme {
+            pass: self.pass.unwrap_or(theme.pass),
+            fail: self.fail.unwrap_or(theme.fail),
+            skip: self.skip.unwrap_or(theme.skip),
+            running: self.running.unwrap_or(theme.running),
+            emphasis: self.emphasis.unwrap_or(theme.emphasis),
+            accent: self.accent.unwrap_or(theme.accent),
+            search_match: self.search_match.unwrap_or(theme.search_match),
+            current_match: self.current_match.unwrap_or(theme.current_match),
+            selection: self.selection.unwrap_or(theme.selection),
+        }
+    }
+}
+
+/// Parses an index from 0 to 3, or the name of the palette color zellij shows for it
+fn parse_color(value: &str) -> Option<usize> {
+    match value.trim() {
+        "orange" => Some(0),
+        "cyan" => Some(1),
+        "green" => Some(2),
+        "magenta" => Some(3),
+        value => value.parse().ok().filter(|index| *index <= 3),
+    }
+}
+
+fn rgb(color: PaletteColor) -> (u8, u8, u8) {
+    match color {
+        PaletteColor::Rgb(rgb) => rgb,
+        PaletteColor::EightBit(index) => eight_bit_rgb(index),
+    }
+}
+
+/// Approximates the xterm colors of a 256 color palette index
+fn eight_bit_rgb(index: u8) -> (u8, u8, u8) {
+    const BASIC_COLORS: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    match index {
+        0..=15 => BASIC_COLORS[usize::from(index)],
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// Squared euclidean distance between two colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
@@ -0,0 +1,4 @@
+use std::time::Duration;
+
+/// Timers may fire slightly before the requested time, so they count as due this much earlier
+pub(crate) const TIMER_TOLERANCE: Duration = Duration::from_millis(10);
@@ -0,0 +1,60 @@
+/// Parses an RFC 3339 timestamp as emitted by `test2json`, e.g.
+/// `2024-05-01T12:34:56.789012+02:00`, into seconds since the Unix epoch
+pub(crate) fn parse(timestamp: &str) -> Option<f64> {
+    let (date, time) = timestamp.split_once(['T', 't', ' '])?;
+    let mut date_fields = date.splitn(3, '-');
+    let year: i64 = date_fields.next()?.parse().ok()?;
+    let month: i64 = date_fields.next()?.parse().ok()?;
+    let day: i64 = date_fields.next()?.parse().ok()?;
+
+    let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(index) => time.split_at(index),
+        None => (time, ""),
+    };
+    let mut time_fields = time.splitn(3, ':');
+    let hours: f64 = time_fields.next()?.parse().ok()?;
+    let minutes: f64 = time_fields.next()?.parse().ok()?;
+    let seconds: f64 = time_fields.next()?.parse().ok()?;
+    let offset = parse_offset(offset)?;
+
+    let days = days_from_civil(year, month, day) as f64;
+    Some(days * 86_400.0 + hours * 3_600.0 + minutes * 60.0 + seconds - offset)
+}
+
+/// Time of day in UTC of a timestamp, e.g. `12:34:56.789`
+pub(crate) fn format_time_of_day(timestamp: f64) -> String {
+    let millis = (timestamp.rem_euclid(86_400.0) * 1000.0) as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Offset from UTC in seconds, e.g. `+02:00`, `Z` or none at all
+fn parse_offset(offset: &str) -> Option<f64> {
+    let (sign, offset) = match offset.split_at_checked(1) {
+        None => return Some(0.0),
+        Some(("Z" | "z", "")) => return Some(0.0),
+        Some(("+", offset)) => (1.0, offset),
+        Some(("-", offset)) => (-1.0, offset),
+        Some(_) => return None,
+    };
+    let (hours, minutes) = offset.split_once(':')?;
+    let hours: f64 = hours.parse().ok()?;
+    let minutes: f64 = minutes.parse().ok()?;
+    Some(sign * (hours * 3_600.0 + minutes * 60.0))
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
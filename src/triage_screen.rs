@@ -0,0 +1,227 @@
+use strum::EnumIter;
+use zellij_tile::prelude::*;
+
+use crate::{
+    keymap::ScreenAction, name::Name, source_location::SourceLocation, theme, viewport::Viewport,
+    Package, TestCase, TestResult,
+};
+
+#[derive(Debug)]
+pub(crate) enum UpdateCommand {
+    ExitScreen,
+    Render,
+    /// Opens the log of a failed package, or of one of its failed tests
+    OpenLogs {
+        package: Name,
+        test: Option<Name>,
+        attempt: usize,
+    },
+    OpenSource {
+        package: Name,
+        location: SourceLocation,
+    },
+    Notice(String),
+}
+
+/// Keys of the triage screen
+#[derive(Debug, Clone, Copy, EnumIter)]
+pub(crate) enum TriageAction {
+    Exit,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    OpenLogs,
+    OpenSource,
+}
+
+impl ScreenAction for TriageAction {
+    const SCREEN: &'static str = "Triage";
+
+    fn description(&self) -> &'static str {
+        match self {
+            TriageAction::Exit => "back to the tests",
+            TriageAction::Down => "select the next failure",
+            TriageAction::Up => "select the previous failure",
+            TriageAction::PageDown => "move the selection down a page",
+            TriageAction::PageUp => "move the selection up a page",
+            TriageAction::OpenLogs => "open the logs of the failure",
+            TriageAction::OpenSource => "open the file:line the failure's log points to",
+        }
+    }
+
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            TriageAction::Exit => &["Esc"],
+            TriageAction::Down => &["Down", "j"],
+            TriageAction::Up => &["Up", "k"],
+            TriageAction::PageDown => &["PageDown", "f"],
+            TriageAction::PageUp => &["PageUp", "b"],
+            TriageAction::OpenLogs => &["Enter"],
+            TriageAction::OpenSource => &["o"],
+        }
+    }
+}
+
+/// A row of the screen: a failed test, or a package that failed without a failed test, e.g. a
+/// build failure
+#[derive(Debug)]
+struct Failure<'a> {
+    package: &'a Package,
+    test: Option<&'a TestCase>,
+    result: TestResult,
+    message: Option<String>,
+    location: Option<SourceLocation>,
+}
+
+/// Lists one failure per row with its message and `file:line`, for going through the failures of
+/// a run one by one. Parents failing only because of a subtest are left out.
+#[derive(Debug, Default)]
+pub(crate) struct TriageScreen {
+    viewport: Viewport,
+}
+
+impl TriageScreen {
+    pub(crate) fn update(&mut self, event: Event, packages: &[Package]) -> Option<UpdateCommand> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let failures = failures(packages);
+        match TriageAction::from_key(&key)? {
+            TriageAction::Exit => Some(UpdateCommand::ExitScreen),
+            TriageAction::Down => {
+                self.viewport.select_next(1, failures.len());
+                Some(UpdateCommand::Render)
+            }
+            TriageAction::Up => {
+                self.viewport.select_previous(1);
+                Some(UpdateCommand::Render)
+            }
+            TriageAction::PageDown => {
+                self.viewport
+                    .select_next(self.viewport.page(), failures.len());
+                Some(UpdateCommand::Render)
+            }
+            TriageAction::PageUp => {
+                self.viewport.select_previous(self.viewport.page());
+                Some(UpdateCommand::Render)
+            }
+            TriageAction::OpenLogs => {
+                failures
+                    .get(self.viewport.selected)
+                    .map(|failure| UpdateCommand::OpenLogs {
+                        package: failure.package.name.clone(),
+                        test: failure.test.map(|test| test.name.clone()),
+                        attempt: failure.test.map_or(0, |test| test.attempt),
+                    })
+            }
+            TriageAction::OpenSource => {
+                failures
+                    .get(self.viewport.selected)
+                    .map(|failure| match &failure.location {
+                        Some(location) => UpdateCommand::OpenSource {
+                            package: failure.package.name.clone(),
+                            location: location.clone(),
+                        },
+                        None => UpdateCommand::Notice(
+                            "No file:line reference found in the log".to_owned(),
+                        ),
+                    })
+            }
+        }
+    }
+
+    pub(crate) fn render(&mut self, rows: usize, cols: usize, packages: &[Package]) {
+        let theme = theme::current();
+        let failures = failures(packages);
+        self.viewport.resize(cols, rows.saturating_sub(2));
+        self.viewport.follow_selection(failures.len());
+
+        let table = failures
+            .iter()
+            .enumerate()
+            .skip(self.viewport.scroll_y)
+            .take(self.viewport.height())
+            .fold(
+                Table::new().add_row(vec!["failure", "at", "message"]),
+                |acc, (i, failure)| {
+                    let marker_char = failure.result.marker_char();
+                    let name = match failure.test {
+                        Some(test) => {
+                            format!("{} {} {}", marker_char, failure.package.name, test.name)
+                        }
+                        None => format!("{} {}", marker_char, failure.package.name),
+                    };
+                    let row = vec![
+                        Text::new(name).color_range(failure.result.marker_color(), ..1),
+                        Text::new(
+                            failure
+                                .location
+                                .as_ref()
+                                .map_or(" ".to_owned(), |location| {
+                                    format!("{}:{}", location.file, location.line)
+                                }),
+                        ),
+                        Text::new(failure.message.as_deref().unwrap_or(" "))
+                            .color_range(theme.fail, ..),
+                    ];
+                    if i == self.viewport.selected {
+                        acc.add_styled_row(
+                            row.into_iter().map(|column| theme.select(column)).collect(),
+                        )
+                    } else {
+                        acc.add_styled_row(row)
+                    }
+                },
+            );
+        print_table_with_coordinates(table, 0, 0, Some(cols), Some(rows.saturating_sub(1)));
+
+        let count = failures.len().to_string();
+        let bottom_text = Text::new(format!(
+            "{} failures — [Enter] logs, [o] open file, [Esc] back",
+            count
+        ))
+        .color_range(theme.fail, ..count.len());
+        print_text_with_coordinates(bottom_text, 0, rows.saturating_sub(1), Some(cols), Some(1));
+    }
+}
+
+fn failures(packages: &[Package]) -> Vec<Failure<'_>> {
+    let mut failures = Vec::new();
+    for package in packages {
+        let failed_tests = TestCase::flatten(&package.tests)
+            .into_iter()
+            .filter(|test| is_failed(test.result) && !test.subtests.iter().any(has_failure))
+            .collect::<Vec<_>>();
+        if failed_tests.is_empty() {
+            if let Some(result) = package.result.filter(TestResult::is_failure) {
+                failures.push(Failure {
+                    package,
+                    test: None,
+                    result,
+                    message: package
+                        .timeout
+                        .clone()
+                        .or_else(|| crate::failure_message(&package.log)),
+                    location: SourceLocation::find_in_log(&package.log),
+                });
+            }
+        }
+        failures.extend(failed_tests.into_iter().map(|test| Failure {
+            package,
+            test: Some(test),
+            result: test.result.unwrap_or(TestResult::Fail),
+            message: test.failure_message.clone(),
+            location: SourceLocation::find_in_log(&test.log),
+        }));
+    }
+    failures
+}
+
+fn is_failed(result: Option<TestResult>) -> bool {
+    result.is_some_and(|result| result.is_failure())
+}
+
+fn has_failure(test: &TestCase) -> bool {
+    is_failed(test.result) || test.subtests.iter().any(has_failure)
+}
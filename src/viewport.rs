@@ -0,0 +1,107 @@
+use std::ops::Range;
+
+/// The selection and scroll offsets of a list shown in a limited number of rows. Screens resize
+/// it on every render, so moving the selection by pages and clamping work the same everywhere.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Viewport {
+    /// Index of the selected item, screens without a selection leave it at 0
+    pub(crate) selected: usize,
+    /// Index of the first item shown
+    pub(crate) scroll_y: usize,
+    /// Columns or chars scrolled to the right
+    pub(crate) scroll_x: usize,
+    /// Columns and items shown at once, `None` before the first render
+    size: Option<(usize, usize)>,
+}
+
+impl Viewport {
+    /// Sets the size of the area the items are shown in
+    pub(crate) fn resize(&mut self, width: usize, height: usize) {
+        self.size = Some((width, height));
+    }
+
+    pub(crate) fn width(&self) -> usize {
+        self.size.map_or(0, |(width, _)| width)
+    }
+
+    /// Items shown at once, at least one so the selection stays visible in tiny panes
+    pub(crate) fn height(&self) -> usize {
+        self.size.map_or(0, |(_, height)| height.max(1))
+    }
+
+    /// Items moved by a page, none before the first render
+    pub(crate) fn page(&self) -> usize {
+        self.size.map_or(0, |(_, height)| height)
+    }
+
+    pub(crate) fn half_page(&self) -> usize {
+        self.page() / 2
+    }
+
+    pub(crate) fn select_next(&mut self, count: usize, len: usize) {
+        self.selected = self
+            .selected
+            .saturating_add(count)
+            .min(len.saturating_sub(1));
+    }
+
+    pub(crate) fn select_previous(&mut self, count: usize) {
+        self.selected = self.selected.saturating_sub(count);
+    }
+
+    /// Selects an item, or the last one if the index is past the end
+    pub(crate) fn select(&mut self, index: usize, len: usize) {
+        self.selected = index.min(len.saturating_sub(1));
+    }
+
+    /// Keeps the selection within the items, e.g. after some got filtered out
+    pub(crate) fn clamp_selection(&mut self, len: usize) {
+        self.select(self.selected, len);
+    }
+
+    /// Clamps the selection and scrolls just enough to show it
+    pub(crate) fn follow_selection(&mut self, len: usize) {
+        self.clamp_selection(len);
+        let height = self.height();
+        if self.selected >= self.scroll_y + height {
+            self.scroll_y = self.selected + 1 - height;
+        } else if self.selected < self.scroll_y {
+            self.scroll_y = self.selected;
+        }
+    }
+
+    /// Scroll offset showing the last page of the items
+    pub(crate) fn max_scroll_y(&self, len: usize) -> usize {
+        len.saturating_sub(self.height())
+    }
+
+    pub(crate) fn scroll_down(&mut self, count: usize, len: usize) {
+        self.scroll_y = self
+            .scroll_y
+            .saturating_add(count)
+            .min(self.max_scroll_y(len));
+    }
+
+    pub(crate) fn scroll_up(&mut self, count: usize) {
+        self.scroll_y = self.scroll_y.saturating_sub(count);
+    }
+
+    /// Keeps the scroll offset within the items, e.g. after they got fewer
+    pub(crate) fn clamp_scroll_y(&mut self, len: usize) {
+        self.scroll_y = self.scroll_y.min(self.max_scroll_y(len));
+    }
+
+    pub(crate) fn scroll_right(&mut self, count: usize, max: usize) {
+        self.scroll_x = self.scroll_x.saturating_add(count).min(max);
+    }
+
+    pub(crate) fn scroll_left(&mut self, count: usize) {
+        self.scroll_x = self.scroll_x.saturating_sub(count);
+    }
+
+    /// Indices of the items shown
+    pub(crate) fn visible_range(&self, len: usize) -> Range<usize> {
+        let start = self.scroll_y.min(len);
+        start..start.saturating_add(self.height()).min(len)
+    }
+}
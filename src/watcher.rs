@@ -0,0 +1,91 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use zellij_tile::prelude::*;
+
+use crate::{runner, timer::TIMER_TOLERANCE};
+
+/// Time to wait after the last file change before running the tests
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Re-runs the tests when `.go` files change in zellij's working directory
+#[derive(Debug, Default)]
+pub(crate) struct Watcher {
+    enabled: bool,
+    is_watching_filesystem: bool,
+    last_change: Option<Instant>,
+    is_running: bool,
+    is_queued: bool,
+}
+
+impl Watcher {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.last_change = None;
+        self.is_queued = false;
+        if enabled && !self.is_watching_filesystem {
+            watch_filesystem();
+            self.is_watching_filesystem = true;
+        }
+    }
+
+    pub(crate) fn on_files_changed(&mut self, paths: &[(PathBuf, Option<FileMetadata>)]) {
+        if !self.enabled
+            || !paths
+                .iter()
+                .any(|(path, _)| path.extension().is_some_and(|extension| extension == "go"))
+        {
+            return;
+        }
+        self.last_change = Some(Instant::now());
+        set_timeout(DEBOUNCE.as_secs_f64());
+    }
+
+    /// Starts a run once no file has changed for the debounce duration. Returns whether a run was
+    /// started.
+    pub(crate) fn on_timer(&mut self) -> bool {
+        match self.last_change {
+            Some(last_change) if last_change.elapsed() + TIMER_TOLERANCE >= DEBOUNCE => {
+                self.last_change = None;
+                if self.is_running {
+                    self.is_queued = true;
+                    false
+                } else {
+                    self.run();
+                    true
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Starts the queued run, if any, after the previous one finished. Returns whether a run was
+    /// started.
+    pub(crate) fn on_run_finished(&mut self) -> bool {
+        self.is_running = false;
+        if self.is_queued && self.enabled {
+            self.is_queued = false;
+            self.run();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Forgets the run in flight and any queued run, as the user stopped it on purpose
+    pub(crate) fn on_run_aborted(&mut self) {
+        self.is_running = false;
+        self.is_queued = false;
+    }
+
+    fn run(&mut self) {
+        self.is_running = true;
+        runner::run_all_tests();
+    }
+}